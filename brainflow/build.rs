@@ -127,6 +127,78 @@ fn generate_binding() {
     generate_constants_binding();
 }
 
+/// Write wrappers with the signatures of the `extern "C"` functions in `src/ffi/<module>.rs` which call the
+/// symbols of the library loaded at runtime, other items of the bindings are copied as they are.
+#[cfg(feature = "runtime_load")]
+fn generate_runtime_binding(out_path: &Path, module: &str, native_module: &str) {
+    let binding_path = PathBuf::new().join("src").join("ffi").join(format!("{}.rs", module));
+    let bindings = std::fs::read_to_string(&binding_path).expect("Could not read binding");
+
+    let extern_fn = regex::Regex::new(
+        r#"(?s)extern "C" \{\s*pub fn (\w+)\((.*?)\)\s*(?:->\s*([^;]+?))?\s*;\s*\}"#,
+    )
+    .unwrap();
+
+    let mut wrappers = String::new();
+    for captures in extern_fn.captures_iter(&bindings) {
+        let name = &captures[1];
+        let args = captures[2]
+            .split(',')
+            .map(str::trim)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| arg.split_once(':').expect("Unexpected argument in binding"))
+            .map(|(arg, ty)| (arg.trim(), ty.trim()))
+            .collect::<Vec<_>>();
+        let ret = captures.get(3).map(|r| r.as_str()).unwrap_or("()");
+        // a failed load is reported through the exit code
+        assert!(ret.ends_with("c_int"), "{} does not return an exit code", name);
+        let arg_list = args
+            .iter()
+            .map(|(arg, ty)| format!("{}: {}", arg, ty))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let arg_types = args.iter().map(|(_, ty)| *ty).collect::<Vec<_>>().join(", ");
+        let arg_names = args.iter().map(|(arg, _)| *arg).collect::<Vec<_>>().join(", ");
+        wrappers.push_str(&format!(
+            "pub unsafe fn {name}({arg_list}) -> {ret} {{\n    \
+                type Symbol = unsafe extern \"C\" fn({arg_types}) -> {ret};\n    \
+                static SYMBOL: ::std::sync::OnceLock<Symbol> = ::std::sync::OnceLock::new();\n    \
+                let symbol = match SYMBOL.get() {{\n        \
+                    Some(symbol) => *symbol,\n        \
+                    None => match crate::native_library::symbol::<Symbol>(\n            \
+                        crate::error::NativeModule::{native_module},\n            \
+                        b\"{name}\\0\",\n        \
+                    ) {{\n            \
+                        Ok(symbol) => *SYMBOL.get_or_init(|| symbol),\n            \
+                        Err(e) => return crate::native_library::not_loaded(e),\n        \
+                    }},\n    \
+                }};\n    \
+                symbol({arg_names})\n\
+            }}\n",
+            name = name,
+            arg_list = arg_list,
+            ret = ret,
+            arg_types = arg_types,
+            arg_names = arg_names,
+            native_module = native_module,
+        ));
+    }
+
+    // inner attributes are not allowed in included files
+    let other_items = extern_fn.replace_all(&bindings, "");
+    let other_items = other_items
+        .lines()
+        .filter(|line| !line.starts_with("#!"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    std::fs::write(
+        out_path.join(format!("{}_runtime.rs", module)),
+        format!("{}\n{}", other_items, wrappers),
+    )
+    .expect("Could not write runtime binding");
+}
+
 fn main() {
     #[cfg(feature = "generate_binding")]
     generate_binding();
@@ -142,8 +214,20 @@ fn main() {
     options.copy_inside = true;
     fs_extra::dir::copy(lib_path, lib_out_path, &options).unwrap();
 
+    // the search path also lets `cargo run` and `cargo test` find the libraries when they are loaded at runtime
     println!("cargo:rustc-link-search=native={}/lib", out_path.display());
-    println!("cargo:rustc-link-lib=dylib=BoardController");
-    println!("cargo:rustc-link-lib=dylib=DataHandler");
-    println!("cargo:rustc-link-lib=dylib=MLModule");
+
+    #[cfg(feature = "runtime_load")]
+    {
+        generate_runtime_binding(lib_out_path, "board_controller", "BoardController");
+        generate_runtime_binding(lib_out_path, "data_handler", "DataHandler");
+        generate_runtime_binding(lib_out_path, "ml_module", "MlModule");
+    }
+
+    #[cfg(not(feature = "runtime_load"))]
+    {
+        println!("cargo:rustc-link-lib=dylib=BoardController");
+        println!("cargo:rustc-link-lib=dylib=DataHandler");
+        println!("cargo:rustc-link-lib=dylib=MLModule");
+    }
 }
@@ -5,14 +5,16 @@ use brainflow::board_shim::BoardShim;
 use brainflow::brainflow_input_params::BrainFlowInputParamsBuilder;
 use brainflow::BoardIds;
 use brainflow::BrainFlowPresets;
+use brainflow::streamer::{FileMode, StreamerSpec};
 
 fn main() {
     brainflow::board_shim::enable_dev_board_logger().unwrap();
     let params = BrainFlowInputParamsBuilder::default().build();
     let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
 
+    let streamer = StreamerSpec::file("data.csv", FileMode::Write).unwrap();
     board.prepare_session().unwrap();
-    board.start_stream(45000, "file://data.csv:w").unwrap();
+    board.start_stream_with_spec(45000, &streamer).unwrap();
     thread::sleep(Duration::from_secs(5));
     board.insert_marker(1.0, BrainFlowPresets::DefaultPreset).unwrap();
     thread::sleep(Duration::from_secs(5));
@@ -0,0 +1,31 @@
+use std::{thread, time::Duration};
+
+use brainflow::{
+    board_shim::BoardShim, brainflow_input_params::BrainFlowInputParamsBuilder, multi_board, BoardIds,
+    BrainFlowPresets,
+};
+
+fn main() {
+    brainflow::board_shim::enable_dev_board_logger().unwrap();
+    // two synthetic boards are told apart by their other_info
+    let boards = ["first", "second"]
+        .iter()
+        .map(|info| {
+            let params = BrainFlowInputParamsBuilder::default().other_info(info).build();
+            BoardShim::new(BoardIds::SyntheticBoard, params).unwrap()
+        })
+        .collect();
+    let mut session = multi_board::MultiBoardSession::new(boards);
+
+    session.prepare_session().unwrap();
+    session.start_stream(45000, "").unwrap();
+    thread::sleep(Duration::from_secs(2));
+    session.insert_marker(1.0, BrainFlowPresets::DefaultPreset).unwrap();
+    thread::sleep(Duration::from_secs(2));
+    session.stop_stream().unwrap();
+    let chunks = session.get_board_data(BrainFlowPresets::DefaultPreset).unwrap();
+    session.release_session().unwrap();
+
+    let merged = multi_board::merge(&chunks, 250.0).unwrap();
+    println!("{} rows, {} samples", merged.data().nrows(), merged.data().ncols());
+}
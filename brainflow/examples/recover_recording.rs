@@ -0,0 +1,25 @@
+use std::{env, path::PathBuf};
+
+use brainflow::recorder;
+
+fn main() {
+    // salvage the complete chunks of recorder files into a csv file,
+    // e.g. `recover_recording data.csv session_0000.bfrec session_0001.bfrec`
+    let mut args = env::args().skip(1);
+    let output = args.next().expect("path of the csv file");
+    let files = args.map(PathBuf::from).collect::<Vec<_>>();
+
+    for file in &files {
+        let report = *recorder::read_file(file).unwrap().report();
+        println!(
+            "{}: {} chunks, {} samples, {} damaged and {} truncated bytes",
+            file.display(),
+            report.chunks,
+            report.samples,
+            report.damaged_bytes,
+            report.truncated_bytes
+        );
+    }
+    let report = recorder::export_csv(&files, &output).unwrap();
+    println!("wrote {} samples to {}", report.samples, output);
+}
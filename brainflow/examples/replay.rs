@@ -0,0 +1,26 @@
+use std::{env, thread, time::Duration};
+
+use brainflow::{board::Board, replay_board::ReplayBoardBuilder, BoardIds, BrainFlowPresets};
+
+fn main() {
+    // replay a file written by data_filter::write_file, e.g. `replay data.csv CytonBoard`
+    let mut args = env::args().skip(1);
+    let path = args.next().expect("path of the recording");
+    let board_id: BoardIds = args.next().as_deref().unwrap_or("SyntheticBoard").parse().unwrap();
+
+    let board = ReplayBoardBuilder::open_for_board(path, board_id)
+        .unwrap()
+        .speed(2.0)
+        .looping(true)
+        .build()
+        .unwrap();
+    board.prepare_session().unwrap();
+    board.start_stream(45000, "").unwrap();
+    for _ in 0..5 {
+        thread::sleep(Duration::from_secs(1));
+        let data = board.get_board_data(None, BrainFlowPresets::DefaultPreset).unwrap();
+        println!("{:.1} s: {} samples", board.position(), data.ncols());
+    }
+    board.stop_stream().unwrap();
+    board.release_session().unwrap();
+}
@@ -0,0 +1,28 @@
+use std::{env, thread, time::Duration};
+
+use brainflow::session_config::SessionConfig;
+
+const DEFAULT_CONFIG: &str = r#"
+board_id = "SyntheticBoard"
+buffer_size = 45000
+
+[log_levels]
+board_controller = "LevelTrace"
+"#;
+
+fn main() {
+    // pass the path of a .toml or .json config to use another board
+    let config = match env::args().nth(1) {
+        Some(path) => SessionConfig::load(path).unwrap(),
+        None => SessionConfig::from_toml_str(DEFAULT_CONFIG).unwrap(),
+    };
+    let board = config.open().unwrap();
+    config.start_stream(&board).unwrap();
+    thread::sleep(Duration::from_secs(5));
+    board.stop_stream().unwrap();
+    for preset in config.presets() {
+        let data = board.get_board_data(Some(10), *preset).unwrap();
+        println!("{:?}: {:?}", preset, data);
+    }
+    board.release_session().unwrap();
+}
@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use brainflow::{board_shim, brainflow_input_params::BrainFlowInputParamsBuilder, BoardIds, BrainFlowPresets};
+
+fn main() {
+    brainflow::board_shim::enable_dev_board_logger().unwrap();
+    let params = BrainFlowInputParamsBuilder::default().build();
+    let board = board_shim::BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+
+    let session = board.prepare().unwrap().start_stream(45000, "").unwrap();
+    let subscription = session
+        .subscribe(BrainFlowPresets::DefaultPreset, Duration::from_millis(100))
+        .unwrap();
+    for chunk in subscription.take(10) {
+        let chunk = chunk.unwrap();
+        println!("received {} samples", chunk.ncols());
+    }
+}
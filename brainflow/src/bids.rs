@@ -0,0 +1,371 @@
+use ndarray::Array2;
+use serde_json::{json, Map, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    board_description::BoardDescription,
+    board_shim,
+    brainvision::{BinaryFormat, BrainVisionHeader, BrainVisionWriter},
+    catalogue::Capability,
+    edf::{EdfFormat, EdfHeader, EdfWriter},
+    error::Error,
+    BoardIds, BrainFlowPresets, Result,
+};
+
+/// BIDS version of the written datasets.
+pub const BIDS_VERSION: &str = "1.9.0";
+
+/// Format of the signal file, one of the formats BIDS-EEG allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignalFormat {
+    Edf,
+    Bdf,
+    /// BrainVision files with IEEE float samples.
+    BrainVision,
+}
+
+/// Exports recordings to the `sub-<subject>/ses-<session>/eeg/` directory of a BIDS dataset.
+///
+/// Every export writes the signal file, `channels.tsv`, `events.tsv` with the markers and the
+/// `eeg.json` sidecar, and `dataset_description.json` if the dataset has none yet.
+/// All inputs are checked before the first file is written.
+#[derive(Debug, Clone)]
+pub struct BidsExporter {
+    root: PathBuf,
+    subject: String,
+    session: Option<String>,
+    task: String,
+    run: Option<u32>,
+    format: SignalFormat,
+    power_line_frequency: Option<f64>,
+    reference: String,
+    overwrite: bool,
+}
+
+impl BidsExporter {
+    /// Exporter into the dataset at `root` for a subject and task label, writing EDF files.
+    pub fn new<P: AsRef<Path>, S: Into<String>>(root: P, subject: S, task: S) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            subject: subject.into(),
+            session: None,
+            task: task.into(),
+            run: None,
+            format: SignalFormat::Edf,
+            power_line_frequency: None,
+            reference: "n/a".to_string(),
+            overwrite: false,
+        }
+    }
+
+    /// Session label, the files are written without a session directory if not set.
+    pub fn session<S: Into<String>>(mut self, session: S) -> Self {
+        self.session = Some(session.into());
+        self
+    }
+
+    /// Index of the run, for several recordings of the same task in one session.
+    pub fn run(mut self, run: u32) -> Self {
+        self.run = Some(run);
+        self
+    }
+
+    /// Format of the signal file, [SignalFormat::Edf] by default.
+    pub fn format(mut self, format: SignalFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Frequency of the power line in Hz, written as `n/a` if not set.
+    pub fn power_line_frequency(mut self, power_line_frequency: f64) -> Self {
+        self.power_line_frequency = Some(power_line_frequency);
+        self
+    }
+
+    /// Description of the EEG reference, e.g. `Cz` or `linked mastoids`, `n/a` by default.
+    pub fn reference<S: Into<String>>(mut self, reference: S) -> Self {
+        self.reference = reference.into();
+        self
+    }
+
+    /// Replace the files of an earlier export with the same labels instead of failing.
+    pub fn overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Directory of the EEG files, `sub-<subject>[/ses-<session>]/eeg` in the dataset.
+    pub fn eeg_dir(&self) -> PathBuf {
+        let mut dir = self.root.join(format!("sub-{}", self.subject));
+        if let Some(session) = &self.session {
+            dir.push(format!("ses-{}", session));
+        }
+        dir.join("eeg")
+    }
+
+    /// File name of the export with the given suffix and extension, e.g. `sub-01_ses-1_task-rest_eeg.edf`.
+    fn file_path(&self, suffix: &str, extension: &str) -> PathBuf {
+        let mut name = format!("sub-{}", self.subject);
+        if let Some(session) = &self.session {
+            name.push_str(&format!("_ses-{}", session));
+        }
+        name.push_str(&format!("_task-{}", self.task));
+        if let Some(run) = self.run {
+            name.push_str(&format!("_run-{}", run));
+        }
+        self.eeg_dir().join(format!("{}_{}.{}", name, suffix, extension))
+    }
+
+    /// All files written by an export, the signal file first.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = match self.format {
+            SignalFormat::Edf => vec![self.file_path("eeg", "edf")],
+            SignalFormat::Bdf => vec![self.file_path("eeg", "bdf")],
+            SignalFormat::BrainVision => vec![
+                self.file_path("eeg", "vhdr"),
+                self.file_path("eeg", "vmrk"),
+                self.file_path("eeg", "eeg"),
+            ],
+        };
+        files.push(self.file_path("channels", "tsv"));
+        files.push(self.file_path("events", "tsv"));
+        files.push(self.file_path("eeg", "json"));
+        files
+    }
+
+    /// Check labels, board data and existing files, reporting every problem at once.
+    pub fn validate(&self, data: &Array2<f64>, descr: &BoardDescription) -> Result<()> {
+        let mut errors = Vec::new();
+        let labels = [("subject", Some(&self.subject)), ("session", self.session.as_ref()), ("task", Some(&self.task))];
+        for (entity, label) in labels.iter() {
+            match label {
+                Some(label) if label.is_empty() || !label.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                    errors.push(format!("{} label `{}` is not alphanumeric", entity, label))
+                }
+                _ => (),
+            }
+        }
+        if *descr.sampling_rate() == 0 {
+            errors.push("sampling rate must not be 0".to_string());
+        }
+        if data.nrows() != *descr.num_rows() {
+            errors.push(format!("board data has {} rows instead of {}", data.nrows(), descr.num_rows()));
+        } else if data.ncols() == 0 {
+            errors.push("board data has no samples".to_string());
+        } else if let Err(e) = self.check_signal_header(data, descr) {
+            errors.push(e.to_string());
+        }
+        if let Some(frequency) = self.power_line_frequency.filter(|f| !(f.is_finite() && *f > 0.0)) {
+            errors.push(format!("power line frequency {} is not positive", frequency));
+        }
+        if !self.overwrite {
+            for file in self.files().iter().filter(|f| f.exists()) {
+                errors.push(format!("{} exists", file.display()));
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(Error::InvalidBids(errors.join("; "))),
+        }
+    }
+
+    fn check_signal_header(&self, data: &Array2<f64>, descr: &BoardDescription) -> Result<()> {
+        match self.format {
+            SignalFormat::Edf => self.edf_header(EdfFormat::Edf, data, descr, "").check(),
+            SignalFormat::Bdf => self.edf_header(EdfFormat::Bdf, data, descr, "").check(),
+            SignalFormat::BrainVision => BrainVisionHeader::from_description(BinaryFormat::IeeeFloat32, descr).check(),
+        }
+    }
+
+    fn edf_header(&self, format: EdfFormat, data: &Array2<f64>, descr: &BoardDescription, device: &str) -> EdfHeader {
+        let patient = format!("sub-{} X X X", self.subject);
+        EdfHeader::from_description(format, descr)
+            .fit_ranges(data)
+            .patient(patient)
+            .recording(device.replace(' ', "_"))
+    }
+
+    /// Export board data recorded from a board with the given preset, returning the written files.
+    ///
+    /// The device name and versions are taken from the native libraries.
+    pub fn export(&self, board_id: BoardIds, preset: BrainFlowPresets, data: &Array2<f64>) -> Result<Vec<PathBuf>> {
+        let descr = BoardDescription::load(board_id, preset)?;
+        let device_name = board_shim::get_device_name(board_id, preset)?;
+        let versions = format!("BrainFlow {}, brainflow crate {}", board_shim::get_version()?, env!("CARGO_PKG_VERSION"));
+        self.export_with_description(data, &descr, &device_name, &versions)
+    }
+
+    /// Export board data with the layout of `descr`, for boards without a native description.
+    pub fn export_with_description(
+        &self,
+        data: &Array2<f64>,
+        descr: &BoardDescription,
+        device_name: &str,
+        software_versions: &str,
+    ) -> Result<Vec<PathBuf>> {
+        self.validate(data, descr)?;
+        fs::create_dir_all(self.eeg_dir())?;
+
+        let files = self.files();
+        let names = match self.format {
+            SignalFormat::Edf | SignalFormat::Bdf => {
+                let format = if self.format == SignalFormat::Edf { EdfFormat::Edf } else { EdfFormat::Bdf };
+                let header = self.edf_header(format, data, descr, device_name);
+                let names = header.signals().iter().map(|s| s.label.clone()).collect::<Vec<_>>();
+                let mut writer = EdfWriter::create(&files[0], header)?;
+                writer.write(data)?;
+                writer.finish()?;
+                names
+            }
+            SignalFormat::BrainVision => {
+                let header = BrainVisionHeader::from_description(BinaryFormat::IeeeFloat32, descr);
+                let names = header.channels().iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+                let mut writer = BrainVisionWriter::create(&files[0], header)?;
+                writer.write(data)?;
+                writer.finish()?;
+                names
+            }
+        };
+
+        let rows = Capability::labeled_rows(descr);
+        let sampling_rate = *descr.sampling_rate();
+        let mut channels = String::from("name\ttype\tunits\tsampling_frequency\n");
+        for ((_, capability, _), name) in rows.iter().zip(&names) {
+            let unit = match capability.unit() {
+                "" => "n/a",
+                "uV" => "µV",
+                "uS" => "µS",
+                "uT" => "µT",
+                "degC" => "oC",
+                unit => unit,
+            };
+            channels.push_str(&format!("{}\t{}\t{}\t{}\n", name, channel_type(*capability), unit, sampling_rate));
+        }
+        let tsv = files.len() - 3;
+        fs::write(&files[tsv], channels)?;
+
+        let mut events = String::from("onset\tduration\tsample\tvalue\n");
+        if let Some(marker_channel) = descr.marker_channel() {
+            let markers = data.row(*marker_channel);
+            for (sample, value) in markers.iter().enumerate().filter(|(_, v)| **v != 0.0) {
+                let onset = sample as f64 / sampling_rate as f64;
+                events.push_str(&format!("{:.6}\t0\t{}\t{}\n", onset, sample, value));
+            }
+        }
+        fs::write(&files[tsv + 1], events)?;
+
+        let mut sidecar = Map::new();
+        sidecar.insert("TaskName".to_string(), json!(self.task));
+        sidecar.insert("Manufacturer".to_string(), json!("n/a"));
+        sidecar.insert("ManufacturersModelName".to_string(), json!(device_name));
+        sidecar.insert("SoftwareVersions".to_string(), json!(software_versions));
+        sidecar.insert("SamplingFrequency".to_string(), json!(sampling_rate));
+        sidecar.insert("EEGReference".to_string(), json!(self.reference));
+        let power_line = self.power_line_frequency.map_or(json!("n/a"), |f| json!(f));
+        sidecar.insert("PowerLineFrequency".to_string(), power_line);
+        sidecar.insert("SoftwareFilters".to_string(), json!("n/a"));
+        let counts = [
+            ("EEGChannelCount", "EEG"),
+            ("ECGChannelCount", "ECG"),
+            ("EMGChannelCount", "EMG"),
+            ("EOGChannelCount", "EOG"),
+            ("MiscChannelCount", "MISC"),
+        ];
+        for (key, channel_type_name) in counts.iter() {
+            let count = rows.iter().filter(|(_, c, _)| channel_type(*c) == *channel_type_name).count();
+            sidecar.insert(key.to_string(), json!(count));
+        }
+        sidecar.insert("RecordingDuration".to_string(), json!(data.ncols() as f64 / sampling_rate as f64));
+        sidecar.insert("RecordingType".to_string(), json!("continuous"));
+        fs::write(&files[tsv + 2], serde_json::to_string_pretty(&Value::Object(sidecar))?)?;
+
+        let description = self.root.join("dataset_description.json");
+        if !description.exists() {
+            let name = self.root.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let description_json = json!({"Name": name, "BIDSVersion": BIDS_VERSION, "DatasetType": "raw"});
+            fs::write(&description, serde_json::to_string_pretty(&description_json)?)?;
+        }
+        Ok(files)
+    }
+}
+
+/// BIDS channel type of a kind of data.
+fn channel_type(capability: Capability) -> &'static str {
+    match capability {
+        Capability::Eeg => "EEG",
+        Capability::Emg => "EMG",
+        Capability::Ecg => "ECG",
+        Capability::Eog => "EOG",
+        Capability::Eda => "GSR",
+        Capability::Ppg => "PPG",
+        Capability::Accel => "ACCEL",
+        Capability::Gyro => "GYRO",
+        Capability::Magnetometer => "MAGN",
+        Capability::Temperature => "TEMP",
+        Capability::Exg
+        | Capability::Rotation
+        | Capability::Analog
+        | Capability::Resistance
+        | Capability::Battery
+        | Capability::Other => "MISC",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+    use std::{env, fs};
+
+    use super::{BidsExporter, SignalFormat};
+    use crate::{board_description::BoardDescription, edf, error::Error, BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_export() {
+        let board_id = BoardIds::SyntheticBoard;
+        let preset = BrainFlowPresets::DefaultPreset;
+        let descr = BoardDescription::load(board_id, preset).unwrap();
+        let marker_channel = descr.marker_channel().unwrap();
+        let mut data = Array2::from_shape_fn((*descr.num_rows(), 500), |(row, col)| (row * col) as f64 % 7.0);
+        data.row_mut(marker_channel).fill(0.0);
+        data[[marker_channel, 125]] = 3.0;
+
+        let root = env::temp_dir().join("brainflow_tests/rust/bids");
+        let _ = fs::remove_dir_all(&root);
+        let exporter = BidsExporter::new(&root, "01", "rest").session("1").power_line_frequency(50.0);
+        let files = exporter.export(board_id, preset, &data).unwrap();
+        assert_eq!(root.join("sub-01/ses-1/eeg/sub-01_ses-1_task-rest_eeg.edf"), files[0]);
+        assert!(root.join("dataset_description.json").exists());
+
+        let channels = fs::read_to_string(&files[1]).unwrap();
+        let mut lines = channels.lines();
+        assert_eq!(Some("name\ttype\tunits\tsampling_frequency"), lines.next());
+        assert_eq!(Some("EEG Fz\tEEG\tµV\t250"), lines.next());
+        assert_eq!(edf::read_file(&files[0]).unwrap().channels().len(), channels.lines().count() - 1);
+
+        let events = fs::read_to_string(&files[2]).unwrap();
+        assert_eq!("onset\tduration\tsample\tvalue\n0.500000\t0\t125\t3\n", events);
+
+        let sidecar: serde_json::Value = serde_json::from_str(&fs::read_to_string(&files[3]).unwrap()).unwrap();
+        assert_eq!("Synthetic", sidecar["ManufacturersModelName"]);
+        assert_eq!(16, sidecar["EEGChannelCount"]);
+        assert_eq!(50.0, sidecar["PowerLineFrequency"]);
+        assert_eq!(2.0, sidecar["RecordingDuration"]);
+
+        // an existing export and invalid labels are reported together, without writing
+        let invalid = BidsExporter::new(&root, "01", "rest task").session("1").format(SignalFormat::BrainVision);
+        let again = BidsExporter::new(&root, "01", "rest").session("1");
+        match again.export(board_id, preset, &data) {
+            Err(Error::InvalidBids(reason)) => assert!(reason.contains("exists"), "{}", reason),
+            result => panic!("{:?}", result),
+        }
+        match invalid.export(board_id, preset, &data.slice(ndarray::s![..3, ..]).to_owned()) {
+            Err(Error::InvalidBids(reason)) => assert_eq!(2, reason.split("; ").count(), "{}", reason),
+            result => panic!("{:?}", result),
+        }
+        assert!(!invalid.eeg_dir().join("sub-01_ses-1_task-rest task_eeg.vhdr").exists());
+        assert!(again.overwrite(true).format(SignalFormat::BrainVision).export(board_id, preset, &data).is_ok());
+    }
+}
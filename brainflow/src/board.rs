@@ -0,0 +1,130 @@
+use ndarray::Array2;
+
+use crate::{
+    board_data::BoardData, board_description::BoardDescription, board_shim::BoardShim, BoardIds,
+    BrainFlowPresets, Result,
+};
+
+/// Acquisition backend, implemented by [BoardShim] for native boards and by pure Rust boards like
+/// [crate::memory_board::MemoryBoard].
+///
+/// Methods follow [BoardShim], so code written against `impl Board` works with every backend.
+pub trait Board {
+    /// Board id of the data, e.g. the master board of a streaming board.
+    fn get_board_id(&self) -> BoardIds;
+
+    /// Prepare the session, see [BoardShim::prepare_session].
+    fn prepare_session(&self) -> Result<()>;
+
+    /// Returns true if the session is ready.
+    fn is_prepared(&self) -> Result<bool>;
+
+    /// Start streaming data into the ring buffer, see [BoardShim::start_stream].
+    fn start_stream(&self, buffer_size: usize, streamer_params: &str) -> Result<()>;
+
+    /// Stop streaming data.
+    fn stop_stream(&self) -> Result<()>;
+
+    /// Release all resources of the session.
+    fn release_session(&self) -> Result<()>;
+
+    /// Number of samples in the ring buffer.
+    fn get_board_data_count(&self, preset: BrainFlowPresets) -> Result<usize>;
+
+    /// Get up to `n_data_points` of the oldest samples and remove them from the ring buffer, all if `None`.
+    fn get_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>>;
+
+    /// Get up to `num_samples` of the newest samples without removing them.
+    fn get_current_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>>;
+
+    /// Insert a marker into the next sample, 0 is not a valid marker.
+    fn insert_marker(&self, value: f64, preset: BrainFlowPresets) -> Result<()>;
+
+    /// Layout of the data tables of `preset`.
+    fn get_description(&self, preset: BrainFlowPresets) -> Result<BoardDescription>;
+
+    /// Get board data as [BoardData] and remove it from the ring buffer.
+    fn get_typed_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<BoardData> {
+        let data = self.get_board_data(n_data_points, preset)?;
+        let descr = self.get_description(preset)?;
+        BoardData::with_description(self.get_board_id(), preset, &descr, data)
+    }
+}
+
+impl Board for BoardShim {
+    fn get_board_id(&self) -> BoardIds {
+        BoardShim::get_board_id(self)
+    }
+
+    fn prepare_session(&self) -> Result<()> {
+        BoardShim::prepare_session(self)
+    }
+
+    fn is_prepared(&self) -> Result<bool> {
+        BoardShim::is_prepared(self)
+    }
+
+    fn start_stream(&self, buffer_size: usize, streamer_params: &str) -> Result<()> {
+        BoardShim::start_stream(self, buffer_size, streamer_params)
+    }
+
+    fn stop_stream(&self) -> Result<()> {
+        BoardShim::stop_stream(self)
+    }
+
+    fn release_session(&self) -> Result<()> {
+        BoardShim::release_session(self)
+    }
+
+    fn get_board_data_count(&self, preset: BrainFlowPresets) -> Result<usize> {
+        BoardShim::get_board_data_count(self, preset)
+    }
+
+    fn get_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        BoardShim::get_board_data(self, n_data_points, preset)
+    }
+
+    fn get_current_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        BoardShim::get_current_board_data(self, num_samples, preset)
+    }
+
+    fn insert_marker(&self, value: f64, preset: BrainFlowPresets) -> Result<()> {
+        BoardShim::insert_marker(self, value, preset)
+    }
+
+    fn get_description(&self, preset: BrainFlowPresets) -> Result<BoardDescription> {
+        BoardDescription::load(BoardShim::get_board_id(self), preset)
+    }
+
+    fn get_typed_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<BoardData> {
+        BoardShim::get_typed_board_data(self, n_data_points, preset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::Board;
+    use crate::board_shim::BoardShim;
+    use crate::brainflow_input_params::BrainFlowInputParamsBuilder;
+    use crate::{BoardIds, BrainFlowPresets};
+
+    /// Pipeline written against the trait only.
+    fn acquire<B: Board>(board: &B) -> usize {
+        board.prepare_session().unwrap();
+        board.start_stream(45000, "").unwrap();
+        thread::sleep(Duration::from_millis(100));
+        board.stop_stream().unwrap();
+        let data = board.get_typed_board_data(None, BrainFlowPresets::DefaultPreset).unwrap();
+        board.release_session().unwrap();
+        data.num_samples()
+    }
+
+    #[test]
+    fn test_board_shim_is_a_board() {
+        let params = BrainFlowInputParamsBuilder::new().other_info("board_trait").build();
+        let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+        assert!(acquire(&board) > 0);
+    }
+}
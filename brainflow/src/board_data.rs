@@ -0,0 +1,139 @@
+use getset::Getters;
+use ndarray::{Array2, ArrayView1, Axis};
+
+use crate::{board_description::BoardDescription, error::Error, BoardIds, BrainFlowPresets, Result};
+
+/// Data table returned by a board together with the layout of its rows.
+///
+/// Channel lists which are not defined for the board and preset are empty,
+/// single channels which are not defined are `None`.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct BoardData {
+    board_id: BoardIds,
+    preset: BrainFlowPresets,
+    sampling_rate: usize,
+    data: Array2<f64>,
+    eeg_channels: Vec<usize>,
+    eeg_names: Vec<String>,
+    accel_channels: Vec<usize>,
+    ppg_channels: Vec<usize>,
+    timestamp_channel: Option<usize>,
+    marker_channel: Option<usize>,
+    battery_channel: Option<usize>,
+}
+
+impl BoardData {
+    /// Describe a data table returned by `board_id` for `preset`.
+    pub fn new(board_id: BoardIds, preset: BrainFlowPresets, data: Array2<f64>) -> Result<Self> {
+        let descr = BoardDescription::load(board_id, preset)?;
+        Self::with_description(board_id, preset, &descr, data)
+    }
+
+    /// Describe a data table using an already loaded [BoardDescription],
+    /// the table must have the rows of the description.
+    pub fn with_description(
+        board_id: BoardIds,
+        preset: BrainFlowPresets,
+        descr: &BoardDescription,
+        data: Array2<f64>,
+    ) -> Result<Self> {
+        if data.nrows() != *descr.num_rows() {
+            return Err(Error::InvalidBoardData(format!(
+                "data of {} ({:?}) has {} rows instead of {}",
+                board_id,
+                preset,
+                data.nrows(),
+                descr.num_rows()
+            )));
+        }
+        Ok(Self {
+            board_id,
+            preset,
+            sampling_rate: *descr.sampling_rate(),
+            data,
+            eeg_channels: descr.eeg_channels().clone(),
+            eeg_names: descr.eeg_names().clone(),
+            accel_channels: descr.accel_channels().clone(),
+            ppg_channels: descr.ppg_channels().clone(),
+            timestamp_channel: *descr.timestamp_channel(),
+            marker_channel: *descr.marker_channel(),
+            battery_channel: *descr.battery_channel(),
+        })
+    }
+
+    /// Number of samples in the data table.
+    pub fn num_samples(&self) -> usize {
+        self.data.ncols()
+    }
+
+    /// Rows of the given channels, in the given order.
+    pub fn channels(&self, channels: &[usize]) -> Array2<f64> {
+        self.data.select(Axis(0), channels)
+    }
+
+    /// EEG rows, in the order of [BoardData::eeg_names].
+    pub fn eeg(&self) -> Array2<f64> {
+        self.channels(&self.eeg_channels)
+    }
+
+    /// Accelerometer rows.
+    pub fn accel(&self) -> Array2<f64> {
+        self.channels(&self.accel_channels)
+    }
+
+    /// PPG rows.
+    pub fn ppg(&self) -> Array2<f64> {
+        self.channels(&self.ppg_channels)
+    }
+
+    /// Timestamp row.
+    pub fn timestamps(&self) -> Option<ArrayView1<'_, f64>> {
+        self.timestamp_channel.map(|c| self.data.row(c))
+    }
+
+    /// Marker row.
+    pub fn markers(&self) -> Option<ArrayView1<'_, f64>> {
+        self.marker_channel.map(|c| self.data.row(c))
+    }
+
+    /// Battery row.
+    pub fn battery(&self) -> Option<ArrayView1<'_, f64>> {
+        self.battery_channel.map(|c| self.data.row(c))
+    }
+
+    /// Return the raw data table.
+    pub fn into_inner(self) -> Array2<f64> {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+
+    use super::BoardData;
+    use crate::{board_shim, BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_views_follow_board_layout() {
+        let board_id = BoardIds::SyntheticBoard;
+        let preset = BrainFlowPresets::DefaultPreset;
+        let num_rows = board_shim::get_num_rows(board_id, preset).unwrap();
+        let data = Array2::from_shape_fn((num_rows, 4), |(row, col)| (row * 10 + col) as f64);
+
+        let board_data = BoardData::new(board_id, preset, data).unwrap();
+        assert_eq!(250, *board_data.sampling_rate());
+        assert_eq!(4, board_data.num_samples());
+        assert_eq!(board_data.eeg_names().len(), board_data.eeg().nrows());
+
+        let eeg_channels = board_shim::get_eeg_channels(board_id, preset).unwrap();
+        assert_eq!(eeg_channels[0] as f64 * 10.0, board_data.eeg()[[0, 0]]);
+        let timestamp_channel = board_shim::get_timestamp_channel(board_id, preset).unwrap();
+        assert_eq!(timestamp_channel as f64 * 10.0 + 3.0, board_data.timestamps().unwrap()[3]);
+        assert_eq!(3, board_data.accel().nrows());
+
+        let data = Array2::zeros((num_rows - 1, 4));
+        assert!(BoardData::new(board_id, preset, data).is_err());
+    }
+}
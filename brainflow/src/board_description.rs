@@ -0,0 +1,145 @@
+use getset::Getters;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{board_shim, BoardIds, BrainFlowPresets, Result};
+
+/// Board description as returned by [board_shim::get_board_descr].
+///
+/// Channel lists which are not defined for the board and preset are empty,
+/// single channels which are not defined are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(default)]
+pub struct BoardDescription {
+    name: String,
+    sampling_rate: usize,
+    num_rows: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_num_channel: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp_channel: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    marker_channel: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    battery_channel: Option<usize>,
+    #[serde(
+        deserialize_with = "deserialize_names",
+        serialize_with = "serialize_names",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    eeg_names: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    eeg_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exg_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    emg_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ecg_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    eog_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    eda_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ppg_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    accel_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    rotation_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    gyro_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    analog_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    other_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    temperature_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    resistance_channels: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    magnetometer_channels: Vec<usize>,
+}
+
+impl BoardDescription {
+    /// Load the description of a board for the given preset.
+    pub fn load(board_id: BoardIds, preset: BrainFlowPresets) -> Result<Self> {
+        let descr = board_shim::get_board_descr(board_id, preset)?;
+        Ok(serde_json::from_str(&descr)?)
+    }
+
+    /// Load the descriptions of all presets supported by a board.
+    /// Presets the native library reports but can not describe are skipped.
+    pub fn load_all(board_id: BoardIds) -> Result<Vec<(BrainFlowPresets, Self)>> {
+        let mut descriptions = get_presets(board_id)?
+            .into_iter()
+            .filter_map(|preset| Self::load(board_id, preset).ok().map(|descr| (preset, descr)))
+            .collect::<Vec<_>>();
+        descriptions.sort_by_key(|(preset, _)| *preset as i32);
+        Ok(descriptions)
+    }
+}
+
+/// Get presets for this board as [BrainFlowPresets], unknown presets are skipped.
+pub fn get_presets(board_id: BoardIds) -> Result<Vec<BrainFlowPresets>> {
+    Ok(board_shim::get_board_presets(board_id)?
+        .into_iter()
+        .filter_map(num::FromPrimitive::from_usize)
+        .collect())
+}
+
+fn deserialize_names<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = String::deserialize(deserializer)?;
+    Ok(names
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect())
+}
+
+fn serialize_names<S>(names: &[String], serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&names.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoardDescription;
+    use crate::{board_shim, BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_load_synthetic_board() {
+        let descr = BoardDescription::load(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        assert_eq!("Synthetic", descr.name());
+        assert_eq!(250, *descr.sampling_rate());
+        assert_eq!(
+            board_shim::get_eeg_channels(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap(),
+            *descr.eeg_channels()
+        );
+        assert_eq!(
+            board_shim::get_eeg_names(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap(),
+            *descr.eeg_names()
+        );
+        assert_eq!(Some(30), *descr.timestamp_channel());
+        assert_eq!(Some(31), *descr.marker_channel());
+    }
+
+    #[test]
+    fn test_load_all_presets() {
+        let descriptions = BoardDescription::load_all(BoardIds::SyntheticBoard).unwrap();
+        assert_eq!(BrainFlowPresets::DefaultPreset, descriptions[0].0);
+        assert_eq!("SyntheticAux", descriptions[1].1.name());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let descr = BoardDescription::load(BoardIds::CytonBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        assert_eq!(None, *descr.battery_channel());
+        let json = serde_json::to_string(&descr).unwrap();
+        assert_eq!(descr, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -4,15 +4,22 @@ use std::{
     ffi::CString,
     ffi::CStr,
     os::raw::{c_double, c_int},
+    time::Duration,
 };
 use std::os::raw::c_char;
+use std::path::Path;
 
 use crate::{
-    brainflow_input_params::BrainFlowInputParams, check_brainflow_exit_code, BoardIds, LogLevels,
-    Result, BrainFlowPresets,
+    brainflow_input_params::BrainFlowInputParams, check_brainflow_exit_code,
+    error::{Error, NativeCall, NativeModule}, BoardIds,
+    LogLevels, Result, BrainFlowPresets,
 };
 
+use crate::board_data::BoardData;
 use crate::ffi::board_controller;
+use crate::session::PreparedSession;
+use crate::streamer::StreamerSpec;
+use crate::subscription::BoardSubscription;
 
 const MAX_CHANNELS: usize = 512;
 
@@ -24,13 +31,16 @@ pub struct BoardShim {
 }
 
 impl BoardShim {
-    /// Creates a new [BoardShim].
+    /// Creates a new [BoardShim], the input params are checked with [BrainFlowInputParams::validate_for].
     pub fn new(board_id: BoardIds, input_params: BrainFlowInputParams) -> Result<Self> {
+        input_params.validate_for(board_id)?;
         let json_brainflow_input_params = serde_json::to_string(&input_params)?;
         let json_brainflow_input_params = CString::new(json_brainflow_input_params)?;
         let master_board_id =
             if let BoardIds::StreamingBoard | BoardIds::PlaybackFileBoard = board_id {
-                num::FromPrimitive::from_usize(*input_params.master_board()).unwrap()
+                input_params.master_board_id().ok_or_else(|| {
+                    Error::InvalidInputParams(board_id, "`master_board` is not a board id".to_string())
+                })?
             } else {
                 board_id
             };
@@ -41,6 +51,16 @@ impl BoardShim {
         })
     }
 
+    /// Another handle to the native session of this board, for the background workers of this crate.
+    /// Workers must not outlive `self`, only the owner of a [BoardShim] changes its lifecycle.
+    pub(crate) fn handle(&self) -> Self {
+        Self {
+            board_id: self.board_id,
+            master_board_id: self.master_board_id,
+            json_brainflow_input_params: self.json_brainflow_input_params.clone(),
+        }
+    }
+
     /// Returns the currently used BrainFlowInputParams
     pub fn input_params(&self) -> Result<BrainFlowInputParams> {
         Ok(serde_json::from_str(
@@ -57,7 +77,16 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("prepare_session").board_id(self.board_id),
+        )
+    }
+
+    /// Prepare streaming session and move the board into a [PreparedSession],
+    /// which releases the session when dropped.
+    pub fn prepare(self) -> Result<PreparedSession> {
+        PreparedSession::new(self)
     }
 
     /// Returns true if the session is ready.
@@ -70,7 +99,7 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        check_brainflow_exit_code(res)?;
+        check_brainflow_exit_code(res, NativeCall::board_controller("is_prepared").board_id(self.board_id))?;
         Ok(prepared > 0)
     }
 
@@ -89,7 +118,7 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(res, NativeCall::board_controller("start_stream").board_id(self.board_id))
     }
 
     /// Add streamer from BrainFlow to file or streaming board.
@@ -107,7 +136,10 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("add_streamer").board_id(self.board_id).preset(preset),
+        )
     }
 
     /// Delete streamer registered streamer.
@@ -125,7 +157,28 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("delete_streamer").board_id(self.board_id).preset(preset),
+        )
+    }
+
+    /// Start streaming data and write it to the given streamer, checked with [StreamerSpec::validate].
+    pub fn start_stream_with_spec(&self, buffer_size: usize, streamer: &StreamerSpec) -> Result<()> {
+        streamer.validate()?;
+        self.start_stream(buffer_size, streamer.to_string())
+    }
+
+    /// Add a typed streamer, checked with [StreamerSpec::validate], see [BoardShim::add_streamer].
+    pub fn add_streamer_with_spec(&self, streamer: &StreamerSpec, preset: BrainFlowPresets) -> Result<()> {
+        streamer.validate()?;
+        self.add_streamer(streamer.to_string(), preset)
+    }
+
+    /// Delete a typed streamer, see [BoardShim::delete_streamer].
+    /// It is not validated, a streamer can be deleted after e.g. its directory was removed.
+    pub fn delete_streamer_with_spec(&self, streamer: &StreamerSpec, preset: BrainFlowPresets) -> Result<()> {
+        self.delete_streamer(streamer.to_string(), preset)
     }
 
     /// Stop streaming data.
@@ -136,7 +189,7 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(res, NativeCall::board_controller("stop_stream").board_id(self.board_id))
     }
 
     /// Release all resources.
@@ -147,7 +200,10 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("release_session").board_id(self.board_id),
+        )
     }
 
     /// Get num of elements in ringbuffer.
@@ -161,7 +217,10 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        check_brainflow_exit_code(res)?;
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("get_board_data_count").board_id(self.board_id).preset(preset),
+        )?;
         Ok(data_count as usize)
     }
 
@@ -185,7 +244,10 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        check_brainflow_exit_code(res)?;
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("get_board_data").board_id(self.board_id).preset(preset),
+        )?;
 
         unsafe { data_buf.set_len(capacity) };
 
@@ -208,12 +270,32 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        check_brainflow_exit_code(res)?;
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("get_current_board_data").board_id(self.board_id).preset(preset),
+        )?;
 
         unsafe { data_buf.set_len(len as usize * num_rows) };
         Ok(Array2::from_shape_vec((num_rows, len as usize), data_buf)?)
     }
 
+    /// Get board data as [BoardData] and remove data from ringbuffer.
+    pub fn get_typed_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<BoardData> {
+        let data = self.get_board_data(n_data_points, preset)?;
+        BoardData::new(self.master_board_id, preset, data)
+    }
+
+    /// Get current board data as [BoardData], doesnt remove data from ringbuffer.
+    pub fn get_current_typed_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<BoardData> {
+        let data = self.get_current_board_data(num_samples, preset)?;
+        BoardData::new(self.master_board_id, preset, data)
+    }
+
+    /// Poll new data in a background thread every `poll_interval`, see [BoardSubscription].
+    pub fn subscribe(&self, preset: BrainFlowPresets, poll_interval: Duration) -> Result<BoardSubscription<'_>> {
+        BoardSubscription::new(self, preset, poll_interval)
+    }
+
     /// Use this method carefully and only if you understand what you are doing, do NOT use it to start or stop streaming
     pub fn config_board<S: AsRef<str>>(&self, config: S) -> Result<String> {
         let config = CString::new(config.as_ref())?;
@@ -234,7 +316,7 @@ impl BoardShim {
 
             (res, resp)
         };
-        check_brainflow_exit_code(res)?;
+        check_brainflow_exit_code(res, NativeCall::board_controller("config_board").board_id(self.board_id))?;
         Ok(response
             .to_str()?
             .to_string())
@@ -250,12 +332,15 @@ impl BoardShim {
                 self.json_brainflow_input_params.as_ptr(),
             )
         };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(
+            res,
+            NativeCall::board_controller("insert_marker").board_id(self.board_id).preset(preset),
+        )
     }
 
     /// Get's the actual board id, can be different than provided.
     pub fn get_board_id(&self) -> BoardIds {
-        return self.master_board_id;
+        self.master_board_id
     }
 }
 
@@ -263,7 +348,7 @@ impl BoardShim {
 /// otherwise use [enable_board_logger], [enable_dev_board_logger] or [disable_board_logger].
 pub fn set_log_level(log_level: LogLevels) -> Result<()> {
     let res = unsafe { board_controller::set_log_level_board_controller(log_level as c_int) };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::board_controller("set_log_level_board_controller"))
 }
 
 /// Enable BrainFlow board logger with level INFO, uses stderr for log messages by default.
@@ -286,7 +371,9 @@ pub fn set_log_file<S: AsRef<str>>(log_file: S) -> Result<()> {
     let log_file = log_file.as_ref();
     let log_file = CString::new(log_file)?;
     let res = unsafe { board_controller::set_log_file_board_controller(log_file.as_ptr()) };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::board_controller("set_log_file_board_controller"))?;
+    NativeModule::BoardController.set_log_file(Path::new(log_file.to_str()?));
+    Ok(())
 }
 
 /// Release all sessions
@@ -294,7 +381,7 @@ pub fn release_all_sessions() -> Result<()> {
     let res = unsafe {
         board_controller::release_all_sessions()
     };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::board_controller("release_all_sessions"))
 }
 
 macro_rules! gen_fn {
@@ -304,7 +391,10 @@ macro_rules! gen_fn {
             pub fn [<get_$fn_name>](board_id: BoardIds, preset: BrainFlowPresets) -> Result<$return_type> {
                 let mut value = $initial_value;
                 let res = unsafe { board_controller::[<get_$fn_name>](board_id as c_int, preset as c_int, &mut value) };
-                check_brainflow_exit_code(res)?;
+                check_brainflow_exit_code(
+                    res,
+                    NativeCall::board_controller(concat!("get_", stringify!($fn_name))).board_id(board_id).preset(preset),
+                )?;
                 Ok(value as $return_type)
             }
         }
@@ -352,7 +442,7 @@ pub fn log_message<S: AsRef<str>>(log_level: LogLevels, message: S) -> Result<()
         let _ = CString::from_raw(message);
         res
     };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::board_controller("log_message_board_controller"))
 }
 
 /// Get board description as json.
@@ -365,7 +455,10 @@ pub fn get_board_descr(board_id: BoardIds, preset: BrainFlowPresets) -> Result<S
         let response = CStr::from_ptr(result_char_buffer.as_ptr());
         (res, response)
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(
+        res,
+        NativeCall::board_controller("get_board_descr").board_id(board_id).preset(preset),
+    )?;
     Ok(response.to_str()?.to_string())
 }
 
@@ -379,7 +472,10 @@ pub fn get_eeg_names(board_id: BoardIds, preset: BrainFlowPresets) -> Result<Vec
         let response = CStr::from_ptr(result_char_buffer.as_ptr());
         (res, response)
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(
+        res,
+        NativeCall::board_controller("get_eeg_names").board_id(board_id).preset(preset),
+    )?;
     let names = response.to_str()?;
 
     Ok(names
@@ -399,7 +495,7 @@ pub fn get_board_presets(board_id: BoardIds) -> Result<Vec<usize>> {
             &mut len,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::board_controller("get_board_presets").board_id(board_id))?;
     unsafe { presets.set_len(len as usize) };
     let presets_casted = presets.into_iter().map(|c| c as usize).collect::<Vec<usize>>();
     Ok(presets_casted)
@@ -416,7 +512,7 @@ pub fn get_version() -> Result<String> {
         let response = CStr::from_ptr(result_char_buffer.as_ptr());
         (res, response)
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::board_controller("get_version_board_controller"))?;
     let version = response.to_str()?;
 
     Ok(version.to_string())
@@ -433,7 +529,10 @@ pub fn get_device_name(board_id: BoardIds, preset: BrainFlowPresets) -> Result<S
         let response = CStr::from_ptr(result_char_buffer.as_ptr());
         (res, response)
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(
+        res,
+        NativeCall::board_controller("get_device_name").board_id(board_id).preset(preset),
+    )?;
     Ok(response.to_str()?.to_string())
 }
 
@@ -452,7 +551,10 @@ macro_rules! gen_vec_fn {
                         &mut len,
                     )
                 };
-                check_brainflow_exit_code(res)?;
+                check_brainflow_exit_code(
+                    res,
+                    NativeCall::board_controller(concat!("get_", stringify!($fn_name))).board_id(board_id).preset(preset),
+                )?;
                 unsafe { channels.set_len(len as usize) };
                 let channels_casted = channels.into_iter().map(|c| c as usize).collect::<Vec<usize>>();
                 Ok(channels_casted)
@@ -561,8 +663,10 @@ mod tests {
     #[cfg(test)]
     mod board_shim {
         use crate::board_shim::BoardShim;
-        use crate::BoardIds;
-        use crate::brainflow_input_params::BrainFlowInputParams;
+        use crate::{BoardIds, BrainFlowError, BrainFlowPresets};
+        use crate::brainflow_input_params::{BrainFlowInputParams, BrainFlowInputParamsBuilder};
+        use crate::error::Error;
+        use crate::streamer::{FileMode, StreamerSpec};
 
         fn board_shim() -> BoardShim {
             BoardShim::new(BoardIds::SyntheticBoard,
@@ -571,11 +675,42 @@ mod tests {
 
         #[test]
         fn test_config_board() {
-            let board = board_shim();
-            board.prepare_session();
-            // synthetic board doesnt send any message back as it ignores config calls
-            assert_eq!("", board.config_board("x123456X").unwrap());
+            let session = board_shim().prepare().unwrap();
+            // synthetic board echoes config calls back
+            assert_eq!("Config:x123456X", session.config_board("x123456X").unwrap());
+
+        }
 
+        #[test]
+        fn test_error_describes_call() {
+            let params = BrainFlowInputParamsBuilder::new().other_info("error_context").build();
+            let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+            match board.get_board_data_count(BrainFlowPresets::DefaultPreset) {
+                Err(Error::BrainFlowError(e)) => {
+                    assert_eq!(BrainFlowError::BoardNotCreatedError, *e.kind());
+                    assert_eq!(15, e.exit_code());
+                    assert_eq!("get_board_data_count", *e.operation());
+                    assert_eq!(Some(BoardIds::SyntheticBoard), *e.board_id());
+                    assert_eq!(Some(BrainFlowPresets::DefaultPreset), *e.preset());
+                }
+                res => panic!("unexpected {:?}", res),
+            }
+        }
+
+        #[test]
+        fn test_streamer_spec_is_validated() {
+            let params = BrainFlowInputParamsBuilder::new().other_info("streamer_spec_is_validated").build();
+            let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+            let streamer = StreamerSpec::file("/does/not/exist/data.csv", FileMode::Write).unwrap();
+            // rejected before the native library complains about the missing session
+            match board.start_stream_with_spec(45000, &streamer) {
+                Err(Error::InvalidStreamerParams(_)) => {}
+                res => panic!("unexpected {:?}", res),
+            }
+            match board.add_streamer_with_spec(&streamer, BrainFlowPresets::DefaultPreset) {
+                Err(Error::InvalidStreamerParams(_)) => {}
+                res => panic!("unexpected {:?}", res),
+            }
         }
     }
 
@@ -0,0 +1,152 @@
+use futures::Stream;
+use ndarray::Array2;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::{mpsc, watch};
+
+use crate::{board_shim::BoardShim, BrainFlowPresets, Result};
+
+/// Run a blocking board call on the blocking thread pool of tokio.
+async fn offload<T, F>(board: &BoardShim, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&BoardShim) -> Result<T> + Send + 'static,
+{
+    let board = board.handle();
+    tokio::task::spawn_blocking(move || f(&board)).await?
+}
+
+impl BoardShim {
+    /// Async version of [BoardShim::prepare_session].
+    pub async fn prepare_session_async(&self) -> Result<()> {
+        offload(self, BoardShim::prepare_session).await
+    }
+
+    /// Async version of [BoardShim::start_stream].
+    pub async fn start_stream_async<S: AsRef<str>>(&self, buffer_size: usize, streamer_params: S) -> Result<()> {
+        let streamer_params = streamer_params.as_ref().to_string();
+        offload(self, move |board| board.start_stream(buffer_size, streamer_params)).await
+    }
+
+    /// Async version of [BoardShim::stop_stream].
+    pub async fn stop_stream_async(&self) -> Result<()> {
+        offload(self, BoardShim::stop_stream).await
+    }
+
+    /// Async version of [BoardShim::release_session].
+    pub async fn release_session_async(&self) -> Result<()> {
+        offload(self, BoardShim::release_session).await
+    }
+
+    /// Async version of [BoardShim::get_board_data].
+    pub async fn get_board_data_async(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        offload(self, move |board| board.get_board_data(n_data_points, preset)).await
+    }
+
+    /// Poll new data every `poll_interval` as a [Stream], see [BoardStream].
+    /// Must be called from within a tokio runtime.
+    pub fn stream(&self, preset: BrainFlowPresets, poll_interval: Duration) -> BoardStream<'_> {
+        BoardStream::new(self, preset, poll_interval)
+    }
+}
+
+/// [Stream] of new data of a board, the async counterpart of [crate::subscription::BoardSubscription].
+///
+/// Every item holds the samples which arrived since the previous item, so items never overlap.
+/// The stream ends after an error was delivered. Dropping it stops polling before the next poll,
+/// the samples of a poll which is already running when the stream is dropped are lost.
+/// It borrows the board and cannot outlive it, stopping or releasing the session is not prevented.
+pub struct BoardStream<'a> {
+    chunks: mpsc::Receiver<Result<Array2<f64>>>,
+    cancel: watch::Sender<bool>,
+    board: PhantomData<&'a BoardShim>,
+}
+
+impl<'a> BoardStream<'a> {
+    /// Start polling `board` every `poll_interval` for new data of `preset`.
+    /// Must be called from within a tokio runtime.
+    pub fn new(board: &'a BoardShim, preset: BrainFlowPresets, poll_interval: Duration) -> Self {
+        let (sender, chunks) = mpsc::channel(16);
+        let (cancel, cancelled) = watch::channel(false);
+        let board = board.handle();
+        // not aborted on drop, an abort during a poll would lose the samples it took from the board
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if *cancelled.borrow() {
+                    break;
+                }
+                let chunk = offload(&board, move |board| match board.get_board_data_count(preset)? {
+                    0 => Ok(None),
+                    count => board.get_board_data(Some(count), preset).map(Some),
+                })
+                .await;
+                let chunk = match chunk {
+                    Ok(None) => continue,
+                    Ok(Some(chunk)) => Ok(chunk),
+                    Err(e) => Err(e),
+                };
+                let failed = chunk.is_err();
+                if sender.send(chunk).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Self {
+            chunks,
+            cancel,
+            board: PhantomData,
+        }
+    }
+}
+
+impl Stream for BoardStream<'_> {
+    type Item = Result<Array2<f64>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chunks.poll_recv(cx)
+    }
+}
+
+impl Drop for BoardStream<'_> {
+    fn drop(&mut self) {
+        let _ = self.cancel.send(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    use crate::board_shim::BoardShim;
+    use crate::brainflow_input_params::BrainFlowInputParamsBuilder;
+    use crate::{BoardIds, BrainFlowPresets};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stream_synthetic_board() {
+        let params = BrainFlowInputParamsBuilder::new().other_info("stream_synthetic_board").build();
+        let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+        board.prepare_session_async().await.unwrap();
+        board.start_stream_async(45000, "").await.unwrap();
+
+        let chunks: Vec<_> = board
+            .stream(BrainFlowPresets::DefaultPreset, Duration::from_millis(20))
+            .take(3)
+            .collect()
+            .await;
+        assert_eq!(3, chunks.len());
+        for chunk in chunks {
+            assert!(chunk.unwrap().ncols() > 0);
+        }
+
+        board.stop_stream_async().await.unwrap();
+        board.release_session_async().await.unwrap();
+    }
+}
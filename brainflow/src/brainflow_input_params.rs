@@ -2,7 +2,7 @@ use crate::BoardIds;
 use getset::Getters;
 use serde::{Deserialize, Serialize};
 
-use crate::IpProtocolTypes;
+use crate::{error::Error, IpProtocolTypes, Result};
 
 /// Input parameters for [crate::board_shim::BoardShim].
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Getters)]
@@ -49,6 +49,92 @@ impl Default for BrainFlowInputParams {
     }
 }
 
+impl BrainFlowInputParams {
+    /// Check that the fields needed by `board_id` are set and no fields only used by other boards are set.
+    ///
+    /// The error lists every problem found, e.g. a [BoardIds::CytonBoard] without `serial_port`.
+    pub fn validate_for(&self, board_id: BoardIds) -> Result<()> {
+        let mut problems = Vec::new();
+        for field in required_fields(board_id) {
+            if !self.is_set(field) {
+                problems.push(format!("`{}` is missing", field));
+            }
+        }
+        for field in forbidden_fields(board_id) {
+            if self.is_set(field) {
+                problems.push(format!("`{}` is not used by this board", field));
+            }
+        }
+        if required_fields(board_id).contains(&"master_board") && self.is_set("master_board") {
+            match self.master_board_id() {
+                None => problems.push(format!("`master_board` {} is not a board id", self.master_board as i64)),
+                Some(BoardIds::StreamingBoard | BoardIds::PlaybackFileBoard) => {
+                    problems.push("`master_board` must be the board which recorded the data".to_string())
+                }
+                Some(_) => {}
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidInputParams(board_id, problems.join(", ")))
+        }
+    }
+
+    /// The master board as [BoardIds], `None` if it is not a known board id.
+    pub(crate) fn master_board_id(&self) -> Option<BoardIds> {
+        num::FromPrimitive::from_i64(self.master_board as i64)
+    }
+
+    /// True if the field named `field` differs from its default.
+    fn is_set(&self, field: &str) -> bool {
+        let default = Self::default();
+        match field {
+            "serial_port" => self.serial_port != default.serial_port,
+            "mac_address" => self.mac_address != default.mac_address,
+            "ip_address" => self.ip_address != default.ip_address,
+            "ip_port" => self.ip_port != default.ip_port,
+            "file" => self.file != default.file,
+            "file_aux" => self.file_aux != default.file_aux,
+            "file_anc" => self.file_anc != default.file_anc,
+            "master_board" => self.master_board != default.master_board,
+            _ => unreachable!("no validation for field {}", field),
+        }
+    }
+}
+
+/// Fields the native library needs to open `board_id`.
+fn required_fields(board_id: BoardIds) -> &'static [&'static str] {
+    match board_id {
+        BoardIds::PlaybackFileBoard => &["file", "master_board"],
+        BoardIds::StreamingBoard => &["ip_address", "ip_port", "master_board"],
+        // boards connected by a serial port or by a BLED112 dongle
+        BoardIds::CytonBoard
+        | BoardIds::CytonDaisyBoard
+        | BoardIds::GanglionBoard
+        | BoardIds::Freeeeg32Board
+        | BoardIds::Freeeeg128Board
+        | BoardIds::GaleaSerialBoard
+        | BoardIds::GaleaSerialBoardV4
+        | BoardIds::BrainbitBledBoard
+        | BoardIds::MuseSBledBoard
+        | BoardIds::Muse2BledBoard
+        | BoardIds::Muse2016BledBoard => &["serial_port"],
+        // the WiFi shield sends data to a local port, its address is discovered if not given
+        BoardIds::CytonWifiBoard | BoardIds::CytonDaisyWifiBoard | BoardIds::GanglionWifiBoard => &["ip_port"],
+        _ => &[],
+    }
+}
+
+/// Fields only used by other boards, set by mistake when they are given for `board_id`.
+fn forbidden_fields(board_id: BoardIds) -> &'static [&'static str] {
+    match board_id {
+        BoardIds::PlaybackFileBoard => &[],
+        BoardIds::StreamingBoard => &["file", "file_aux", "file_anc"],
+        _ => &["file", "file_aux", "file_anc", "master_board"],
+    }
+}
+
 /// Builder for [BrainFlowInputParams].
 #[derive(Default)]
 pub struct BrainFlowInputParamsBuilder {
@@ -156,3 +242,48 @@ impl BrainFlowInputParamsBuilder {
         self.params
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BrainFlowInputParamsBuilder;
+    use crate::{error::Error, BoardIds};
+
+    #[test]
+    fn test_validate_required_fields() {
+        let params = BrainFlowInputParamsBuilder::new().build();
+        assert!(params.validate_for(BoardIds::SyntheticBoard).is_ok());
+        match params.validate_for(BoardIds::StreamingBoard) {
+            Err(Error::InvalidInputParams(BoardIds::StreamingBoard, msg)) => {
+                assert_eq!("`ip_address` is missing, `ip_port` is missing, `master_board` is missing", msg)
+            }
+            res => panic!("unexpected {:?}", res),
+        }
+        assert!(params.validate_for(BoardIds::CytonBoard).is_err());
+        let params = BrainFlowInputParamsBuilder::new().serial_port("/dev/ttyUSB0").build();
+        assert!(params.validate_for(BoardIds::CytonBoard).is_ok());
+    }
+
+    #[test]
+    fn test_validate_forbidden_fields() {
+        let params = BrainFlowInputParamsBuilder::new()
+            .file("data.csv")
+            .master_board(BoardIds::SyntheticBoard)
+            .build();
+        assert!(params.validate_for(BoardIds::PlaybackFileBoard).is_ok());
+        let err = params.validate_for(BoardIds::SyntheticBoard).unwrap_err();
+        assert!(err.to_string().contains("`file` is not used"));
+        assert!(err.to_string().contains("`master_board` is not used"));
+    }
+
+    #[test]
+    fn test_validate_master_board() {
+        let mut params = BrainFlowInputParamsBuilder::new()
+            .file("data.csv")
+            .master_board(BoardIds::StreamingBoard)
+            .build();
+        assert!(params.validate_for(BoardIds::PlaybackFileBoard).is_err());
+        params.master_board = 1000;
+        let err = params.validate_for(BoardIds::PlaybackFileBoard).unwrap_err();
+        assert!(err.to_string().contains("1000 is not a board id"));
+    }
+}
@@ -0,0 +1,692 @@
+use getset::Getters;
+use ndarray::Array2;
+use std::{
+    convert::{TryFrom, TryInto},
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    board_description::BoardDescription,
+    catalogue::Capability,
+    edf::{days_from_civil, utc_date_time},
+    error::Error,
+    Result,
+};
+
+/// Sample type of the `.eeg` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryFormat {
+    IeeeFloat32,
+    Int16,
+}
+
+impl BinaryFormat {
+    fn name(self) -> &'static str {
+        match self {
+            BinaryFormat::IeeeFloat32 => "IEEE_FLOAT_32",
+            BinaryFormat::Int16 => "INT_16",
+        }
+    }
+}
+
+/// A channel of the `.vhdr` file, stored values multiplied with the resolution give the value in the unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VhdrChannel {
+    pub name: String,
+    /// Name of the reference channel, empty for the common reference.
+    pub reference: String,
+    pub resolution: f64,
+    /// Unit, e.g. `µV`.
+    pub unit: String,
+}
+
+impl VhdrChannel {
+    /// Channel with the given name, resolution and unit.
+    pub fn new<S: Into<String>>(name: S, resolution: f64, unit: S) -> Self {
+        Self {
+            name: name.into(),
+            reference: String::new(),
+            resolution,
+            unit: unit.into(),
+        }
+    }
+}
+
+/// A marker of the `.vmrk` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VmrkMarker {
+    /// Type, e.g. `Stimulus` or `New Segment`.
+    pub kind: String,
+    pub description: String,
+    /// Sample of the marker, starting at 0.
+    pub position: usize,
+    pub size: usize,
+    /// Channel the marker belongs to, starting at 1, or 0 for all channels.
+    pub channel: usize,
+}
+
+impl VmrkMarker {
+    /// Stimulus marker for a value of the marker channel, integers are written as e.g. `S  1`.
+    pub fn stimulus(position: usize, value: f64) -> Self {
+        let description = if value.fract() == 0.0 && value.abs() < 1e9 {
+            format!("S{:>3}", value as i64)
+        } else {
+            format!("S{}", value)
+        };
+        Self {
+            kind: "Stimulus".to_string(),
+            description,
+            position,
+            size: 1,
+            channel: 0,
+        }
+    }
+
+    /// Value of a stimulus marker, as in the marker channel.
+    pub fn value(&self) -> Option<f64> {
+        if self.kind != "Stimulus" {
+            return None;
+        }
+        let description = self.description.strip_prefix('S').unwrap_or(&self.description);
+        description.trim().parse::<f64>().ok().filter(|v| *v != 0.0)
+    }
+}
+
+/// Channels and sample type of BrainVision files written from board data.
+#[derive(Debug, Clone)]
+pub struct BrainVisionHeader {
+    format: BinaryFormat,
+    sampling_rate: usize,
+    num_rows: usize,
+    /// Row of the board data of each channel.
+    rows: Vec<usize>,
+    channels: Vec<VhdrChannel>,
+    timestamp_channel: Option<usize>,
+    marker_channel: Option<usize>,
+}
+
+impl BrainVisionHeader {
+    /// Header with one channel per data row of `descr`, named with the EEG names of the board.
+    ///
+    /// IEEE float channels have a resolution of 1, int16 channels one covering the values the boards
+    /// report for the kind of channel, e.g. ±187500 µV for EEG, use [BrainVisionHeader::fit_resolutions]
+    /// for a finer one. Markers become stimulus entries of the `.vmrk` file.
+    pub fn from_description(format: BinaryFormat, descr: &BoardDescription) -> Self {
+        let (rows, channels) = Capability::labeled_rows(descr)
+            .into_iter()
+            .map(|(row, capability, label)| {
+                let resolution = match format {
+                    BinaryFormat::IeeeFloat32 => 1.0,
+                    BinaryFormat::Int16 => int16_resolution(capability),
+                };
+                let unit = match capability.unit() {
+                    "uV" => "µV",
+                    "uS" => "µS",
+                    "uT" => "µT",
+                    unit => unit,
+                };
+                (row, VhdrChannel::new(label, resolution, unit.to_string()))
+            })
+            .unzip();
+        Self {
+            format,
+            sampling_rate: *descr.sampling_rate(),
+            num_rows: *descr.num_rows(),
+            rows,
+            channels,
+            timestamp_channel: *descr.timestamp_channel(),
+            marker_channel: *descr.marker_channel(),
+        }
+    }
+
+    /// Replace the channel of board data row `row`, or add it if the row has no channel yet.
+    pub fn channel(mut self, row: usize, channel: VhdrChannel) -> Self {
+        match self.rows.iter().position(|r| *r == row) {
+            Some(i) => self.channels[i] = channel,
+            None => {
+                self.rows.push(row);
+                self.channels.push(channel);
+            }
+        }
+        self
+    }
+
+    /// Set the resolution of every int16 channel to the finest one which fits the values in `data`.
+    pub fn fit_resolutions(mut self, data: &Array2<f64>) -> Self {
+        if self.format != BinaryFormat::Int16 {
+            return self;
+        }
+        for (row, channel) in self.rows.iter().zip(&mut self.channels) {
+            if *row >= data.nrows() {
+                continue;
+            }
+            let max = data.row(*row).iter().filter(|v| v.is_finite()).fold(0.0f64, |a, b| a.max(b.abs()));
+            if max > 0.0 {
+                channel.resolution = max / i16::MAX as f64;
+            }
+        }
+        self
+    }
+
+    /// Channels written to the files, in file order.
+    pub fn channels(&self) -> &[VhdrChannel] {
+        &self.channels
+    }
+
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.sampling_rate == 0 {
+            return Err(Error::InvalidBrainVision("sampling rate must not be 0".to_string()));
+        }
+        if let Some(row) = self.rows.iter().find(|r| **r >= self.num_rows) {
+            return Err(Error::InvalidBrainVision(format!(
+                "row {} is out of the {} board data rows",
+                row, self.num_rows
+            )));
+        }
+        if let Some(channel) = self.channels.iter().find(|c| c.resolution.is_nan() || c.resolution <= 0.0) {
+            return Err(Error::InvalidBrainVision(format!("{} has no positive resolution", channel.name)));
+        }
+        Ok(())
+    }
+
+    /// Reject `data` with values which would be clipped to the int16 range.
+    fn check_int16_range(&self, data: &Array2<f64>) -> Result<()> {
+        for (row, channel) in self.rows.iter().zip(&self.channels) {
+            let limit = channel.resolution * i16::MAX as f64;
+            if let Some(value) = data.row(*row).iter().find(|v| v.abs() > limit) {
+                return Err(Error::InvalidBrainVision(format!(
+                    "{} {} of {} is out of the int16 range ±{} {}",
+                    value, channel.unit, channel.name, limit, channel.unit
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn to_vhdr(&self, name: &str) -> String {
+        let mut vhdr = String::from("Brain Vision Data Exchange Header File Version 1.0\r\n");
+        vhdr.push_str("; Data created by BrainFlow\r\n\r\n[Common Infos]\r\nCodepage=UTF-8\r\n");
+        vhdr.push_str(&format!("DataFile={}.eeg\r\nMarkerFile={}.vmrk\r\n", name, name));
+        vhdr.push_str("DataFormat=BINARY\r\nDataOrientation=MULTIPLEXED\r\n");
+        vhdr.push_str(&format!("NumberOfChannels={}\r\n", self.channels.len()));
+        vhdr.push_str("; Sampling interval in microseconds\r\n");
+        vhdr.push_str(&format!("SamplingInterval={}\r\n\r\n", 1e6 / self.sampling_rate as f64));
+        vhdr.push_str(&format!("[Binary Infos]\r\nBinaryFormat={}\r\n\r\n", self.format.name()));
+        vhdr.push_str("[Channel Infos]\r\n");
+        vhdr.push_str("; Each entry: Ch<Channel number>=<Name>,<Reference channel name>,<Resolution in \"Unit\">,<Unit>\r\n");
+        for (i, channel) in self.channels.iter().enumerate() {
+            vhdr.push_str(&format!(
+                "Ch{}={},{},{},{}\r\n",
+                i + 1,
+                escape(&channel.name),
+                escape(&channel.reference),
+                channel.resolution,
+                channel.unit
+            ));
+        }
+        vhdr
+    }
+}
+
+/// Resolution of int16 channels of a kind, wide enough to store all values the boards report for it.
+fn int16_resolution(capability: Capability) -> f64 {
+    let (min, max) = capability.value_range();
+    min.abs().max(max.abs()) / i16::MAX as f64
+}
+
+/// Writes board data to BrainVision `.vhdr`, `.vmrk` and `.eeg` files.
+///
+/// Data can be written in chunks of any size during a session, the header is complete from the start.
+pub struct BrainVisionWriter {
+    header: BrainVisionHeader,
+    name: String,
+    data: BufWriter<File>,
+    markers: BufWriter<File>,
+    num_markers: usize,
+    num_samples: usize,
+}
+
+impl BrainVisionWriter {
+    /// Create the three files with the file name of `path` and the extensions `.vhdr`, `.vmrk` and `.eeg`.
+    pub fn create<P: AsRef<Path>>(path: P, header: BrainVisionHeader) -> Result<Self> {
+        header.check()?;
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| Error::InvalidBrainVision(format!("{} has no file name", path.display())))?
+            .to_string();
+        fs::write(path.with_extension("vhdr"), header.to_vhdr(&name))?;
+        let data = BufWriter::new(File::create(path.with_extension("eeg"))?);
+        let mut markers = BufWriter::new(File::create(path.with_extension("vmrk"))?);
+        write!(
+            markers,
+            "Brain Vision Data Exchange Marker File, Version 1.0\r\n\r\n[Common Infos]\r\nCodepage=UTF-8\r\n\
+             DataFile={}.eeg\r\n\r\n[Marker Infos]\r\n\
+             ; Each entry: Mk<Marker number>=<Type>,<Description>,<Position in data points>,\
+             <Size in data points>,<Channel number (0 = marker is related to all channels)>\r\n",
+            name
+        )?;
+        Ok(Self {
+            header,
+            name,
+            data,
+            markers,
+            num_markers: 0,
+            num_samples: 0,
+        })
+    }
+
+    /// Header of the files.
+    pub fn header(&self) -> &BrainVisionHeader {
+        &self.header
+    }
+
+    /// Append board data with one sample per column and the rows of the board description.
+    ///
+    /// Nothing is written if a value does not fit the int16 range of its channel.
+    pub fn write(&mut self, data: &Array2<f64>) -> Result<()> {
+        if data.nrows() != self.header.num_rows {
+            return Err(Error::InvalidBrainVision(format!(
+                "board data has {} rows instead of {}",
+                data.nrows(),
+                self.header.num_rows
+            )));
+        }
+        if data.ncols() == 0 {
+            return Ok(());
+        }
+        if self.header.format == BinaryFormat::Int16 {
+            self.header.check_int16_range(data)?;
+        }
+        if self.num_markers == 0 {
+            // the new segment marker holds the start time
+            let start = self
+                .header
+                .timestamp_channel
+                .map(|c| data[[c, 0]])
+                .filter(|t| *t > 0.0)
+                .and_then(|t| Duration::try_from_secs_f64(t).ok())
+                .and_then(|t| UNIX_EPOCH.checked_add(t))
+                .unwrap_or_else(SystemTime::now);
+            let (year, month, day, hours, minutes, seconds) = utc_date_time(start);
+            let micros = start.duration_since(UNIX_EPOCH).map(|d| d.subsec_micros()).unwrap_or(0);
+            self.num_markers += 1;
+            write!(
+                self.markers,
+                "Mk1=New Segment,,1,1,0,{:04}{:02}{:02}{:02}{:02}{:02}{:06}\r\n",
+                year, month, day, hours, minutes, seconds, micros
+            )?;
+        }
+        let mut bytes = Vec::with_capacity(data.ncols() * self.header.channels.len() * 4);
+        for (i, column) in data.columns().into_iter().enumerate() {
+            for (row, channel) in self.header.rows.iter().zip(&self.header.channels) {
+                let value = column[*row] / channel.resolution;
+                match self.header.format {
+                    BinaryFormat::IeeeFloat32 => bytes.extend_from_slice(&(value as f32).to_le_bytes()),
+                    // values are in range, NaN is mapped to 0
+                    BinaryFormat::Int16 => bytes.extend_from_slice(&(value.round() as i16).to_le_bytes()),
+                }
+            }
+            if let Some(value) = self.header.marker_channel.map(|c| column[c]).filter(|v| *v != 0.0) {
+                let marker = VmrkMarker::stimulus(self.num_samples + i, value);
+                self.num_markers += 1;
+                write!(
+                    self.markers,
+                    "Mk{}={},{},{},{},{}\r\n",
+                    self.num_markers,
+                    marker.kind,
+                    escape(&marker.description),
+                    marker.position + 1,
+                    marker.size,
+                    marker.channel
+                )?;
+            }
+        }
+        self.data.write_all(&bytes)?;
+        self.num_samples += data.ncols();
+        Ok(())
+    }
+
+    /// Flush all files.
+    pub fn finish(mut self) -> Result<()> {
+        self.data.flush()?;
+        self.markers.flush()?;
+        Ok(())
+    }
+
+    /// File name of the three files, without extension.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Write board data to BrainVision files, with int16 resolutions fitted to the data.
+pub fn write_files<P: AsRef<Path>>(
+    path: P,
+    format: BinaryFormat,
+    data: &Array2<f64>,
+    descr: &BoardDescription,
+) -> Result<()> {
+    let header = BrainVisionHeader::from_description(format, descr).fit_resolutions(data);
+    let mut writer = BrainVisionWriter::create(path, header)?;
+    writer.write(data)?;
+    writer.finish()
+}
+
+/// BrainVision files read with [read_files].
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct BrainVisionRecording {
+    channels: Vec<VhdrChannel>,
+    sampling_rate: f64,
+    /// One row per channel in the unit of the channel and one column per sample.
+    data: Array2<f64>,
+    markers: Vec<VmrkMarker>,
+    /// Start time of the first new segment marker with a date, read as UTC.
+    start_time: Option<SystemTime>,
+}
+
+impl BrainVisionRecording {
+    /// Stimulus markers as sample and value, like the non-zero values of a marker channel.
+    pub fn events(&self) -> Vec<(usize, f64)> {
+        self.markers.iter().filter_map(|m| m.value().map(|v| (m.position, v))).collect()
+    }
+
+    /// Rebuild board data with the layout the files were written with, see [BrainVisionHeader].
+    ///
+    /// Channels are found by name. The marker row is filled from [BrainVisionRecording::events] and the
+    /// timestamp row from the start time, other rows of the board stay 0.
+    pub fn to_board_data(&self, header: &BrainVisionHeader) -> Result<Array2<f64>> {
+        let mut board_data = Array2::zeros((header.num_rows, self.data.ncols()));
+        for (row, channel) in header.rows.iter().zip(&header.channels) {
+            match self.channels.iter().position(|c| c.name == channel.name) {
+                Some(index) => board_data.row_mut(*row).assign(&self.data.row(index)),
+                None => return Err(Error::InvalidBrainVision(format!("no channel named {}", channel.name))),
+            }
+        }
+        if let Some(channel) = header.marker_channel {
+            for (sample, value) in self.events() {
+                if sample < board_data.ncols() {
+                    board_data[[channel, sample]] = value;
+                }
+            }
+        }
+        if let Some(channel) = header.timestamp_channel {
+            let start = self.start_time.unwrap_or(UNIX_EPOCH);
+            let start = start.duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+            for (i, timestamp) in board_data.row_mut(channel).iter_mut().enumerate() {
+                *timestamp = start + i as f64 / self.sampling_rate;
+            }
+        }
+        Ok(board_data)
+    }
+}
+
+/// Read the `.vhdr` file at `path` and the data and marker files it refers to.
+///
+/// Binary files with IEEE float, int16 or int32 samples in multiplexed or vectorized order are supported.
+pub fn read_files<P: AsRef<Path>>(path: P) -> Result<BrainVisionRecording> {
+    let path = path.as_ref();
+    let invalid = |reason: String| Error::InvalidBrainVision(format!("{}: {}", path.display(), reason));
+    let vhdr = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    if !vhdr.starts_with("Brain Vision Data Exchange Header File") && !vhdr.starts_with("BrainVision Data Exchange") {
+        return Err(invalid("not a BrainVision header".to_string()));
+    }
+    let sections = parse_ini(&vhdr);
+    let common = |key: &str| value(&sections, "Common Infos", key);
+    let dir = path.parent().map(PathBuf::from).unwrap_or_default();
+
+    if common("DataFormat").is_some_and(|f| !f.eq_ignore_ascii_case("BINARY")) {
+        return Err(invalid("only binary data is supported".to_string()));
+    }
+    let vectorized = common("DataOrientation").is_some_and(|o| o.eq_ignore_ascii_case("VECTORIZED"));
+    let num_channels = common("NumberOfChannels")
+        .and_then(|n| n.parse::<usize>().ok())
+        .ok_or_else(|| invalid("missing NumberOfChannels".to_string()))?;
+    let sampling_interval = common("SamplingInterval")
+        .and_then(|n| n.parse::<f64>().ok())
+        .filter(|n| *n > 0.0)
+        .ok_or_else(|| invalid("missing SamplingInterval".to_string()))?;
+    let bytes_per_sample = match value(&sections, "Binary Infos", "BinaryFormat").unwrap_or("INT_16") {
+        "IEEE_FLOAT_32" | "INT_32" => 4,
+        "INT_16" => 2,
+        format => return Err(invalid(format!("unsupported binary format {}", format))),
+    };
+    let binary_format = value(&sections, "Binary Infos", "BinaryFormat").unwrap_or("INT_16").to_string();
+
+    let channels = (1..=num_channels)
+        .map(|i| {
+            let entry = value(&sections, "Channel Infos", &format!("Ch{}", i))
+                .ok_or_else(|| invalid(format!("missing channel {}", i)))?;
+            let mut fields = entry.splitn(4, ',');
+            let name = unescape(fields.next().unwrap_or(""));
+            let reference = unescape(fields.next().unwrap_or(""));
+            let resolution = match fields.next().map(str::trim) {
+                None | Some("") => 1.0,
+                Some(r) => r.parse().map_err(|_| invalid(format!("invalid resolution of channel {}", i)))?,
+            };
+            let unit = fields.next().unwrap_or("µV").trim().to_string();
+            Ok(VhdrChannel {
+                name,
+                reference,
+                resolution,
+                unit,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let data_file = common("DataFile").ok_or_else(|| invalid("missing DataFile".to_string()))?;
+    let raw = fs::read(dir.join(data_file))?;
+    let num_samples = raw.len() / (bytes_per_sample * num_channels.max(1));
+    let mut data = Array2::zeros((num_channels, num_samples));
+    for (i, sample) in raw.chunks_exact(bytes_per_sample).take(num_samples * num_channels).enumerate() {
+        let (channel, col) = if vectorized {
+            (i / num_samples, i % num_samples)
+        } else {
+            (i % num_channels, i / num_channels)
+        };
+        let stored = match (binary_format.as_str(), bytes_per_sample) {
+            ("IEEE_FLOAT_32", _) => f32::from_le_bytes(sample.try_into().unwrap()) as f64,
+            (_, 4) => i32::from_le_bytes(sample.try_into().unwrap()) as f64,
+            _ => i16::from_le_bytes(sample.try_into().unwrap()) as f64,
+        };
+        data[[channel, col]] = stored * channels[channel].resolution;
+    }
+
+    let (markers, start_time) = match common("MarkerFile") {
+        Some(marker_file) => read_markers(&dir.join(marker_file))?,
+        None => (Vec::new(), None),
+    };
+
+    Ok(BrainVisionRecording {
+        channels,
+        sampling_rate: 1e6 / sampling_interval,
+        data,
+        markers,
+        start_time,
+    })
+}
+
+/// Markers of a `.vmrk` file and the date of the first new segment marker.
+fn read_markers(path: &Path) -> Result<(Vec<VmrkMarker>, Option<SystemTime>)> {
+    let vmrk = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    let sections = parse_ini(&vmrk);
+    let mut markers = Vec::new();
+    let mut start_time = None;
+    let entries = sections.iter().filter(|(section, _, _)| section == "Marker Infos");
+    for (_, key, entry) in entries.filter(|(_, key, _)| key.starts_with("Mk")) {
+        let fields = entry.split(',').map(str::trim).collect::<Vec<_>>();
+        let number = |i: usize| fields.get(i).and_then(|f| f.parse::<usize>().ok());
+        let position = number(2).filter(|p| *p > 0).ok_or_else(|| {
+            Error::InvalidBrainVision(format!("{}: invalid position of {}", path.display(), key))
+        })?;
+        let marker = VmrkMarker {
+            kind: fields[0].to_string(),
+            description: unescape(fields.get(1).copied().unwrap_or("")),
+            position: position - 1,
+            size: number(3).unwrap_or(1),
+            channel: number(4).unwrap_or(0),
+        };
+        if marker.kind == "New Segment" && start_time.is_none() {
+            start_time = fields.get(5).and_then(|date| parse_date(date));
+        }
+        markers.push(marker);
+    }
+    Ok((markers, start_time))
+}
+
+/// Parse a `YYYYMMDDhhmmssuuuuuu` date.
+fn parse_date(date: &str) -> Option<SystemTime> {
+    if date.len() != 20 || !date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let part = |range: std::ops::Range<usize>| date[range].parse::<u32>().ok();
+    let days = days_from_civil(part(0..4)? as i64, part(4..6)?, part(6..8)?);
+    let secs = days * 86400 + (part(8..10)? * 3600 + part(10..12)? * 60 + part(12..14)?) as i64;
+    let secs = u64::try_from(secs).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_micros(part(14..20)? as u64))
+}
+
+/// Key value pairs of an ini file with their section.
+fn parse_ini(content: &str) -> Vec<(String, String, String)> {
+    let mut section = String::new();
+    let mut entries = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with(';') || line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            entries.push((section.clone(), key.trim().to_string(), value.to_string()));
+        }
+    }
+    entries
+}
+
+fn value<'a>(entries: &'a [(String, String, String)], section: &str, key: &str) -> Option<&'a str> {
+    entries
+        .iter()
+        .find(|(s, k, _)| s == section && k.eq_ignore_ascii_case(key))
+        .map(|(_, _, v)| v.trim())
+}
+
+/// Commas in names are written as `\1`.
+fn escape(text: &str) -> String {
+    text.replace(',', "\\1")
+}
+
+fn unescape(text: &str) -> String {
+    text.trim().replace("\\1", ",")
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+    use std::{env, fs};
+
+    use super::{read_files, BinaryFormat, BrainVisionHeader, BrainVisionWriter, VhdrChannel, VmrkMarker};
+    use crate::{board_description::BoardDescription, BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_stimulus_markers() {
+        assert_eq!("S  1", VmrkMarker::stimulus(0, 1.0).description);
+        assert_eq!("S123", VmrkMarker::stimulus(0, 123.0).description);
+        assert_eq!("S2.5", VmrkMarker::stimulus(0, 2.5).description);
+        assert_eq!(Some(1.0), VmrkMarker::stimulus(0, 1.0).value());
+        assert_eq!(Some(2.5), VmrkMarker::stimulus(0, 2.5).value());
+    }
+
+    #[test]
+    fn test_int16_values_are_not_clipped() {
+        let descr: BoardDescription = serde_json::from_str(
+            r#"{"name": "Ppg", "sampling_rate": 100, "num_rows": 3, "ppg_channels": [0], "analog_channels": [1],
+                "marker_channel": 2}"#,
+        )
+        .unwrap();
+        let header = BrainVisionHeader::from_description(BinaryFormat::Int16, &descr);
+        // the whole ranges boards report for ppg and analog channels fit
+        assert!(header.channels()[0].resolution * i16::MAX as f64 >= 1048575.0);
+        assert!(header.channels()[1].resolution * i16::MAX as f64 >= 1000000.0);
+
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let mut writer = BrainVisionWriter::create(dir.join("brainvision_clipping.vhdr"), header).unwrap();
+        writer.write(&ndarray::array![[1048575.0], [-1000000.0], [0.0]]).unwrap();
+        assert!(writer.write(&ndarray::array![[2e6], [0.0], [0.0]]).is_err());
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_unrepresentable_start_time() {
+        let descr: BoardDescription = serde_json::from_str(
+            r#"{"name": "Clock", "sampling_rate": 100, "num_rows": 2, "eeg_channels": [0], "timestamp_channel": 1}"#,
+        )
+        .unwrap();
+        let header = BrainVisionHeader::from_description(BinaryFormat::IeeeFloat32, &descr);
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("brainvision_start_time.vhdr");
+        let mut writer = BrainVisionWriter::create(&path, header).unwrap();
+        // too large for a system time, the time of writing is used instead
+        writer.write(&ndarray::array![[1.0], [1e20]]).unwrap();
+        writer.finish().unwrap();
+        assert!(read_files(&path).unwrap().start_time().is_some());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let descr = BoardDescription::load(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        let sampling_rate = *descr.sampling_rate();
+        let marker_channel = descr.marker_channel().unwrap();
+        let timestamp_channel = descr.timestamp_channel().unwrap();
+        let mut data = Array2::from_shape_fn((*descr.num_rows(), 600), |(row, col)| {
+            ((row + col) as f64 / 10.0).sin() * 100.0
+        });
+        data.row_mut(marker_channel).fill(0.0);
+        data[[marker_channel, 0]] = 1.0;
+        data[[marker_channel, 300]] = 2.5;
+        for (i, timestamp) in data.row_mut(timestamp_channel).iter_mut().enumerate() {
+            *timestamp = 1709211909.25 + i as f64 / sampling_rate as f64;
+        }
+
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        for format in [BinaryFormat::IeeeFloat32, BinaryFormat::Int16].iter() {
+            let path = dir.join(format!("brainvision_{}.vhdr", format.name()));
+            let header = BrainVisionHeader::from_description(*format, &descr)
+                .channel(0, VhdrChannel::new("Package, counter", 1.0, ""))
+                .fit_resolutions(&data);
+            let mut writer = BrainVisionWriter::create(&path, header.clone()).unwrap();
+            writer.write(&data.slice(ndarray::s![.., ..250]).to_owned()).unwrap();
+            writer.write(&data.slice(ndarray::s![.., 250..]).to_owned()).unwrap();
+            writer.finish().unwrap();
+
+            let recording = read_files(&path).unwrap();
+            assert_eq!(sampling_rate as f64, *recording.sampling_rate());
+            assert_eq!(header.channels().len(), recording.channels().len());
+            assert_eq!("µV", recording.channels()[0].unit);
+            assert_eq!("Package, counter", recording.channels().last().unwrap().name);
+            assert_eq!(vec![(0, 1.0), (300, 2.5)], recording.events());
+
+            let read = recording.to_board_data(&header).unwrap();
+            assert_eq!(data.dim(), read.dim());
+            for (row, channel) in header.rows.iter().zip(header.channels()) {
+                let tolerance = match format {
+                    BinaryFormat::IeeeFloat32 => 1e-4,
+                    BinaryFormat::Int16 => channel.resolution,
+                };
+                for (a, b) in data.row(*row).iter().zip(read.row(*row)) {
+                    assert!((a - b).abs() <= tolerance, "{} != {} in {}", a, b, channel.name);
+                }
+            }
+            assert_eq!(data.row(marker_channel), read.row(marker_channel));
+            assert!((data[[timestamp_channel, 599]] - read[[timestamp_channel, 599]]).abs() < 1e-5);
+        }
+        assert!(read_files(dir.join("brainvision_missing.vhdr")).is_err());
+    }
+}
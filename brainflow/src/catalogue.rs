@@ -0,0 +1,327 @@
+use getset::Getters;
+use std::sync::OnceLock;
+
+use crate::{
+    board_description::{self, BoardDescription},
+    board_shim,
+    error::Error,
+    BoardIds, BrainFlowPresets, Result,
+};
+
+/// Kind of data a board provides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    Eeg,
+    Exg,
+    Emg,
+    Ecg,
+    Eog,
+    Eda,
+    Ppg,
+    Accel,
+    Rotation,
+    Gyro,
+    Magnetometer,
+    Analog,
+    Temperature,
+    Resistance,
+    Battery,
+    Other,
+}
+
+impl Capability {
+    /// All capabilities, in declaration order.
+    pub const ALL: [Capability; 16] = [
+        Capability::Eeg,
+        Capability::Exg,
+        Capability::Emg,
+        Capability::Ecg,
+        Capability::Eog,
+        Capability::Eda,
+        Capability::Ppg,
+        Capability::Accel,
+        Capability::Rotation,
+        Capability::Gyro,
+        Capability::Magnetometer,
+        Capability::Analog,
+        Capability::Temperature,
+        Capability::Resistance,
+        Capability::Battery,
+        Capability::Other,
+    ];
+
+    /// Capabilities with at least one channel in `descr`.
+    pub fn from_description(descr: &BoardDescription) -> Vec<Capability> {
+        Self::ALL
+            .iter()
+            .filter(|capability| !capability.channels(descr).is_empty())
+            .copied()
+            .collect()
+    }
+
+    /// Rows of `descr` with this kind of data.
+    pub fn channels(self, descr: &BoardDescription) -> Vec<usize> {
+        match self {
+            Capability::Eeg => descr.eeg_channels().clone(),
+            Capability::Exg => descr.exg_channels().clone(),
+            Capability::Emg => descr.emg_channels().clone(),
+            Capability::Ecg => descr.ecg_channels().clone(),
+            Capability::Eog => descr.eog_channels().clone(),
+            Capability::Eda => descr.eda_channels().clone(),
+            Capability::Ppg => descr.ppg_channels().clone(),
+            Capability::Accel => descr.accel_channels().clone(),
+            Capability::Rotation => descr.rotation_channels().clone(),
+            Capability::Gyro => descr.gyro_channels().clone(),
+            Capability::Magnetometer => descr.magnetometer_channels().clone(),
+            Capability::Analog => descr.analog_channels().clone(),
+            Capability::Temperature => descr.temperature_channels().clone(),
+            Capability::Resistance => descr.resistance_channels().clone(),
+            Capability::Battery => descr.battery_channel().iter().copied().collect(),
+            Capability::Other => descr.other_channels().clone(),
+        }
+    }
+
+    /// Every data row of `descr` with its kind, sorted by row.
+    ///
+    /// Rows listed for several kinds get the most specific one, e.g. EEG rows of a board which
+    /// also lists them as ExG rows are [Capability::Eeg].
+    pub fn of_rows(descr: &BoardDescription) -> Vec<(usize, Capability)> {
+        let mut rows: Vec<(usize, Capability)> = Vec::new();
+        let by_priority = Self::ALL.iter().filter(|c| **c != Capability::Exg).chain(&[Capability::Exg]);
+        for capability in by_priority {
+            for row in capability.channels(descr) {
+                if !rows.iter().any(|(r, _)| *r == row) {
+                    rows.push((row, *capability));
+                }
+            }
+        }
+        rows.sort_by_key(|(row, _)| *row);
+        rows
+    }
+
+    /// [Capability::of_rows] with a label for every row, the EEG name for EEG rows or e.g. `Accel 2`
+    /// for the second accelerometer row.
+    pub fn labeled_rows(descr: &BoardDescription) -> Vec<(usize, Capability, String)> {
+        let rows = Self::of_rows(descr);
+        rows.iter()
+            .enumerate()
+            .map(|(i, (row, capability))| {
+                let eeg_name = match capability {
+                    Capability::Eeg => descr
+                        .eeg_channels()
+                        .iter()
+                        .position(|c| c == row)
+                        .and_then(|p| descr.eeg_names().get(p)),
+                    _ => None,
+                };
+                let label = match eeg_name {
+                    Some(name) => name.clone(),
+                    None => {
+                        let index = rows[..i].iter().filter(|(_, c)| c == capability).count();
+                        format!("{} {}", capability.name(), index + 1)
+                    }
+                };
+                (*row, *capability, label)
+            })
+            .collect()
+    }
+
+    /// Short name, e.g. `EEG`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Eeg => "EEG",
+            Capability::Exg => "ExG",
+            Capability::Emg => "EMG",
+            Capability::Ecg => "ECG",
+            Capability::Eog => "EOG",
+            Capability::Eda => "EDA",
+            Capability::Ppg => "PPG",
+            Capability::Accel => "Accel",
+            Capability::Rotation => "Rotation",
+            Capability::Gyro => "Gyro",
+            Capability::Magnetometer => "Magnetometer",
+            Capability::Analog => "Analog",
+            Capability::Temperature => "Temperature",
+            Capability::Resistance => "Resistance",
+            Capability::Battery => "Battery",
+            Capability::Other => "Other",
+        }
+    }
+
+    /// Unit of the values the boards report, empty for raw values.
+    pub fn unit(self) -> &'static str {
+        match self {
+            Capability::Eeg | Capability::Exg | Capability::Emg | Capability::Ecg | Capability::Eog => "uV",
+            Capability::Eda => "uS",
+            Capability::Accel => "g",
+            Capability::Rotation => "deg",
+            Capability::Gyro => "deg/s",
+            Capability::Magnetometer => "uT",
+            Capability::Temperature => "degC",
+            Capability::Resistance => "kOhm",
+            Capability::Battery => "%",
+            Capability::Ppg | Capability::Analog | Capability::Other => "",
+        }
+    }
+
+    /// Smallest and largest value the boards report, used to size the fixed point formats of file exports.
+    pub(crate) fn value_range(self) -> (f64, f64) {
+        match self {
+            Capability::Eeg | Capability::Exg | Capability::Emg | Capability::Ecg | Capability::Eog => {
+                (-187500.0, 187500.0)
+            }
+            Capability::Eda => (0.0, 100.0),
+            Capability::Ppg => (0.0, 1048575.0),
+            Capability::Accel => (-16.0, 16.0),
+            Capability::Rotation => (-360.0, 360.0),
+            Capability::Gyro => (-2000.0, 2000.0),
+            Capability::Magnetometer => (-4900.0, 4900.0),
+            Capability::Temperature => (-40.0, 125.0),
+            Capability::Resistance => (0.0, 10000.0),
+            Capability::Battery => (0.0, 100.0),
+            Capability::Analog | Capability::Other => (-1000000.0, 1000000.0),
+        }
+    }
+}
+
+/// What a board provides for one preset.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct PresetInfo {
+    preset: BrainFlowPresets,
+    description: BoardDescription,
+    capabilities: Vec<Capability>,
+}
+
+impl PresetInfo {
+    /// Sampling rate of this preset.
+    pub fn sampling_rate(&self) -> usize {
+        *self.description.sampling_rate()
+    }
+}
+
+/// What a board provides, for every preset.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct BoardInfo {
+    board_id: BoardIds,
+    device_name: String,
+    presets: Vec<PresetInfo>,
+}
+
+impl BoardInfo {
+    /// Query the native library about a board.
+    pub fn load(board_id: BoardIds) -> Result<Self> {
+        let device_name = board_shim::get_device_name(board_id, BrainFlowPresets::DefaultPreset)?;
+        let presets = board_description::get_presets(board_id)?
+            .into_iter()
+            .map(|preset| {
+                let description = BoardDescription::load(board_id, preset)?;
+                Ok(PresetInfo {
+                    preset,
+                    capabilities: Capability::from_description(&description),
+                    description,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut info = Self {
+            board_id,
+            device_name,
+            presets,
+        };
+        info.presets.sort_by_key(|p| p.preset as i32);
+        Ok(info)
+    }
+
+    /// Info for one preset, if the board supports it.
+    pub fn preset(&self, preset: BrainFlowPresets) -> Option<&PresetInfo> {
+        self.presets.iter().find(|p| p.preset == preset)
+    }
+
+    /// True if any preset of the board provides `capability`.
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.presets.iter().any(|p| p.capabilities.contains(&capability))
+    }
+}
+
+/// Information about all [BoardIds], queried once from the native library.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct BoardCatalogue {
+    boards: Vec<BoardInfo>,
+    /// Boards whose info lookup failed, with the error.
+    failures: Vec<(BoardIds, Error)>,
+}
+
+impl BoardCatalogue {
+    /// Query all boards except [BoardIds::NoBoard].
+    pub fn load() -> Self {
+        let mut boards = Vec::new();
+        let mut failures = Vec::new();
+        for board_id in BoardIds::all().iter().filter(|id| **id != BoardIds::NoBoard) {
+            match BoardInfo::load(*board_id) {
+                Ok(info) => boards.push(info),
+                Err(e) => failures.push((*board_id, e)),
+            }
+        }
+        Self { boards, failures }
+    }
+
+    /// Catalogue shared by the whole process, loaded on first use.
+    pub fn global() -> &'static BoardCatalogue {
+        static CATALOGUE: OnceLock<BoardCatalogue> = OnceLock::new();
+        CATALOGUE.get_or_init(BoardCatalogue::load)
+    }
+
+    /// Info about a board, `None` if its lookup failed.
+    pub fn get(&self, board_id: BoardIds) -> Option<&BoardInfo> {
+        self.boards.iter().find(|b| b.board_id == board_id)
+    }
+
+    /// Boards which provide `capability` with any preset.
+    pub fn with_capability(&self, capability: Capability) -> impl Iterator<Item = &BoardInfo> {
+        self.boards.iter().filter(move |b| b.supports(capability))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoardCatalogue, Capability};
+    use crate::{BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_catalogue() {
+        let catalogue = BoardCatalogue::global();
+        assert_eq!(BoardIds::all().len() - 1, catalogue.boards().len() + catalogue.failures().len());
+
+        let synthetic = catalogue.get(BoardIds::SyntheticBoard).unwrap();
+        assert_eq!("Synthetic", synthetic.device_name());
+        let default = synthetic.preset(BrainFlowPresets::DefaultPreset).unwrap();
+        assert_eq!(250, default.sampling_rate());
+        assert!(default.capabilities().contains(&Capability::Eeg));
+        assert!(default.capabilities().contains(&Capability::Battery));
+        assert!(synthetic.supports(Capability::Ppg));
+
+        let cyton = catalogue.get(BoardIds::CytonBoard).unwrap();
+        assert!(!cyton.supports(Capability::Battery));
+        assert!(catalogue.with_capability(Capability::Eeg).any(|b| *b.board_id() == BoardIds::CytonDaisyBoard));
+    }
+
+    #[test]
+    fn test_rows_of_capabilities() {
+        let cyton = BoardCatalogue::global().get(BoardIds::CytonBoard).unwrap();
+        let descr = cyton.preset(BrainFlowPresets::DefaultPreset).unwrap().description();
+        let rows = Capability::of_rows(descr);
+        for row in descr.eeg_channels() {
+            assert!(rows.contains(&(*row, Capability::Eeg)));
+        }
+        assert!(!rows.iter().any(|(row, _)| Some(*row) == *descr.timestamp_channel()));
+        assert_eq!(descr.accel_channels().len(), Capability::Accel.channels(descr).len());
+
+        let labels = Capability::labeled_rows(descr);
+        assert_eq!(rows.len(), labels.len());
+        assert_eq!(descr.eeg_names()[0], labels[0].2);
+        let accel = descr.accel_channels()[1];
+        assert!(labels.contains(&(accel, Capability::Accel, "Accel 2".to_string())));
+    }
+}
@@ -0,0 +1,297 @@
+use getset::Getters;
+use std::fmt;
+
+use crate::{board_shim::BoardShim, error::Error, BoardIds, Result};
+
+/// Channel commands of the Daisy module, for channels 9 to 16.
+const DAISY_CHANNELS: [char; 8] = ['Q', 'W', 'E', 'R', 'T', 'Y', 'U', 'I'];
+
+/// Command which resets all channels to [CytonChannelSettings::default].
+pub const RESET_CHANNELS_COMMAND: &str = "d";
+/// Command which reports the default channel settings, parse the response with [CytonChannelSettings::parse].
+pub const REPORT_DEFAULT_SETTINGS_COMMAND: &str = "D";
+
+/// Programmable gain of a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+pub enum CytonGain {
+    X1 = 0,
+    X2 = 1,
+    X4 = 2,
+    X6 = 3,
+    X8 = 4,
+    X12 = 5,
+    X24 = 6,
+}
+
+/// Input connected to the ADC of a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, FromPrimitive, ToPrimitive)]
+pub enum CytonInputType {
+    Normal = 0,
+    Shorted = 1,
+    BiasMeas = 2,
+    Mvdd = 3,
+    Temp = 4,
+    TestSig = 5,
+    BiasDrp = 6,
+    BiasDrn = 7,
+}
+
+/// Settings of one channel of a Cyton or Cyton Daisy board.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct CytonChannelSettings {
+    power_down: bool,
+    gain: CytonGain,
+    input_type: CytonInputType,
+    bias: bool,
+    srb2: bool,
+    srb1: bool,
+}
+
+impl Default for CytonChannelSettings {
+    fn default() -> Self {
+        Self {
+            power_down: false,
+            gain: CytonGain::X24,
+            input_type: CytonInputType::Normal,
+            bias: true,
+            srb2: true,
+            srb1: false,
+        }
+    }
+}
+
+impl CytonChannelSettings {
+    /// Command applying these settings to `channel`, counted from 1, e.g. `x1060110X`.
+    pub fn command(&self, channel: usize) -> Result<String> {
+        Ok(format!(
+            "x{}{}{}{}{}{}{}X",
+            channel_char(channel)?,
+            self.power_down as u8,
+            self.gain as u8,
+            self.input_type as u8,
+            self.bias as u8,
+            self.srb2 as u8,
+            self.srb1 as u8,
+        ))
+    }
+
+    /// Parse the six setting digits reported by the board, e.g. the response to
+    /// [REPORT_DEFAULT_SETTINGS_COMMAND] like `060110$$$`.
+    pub fn parse(response: &str) -> Result<Self> {
+        let settings = strip_eot(response);
+        let invalid = || Error::InvalidBoardConfig(format!("`{}` are not channel settings", settings));
+        let digits = settings
+            .chars()
+            .map(|c| c.to_digit(10).ok_or_else(invalid))
+            .collect::<Result<Vec<u32>>>()?;
+        if digits.len() != 6 {
+            return Err(invalid());
+        }
+        let flag = |d: u32| match d {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid()),
+        };
+        Ok(Self {
+            power_down: flag(digits[0])?,
+            gain: num::FromPrimitive::from_u32(digits[1]).ok_or_else(invalid)?,
+            input_type: num::FromPrimitive::from_u32(digits[2]).ok_or_else(invalid)?,
+            bias: flag(digits[3])?,
+            srb2: flag(digits[4])?,
+            srb1: flag(digits[5])?,
+        })
+    }
+}
+
+impl fmt::Display for CytonChannelSettings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}{}{}",
+            self.power_down as u8, self.gain as u8, self.input_type as u8, self.bias as u8, self.srb2 as u8, self.srb1 as u8,
+        )
+    }
+}
+
+/// Builder for [CytonChannelSettings], starting from the board defaults.
+#[derive(Default)]
+pub struct CytonChannelSettingsBuilder {
+    settings: CytonChannelSettings,
+}
+
+impl CytonChannelSettingsBuilder {
+    /// Create a new builder.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Power the channel down.
+    pub fn power_down(mut self, power_down: bool) -> Self {
+        self.settings.power_down = power_down;
+        self
+    }
+
+    /// Programmable gain.
+    pub fn gain(mut self, gain: CytonGain) -> Self {
+        self.settings.gain = gain;
+        self
+    }
+
+    /// Input connected to the ADC.
+    pub fn input_type(mut self, input_type: CytonInputType) -> Self {
+        self.settings.input_type = input_type;
+        self
+    }
+
+    /// Include the channel in bias generation.
+    pub fn bias(mut self, bias: bool) -> Self {
+        self.settings.bias = bias;
+        self
+    }
+
+    /// Connect the channel to SRB2.
+    pub fn srb2(mut self, srb2: bool) -> Self {
+        self.settings.srb2 = srb2;
+        self
+    }
+
+    /// Connect all channels to SRB1.
+    pub fn srb1(mut self, srb1: bool) -> Self {
+        self.settings.srb1 = srb1;
+        self
+    }
+
+    /// Build CytonChannelSettings with the given options.
+    pub fn build(self) -> CytonChannelSettings {
+        self.settings
+    }
+}
+
+/// Command which starts or stops the impedance test of `channel`, counted from 1,
+/// on its P and N inputs, e.g. `z410Z`.
+pub fn impedance_command(channel: usize, test_p: bool, test_n: bool) -> Result<String> {
+    Ok(format!("z{}{}{}Z", channel_char(channel)?, test_p as u8, test_n as u8))
+}
+
+/// Character addressing `channel`, counted from 1, in channel commands.
+pub fn channel_char(channel: usize) -> Result<char> {
+    match channel {
+        1..=8 => Ok((b'0' + channel as u8) as char),
+        9..=16 => Ok(DAISY_CHANNELS[channel - 9]),
+        _ => Err(Error::InvalidBoardConfig(format!(
+            "channel {} is out of range 1 to 16",
+            channel
+        ))),
+    }
+}
+
+/// Response of the board to a configuration command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigResponse {
+    /// The board accepted the command, e.g. `Success: Channel set for 3`.
+    Success(String),
+    /// The board rejected the command, e.g. `Failure: too few chars`.
+    Failure(String),
+    /// Any other response, e.g. an empty one from older firmware.
+    Other(String),
+}
+
+impl ConfigResponse {
+    /// Parse a response returned by [BoardShim::config_board].
+    pub fn parse(response: &str) -> Self {
+        let response = strip_eot(response);
+        if let Some(msg) = response.strip_prefix("Success:") {
+            ConfigResponse::Success(msg.trim().to_string())
+        } else if let Some(msg) = response.strip_prefix("Failure:") {
+            ConfigResponse::Failure(msg.trim().to_string())
+        } else {
+            ConfigResponse::Other(response.to_string())
+        }
+    }
+}
+
+/// Apply `settings` to `channel` of a Cyton or Cyton Daisy board.
+pub fn set_channel_settings(board: &BoardShim, channel: usize, settings: &CytonChannelSettings) -> Result<ConfigResponse> {
+    let num_channels = match board.get_board_id() {
+        BoardIds::CytonBoard | BoardIds::CytonWifiBoard => 8,
+        BoardIds::CytonDaisyBoard | BoardIds::CytonDaisyWifiBoard => 16,
+        board_id => {
+            return Err(Error::InvalidBoardConfig(format!(
+                "{} is not a Cyton board",
+                board_id
+            )))
+        }
+    };
+    if channel > num_channels {
+        return Err(Error::InvalidBoardConfig(format!(
+            "channel {} is out of range 1 to {}",
+            channel, num_channels
+        )));
+    }
+    let response = board.config_board(settings.command(channel)?)?;
+    Ok(ConfigResponse::parse(&response))
+}
+
+/// Remove the `$$$` end of transmission marker and surrounding whitespace.
+fn strip_eot(response: &str) -> &str {
+    response.trim().trim_end_matches("$$$").trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_command() {
+        let settings = CytonChannelSettings::default();
+        assert_eq!("x1060110X", settings.command(1).unwrap());
+        assert_eq!("x8060110X", settings.command(8).unwrap());
+        assert_eq!("xQ060110X", settings.command(9).unwrap());
+        assert_eq!("xI060110X", settings.command(16).unwrap());
+        assert!(settings.command(0).is_err());
+        assert!(settings.command(17).is_err());
+    }
+
+    #[test]
+    fn test_builder_command() {
+        let settings = CytonChannelSettingsBuilder::new()
+            .power_down(true)
+            .gain(CytonGain::X2)
+            .input_type(CytonInputType::Shorted)
+            .bias(false)
+            .srb2(false)
+            .srb1(true)
+            .build();
+        assert_eq!("x3111001X", settings.command(3).unwrap());
+    }
+
+    #[test]
+    fn test_impedance_command() {
+        assert_eq!("z410Z", impedance_command(4, true, false).unwrap());
+        assert_eq!("zE01Z", impedance_command(11, false, true).unwrap());
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        assert_eq!(CytonChannelSettings::default(), CytonChannelSettings::parse("060110$$$").unwrap());
+        let err = CytonChannelSettings::parse("1350011").unwrap_err();
+        assert!(err.to_string().contains("1350011"));
+        assert!(CytonChannelSettings::parse("080110").is_err());
+        let settings = CytonChannelSettingsBuilder::new().gain(CytonGain::X8).build();
+        assert_eq!(settings, CytonChannelSettings::parse(&settings.to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            ConfigResponse::Success("Channel set for 3".to_string()),
+            ConfigResponse::parse("Success: Channel set for 3$$$")
+        );
+        assert_eq!(
+            ConfigResponse::Failure("too few chars".to_string()),
+            ConfigResponse::parse("Failure: too few chars$$$")
+        );
+        assert_eq!(ConfigResponse::Other("".to_string()), ConfigResponse::parse(""));
+    }
+}
@@ -5,8 +5,9 @@ use num_complex::Complex64;
 use std::os::raw::c_int;
 use std::{ffi::CString, ffi::CStr, os::raw::c_double};
 use std::os::raw::c_char;
+use std::path::Path;
 
-use crate::error::{BrainFlowError, Error};
+use crate::error::{BrainFlowError, NativeCall, NativeModule};
 use crate::ffi::data_handler;
 use crate::{
     check_brainflow_exit_code, AggOperations, DetrendOperations, FilterTypes, LogLevels,
@@ -19,7 +20,7 @@ use crate::{
 /// Otherwise use [enable_data_logger], [enable_dev_data_logger] or [disable_data_logger].
 pub fn set_log_level(log_level: LogLevels) -> Result<()> {
     let res = unsafe { data_handler::set_log_level_data_handler(log_level as c_int) };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::data_handler("set_log_level_data_handler"))
 }
 
 /// Enable data logger with level INFO, uses stderr for log messages by default
@@ -46,7 +47,7 @@ pub fn log_message<S: AsRef<str>>(log_level: LogLevels, message: S) -> Result<()
         let _ = CString::from_raw(message);
         res
     };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::data_handler("log_message_data_handler"))
 }
 
 /// Redirect data logger from stderr to file, can be called any time.
@@ -54,7 +55,9 @@ pub fn set_log_file<S: AsRef<str>>(log_file: S) -> Result<()> {
     let log_file = log_file.as_ref();
     let log_file = CString::new(log_file)?;
     let res = unsafe { data_handler::set_log_file_data_handler(log_file.as_ptr()) };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::data_handler("set_log_file_data_handler"))?;
+    NativeModule::DataHandler.set_log_file(Path::new(log_file.to_str()?));
+    Ok(())
 }
 
 /// Apply low pass filter to provided data.
@@ -77,7 +80,7 @@ pub fn perform_lowpass(
             ripple as c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_lowpass"))?;
     Ok(())
 }
 
@@ -101,7 +104,7 @@ pub fn perform_highpass(
             ripple as c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_highpass"))?;
     Ok(())
 }
 
@@ -127,7 +130,7 @@ pub fn perform_bandpass(
             ripple as c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_bandpass"))?;
     Ok(())
 }
 
@@ -153,7 +156,7 @@ pub fn perform_bandstop(
             ripple as c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_bandstop"))?;
     Ok(())
 }
 
@@ -171,7 +174,7 @@ pub fn remove_environmental_noise(
             noise_type as c_int,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("remove_environmental_noise"))?;
     Ok(())
 }
 
@@ -189,7 +192,7 @@ pub fn perform_rolling_filter(
             agg_operation as c_int,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_rolling_filter"))?;
     Ok(())
 }
 
@@ -208,7 +211,7 @@ pub fn calc_stddev(
             &mut output,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("calc_stddev"))?;
     Ok(output as f64)
 }
 
@@ -227,7 +230,7 @@ pub fn get_railed_percentage(
             &mut output,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_railed_percentage"))?;
     Ok(output as f64)
 }
 
@@ -253,7 +256,7 @@ pub fn get_oxygen_level(
             &mut output,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_oxygen_level"))?;
     Ok(output as f64)
 }
 
@@ -275,7 +278,7 @@ pub fn get_heart_rate(
             &mut output,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_heart_rate"))?;
     Ok(output as f64)
 }
 
@@ -286,7 +289,7 @@ pub fn perform_downsampling(
     agg_operation: AggOperations,
 ) -> Result<Vec<f64>> {
     if period == 0 {
-        return Err(Error::BrainFlowError(BrainFlowError::InvalidArgumentsError));
+        return Err(NativeCall::data_handler("perform_downsampling").error(BrainFlowError::InvalidArgumentsError));
     }
     let output_len = data.len() / period as usize;
     let mut output = Vec::<f64>::with_capacity(output_len);
@@ -299,7 +302,7 @@ pub fn perform_downsampling(
             output.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_downsampling"))?;
     unsafe { output.set_len(output_len) }
     Ok(output)
 }
@@ -384,7 +387,7 @@ pub fn perform_wavelet_transform(
             decomposition_lengths,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_wavelet_transform"))?;
     Ok(wavelet_transform)
 }
 
@@ -408,7 +411,7 @@ pub fn restore_data_from_wavelet_detailed_coeffs(
             output.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("restore_data_from_wavelet_detailed_coeffs"))?;
     unsafe { output.set_len(output_len) }
     Ok(output)
 }
@@ -432,7 +435,7 @@ pub fn detect_peaks_z_score(
             output.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("detect_peaks_z_score"))?;
     unsafe { output.set_len(output_len) }
     Ok(output)
 }
@@ -452,7 +455,7 @@ pub fn perform_inverse_wavelet_transform(wavelet_transform: WaveletTransform) ->
             output.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_inverse_wavelet_transform"))?;
     unsafe { output.set_len(wavelet_transform.original_data_len) }
     Ok(output)
 }
@@ -479,7 +482,7 @@ pub fn perform_wavelet_denoising(
             noise_level as c_int,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_wavelet_denoising"))?;
     Ok(())
 }
 
@@ -510,7 +513,7 @@ pub fn get_csp<Labels>(
             output_eigenvalues.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_csp"))?;
 
     unsafe { output_filters.set_len(n_channels * n_channels) };
     unsafe { output_eigenvalues.set_len(n_channels) };
@@ -531,7 +534,7 @@ pub fn get_window(window_function: WindowOperations, window_len: usize) -> Resul
             output.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_window"))?;
 
     unsafe { output.set_len(window_len) };
     Ok(output)
@@ -550,7 +553,7 @@ pub fn perform_fft(data: &mut [f64], window_function: WindowOperations) -> Resul
             output_im.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_fft"))?;
 
     unsafe { output_re.set_len(data.len() / 2 + 1) };
     unsafe { output_im.set_len(data.len() / 2 + 1) };
@@ -575,7 +578,7 @@ pub fn perform_ifft(data: &[Complex64], original_data_len: usize) -> Result<Vec<
             restored_data.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_ifft"))?;
 
     unsafe { restored_data.set_len(original_data_len) };
     Ok(restored_data)
@@ -590,7 +593,7 @@ pub fn detrend(data: &mut [f64], detrend_operation: DetrendOperations) -> Result
             detrend_operation as c_int,
         )
     };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::data_handler("detrend"))
 }
 
 /// Data struct for output of PSD calculations.
@@ -619,7 +622,7 @@ pub fn get_psd(
             frequency.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_psd"))?;
 
     unsafe { amplitude.set_len(data.len() / 2 + 1) };
     unsafe { frequency.set_len(data.len() / 2 + 1) };
@@ -651,7 +654,7 @@ pub fn get_psd_welch(
             frequency.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_psd_welch"))?;
 
     unsafe { amplitude.set_len(nfft / 2 + 1) };
     unsafe { frequency.set_len(nfft / 2 + 1) };
@@ -703,7 +706,7 @@ pub fn perform_ica_select_channels(
             temp_s.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("perform_ica"))?;
     //let w = Array2::from_shape_vec((num_components, num_components), temp_w);
     //let k = Array2::from_shape_vec((num_components, rows), temp_k);
     //let a = Array2::from_shape_vec((rows, num_components), temp_a);
@@ -760,7 +763,7 @@ pub fn get_custom_band_powers(
             stddev_band_powers.as_mut_ptr() as *mut c_double,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_custom_band_powers"))?;
 
     unsafe { avg_band_powers.set_len(x.len()) };
     unsafe { stddev_band_powers.set_len(x.len()) };
@@ -796,7 +799,7 @@ pub fn get_band_power(psd: &mut Psd, band: Band) -> Result<f64> {
             &mut band_power,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_band_power"))?;
     Ok(band_power)
 }
 
@@ -804,7 +807,7 @@ pub fn get_band_power(psd: &mut Psd, band: Band) -> Result<f64> {
 pub fn get_nearest_power_of_two(value: usize) -> Result<usize> {
     let mut output = 0;
     let res = unsafe { data_handler::get_nearest_power_of_two(value as c_int, &mut output) };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_nearest_power_of_two"))?;
     Ok(output as usize)
 }
 
@@ -814,7 +817,7 @@ pub fn read_file<S: AsRef<str>>(file_name: S) -> Result<Array2<f64>> {
     let mut num_elements = 0;
     let res =
         unsafe { data_handler::get_num_elements_in_file(file_name.as_ptr(), &mut num_elements) };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_num_elements_in_file"))?;
 
     let mut data = Vec::with_capacity(num_elements as usize);
     let mut rows = 0;
@@ -828,7 +831,7 @@ pub fn read_file<S: AsRef<str>>(file_name: S) -> Result<Array2<f64>> {
             num_elements as c_int,
         )
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("read_file"))?;
 
     unsafe { data.set_len(num_elements as usize) };
     let data = ArrayBase::from_vec(data);
@@ -856,7 +859,7 @@ where
             file_mode.as_ptr(),
         )
     };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::data_handler("write_file"))
 }
 
 /// Get DataFilter version.
@@ -869,7 +872,7 @@ pub fn get_version() -> Result<String> {
         let response = CStr::from_ptr(result_char_buffer.as_mut_ptr());
         (res, response)
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::data_handler("get_version_data_handler"))?;
 
     Ok(response.to_str()?.to_string())
 }
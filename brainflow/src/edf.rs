@@ -0,0 +1,1219 @@
+use getset::Getters;
+use ndarray::Array2;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Seek, SeekFrom, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{board_description::BoardDescription, catalogue::Capability, error::Error, Result};
+
+/// Offset of the number of data records in the header.
+const NUM_RECORDS_OFFSET: u64 = 236;
+/// Bytes reserved in each data record for the time keeping annotation.
+const TIME_KEEPING_BYTES: usize = 20;
+/// Bytes reserved in each data record for one marker annotation.
+const MARKER_BYTES: usize = 32;
+
+/// EDF+ with 16 bit or BDF+ with 24 bit samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdfFormat {
+    Edf,
+    Bdf,
+}
+
+impl EdfFormat {
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            EdfFormat::Edf => 2,
+            EdfFormat::Bdf => 3,
+        }
+    }
+
+    fn digital_range(self) -> (i32, i32) {
+        match self {
+            EdfFormat::Edf => (-32768, 32767),
+            EdfFormat::Bdf => (-8388608, 8388607),
+        }
+    }
+
+    fn annotations_label(self) -> &'static str {
+        match self {
+            EdfFormat::Edf => "EDF Annotations",
+            EdfFormat::Bdf => "BDF Annotations",
+        }
+    }
+}
+
+/// Description of one signal in an EDF file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdfSignal {
+    /// At most 16 characters, e.g. `EEG Fp1`.
+    pub label: String,
+    pub transducer: String,
+    /// Unit, e.g. `uV`.
+    pub physical_dimension: String,
+    /// Smallest value which can be stored, [EdfWriter::write] rejects smaller values.
+    pub physical_min: f64,
+    /// Largest value which can be stored, [EdfWriter::write] rejects larger values.
+    pub physical_max: f64,
+    pub prefiltering: String,
+}
+
+impl EdfSignal {
+    /// Signal with the given label, unit and range.
+    pub fn new<S: Into<String>>(label: S, physical_dimension: S, physical_min: f64, physical_max: f64) -> Self {
+        Self {
+            label: label.into(),
+            transducer: String::new(),
+            physical_dimension: physical_dimension.into(),
+            physical_min,
+            physical_max,
+            prefiltering: String::new(),
+        }
+    }
+
+    /// Signal labeled `label` with the unit of a kind of channel and a range covering the values the boards
+    /// report for it.
+    fn for_capability(capability: Capability, label: String) -> Self {
+        let (min, max) = capability.value_range();
+        Self::new(label, capability.unit().to_string(), min, max)
+    }
+}
+
+/// Header of an EDF+ or BDF+ file written from board data.
+///
+/// Every data record holds one second of data and the markers of that second as annotations.
+#[derive(Debug, Clone)]
+pub struct EdfHeader {
+    format: EdfFormat,
+    patient: String,
+    recording: String,
+    start_time: Option<SystemTime>,
+    sampling_rate: usize,
+    num_rows: usize,
+    /// Row of the board data of each signal.
+    rows: Vec<usize>,
+    signals: Vec<EdfSignal>,
+    timestamp_channel: Option<usize>,
+    marker_channel: Option<usize>,
+    max_markers_per_record: usize,
+}
+
+impl EdfHeader {
+    /// Header with one signal per data row of `descr`, labeled with the EEG names of the board.
+    ///
+    /// Timestamp, marker and package rows are not written as signals, markers become annotations.
+    /// The start time is taken from the first timestamp unless set with [EdfHeader::start_time].
+    ///
+    /// Physical ranges cover the values the boards report for the kind of signal, e.g. ±187500 uV for EEG,
+    /// which is about 5.7 uV per step in 16 bit EDF. Use [EdfHeader::fit_ranges] or [EdfHeader::signal]
+    /// for a finer range, e.g. when writing during a session.
+    pub fn from_description(format: EdfFormat, descr: &BoardDescription) -> Self {
+        let mut rows = Vec::new();
+        let mut signals = Vec::new();
+        for (row, capability, label) in Capability::labeled_rows(descr) {
+            // EDF+ labels start with the type of signal
+            let label = match capability {
+                Capability::Eeg if !label.starts_with("EEG ") => format!("EEG {}", label),
+                _ => label,
+            };
+            rows.push(row);
+            signals.push(EdfSignal::for_capability(capability, label));
+        }
+        Self {
+            format,
+            patient: "X X X X".to_string(),
+            recording: String::new(),
+            start_time: None,
+            sampling_rate: *descr.sampling_rate(),
+            num_rows: *descr.num_rows(),
+            rows,
+            signals,
+            timestamp_channel: *descr.timestamp_channel(),
+            marker_channel: *descr.marker_channel(),
+            max_markers_per_record: 8,
+        }
+    }
+
+    /// EDF+ patient identification, `X X X X` (code, sex, birthdate and name unknown) by default.
+    pub fn patient<S: Into<String>>(mut self, patient: S) -> Self {
+        self.patient = patient.into();
+        self
+    }
+
+    /// Recording identification after the start date, e.g. `PSG-1234/2024 NN Cyton`.
+    pub fn recording<S: Into<String>>(mut self, recording: S) -> Self {
+        self.recording = recording.into();
+        self
+    }
+
+    /// Start of the recording, in UTC.
+    pub fn start_time(mut self, start_time: SystemTime) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Number of markers which fit into one data record, 8 by default.
+    ///
+    /// Further markers are written to the following records with their original onset.
+    pub fn max_markers_per_record(mut self, max_markers_per_record: usize) -> Self {
+        self.max_markers_per_record = max_markers_per_record;
+        self
+    }
+
+    /// Replace the signal of board data row `row`, or add it if the row has no signal yet.
+    pub fn signal(mut self, row: usize, signal: EdfSignal) -> Self {
+        match self.rows.iter().position(|r| *r == row) {
+            Some(i) => self.signals[i] = signal,
+            None => {
+                self.rows.push(row);
+                self.signals.push(signal);
+            }
+        }
+        self
+    }
+
+    /// Set the physical range of every signal to the range of its values in `data`, for the best resolution
+    /// when all data is known in advance.
+    pub fn fit_ranges(mut self, data: &Array2<f64>) -> Self {
+        for (row, signal) in self.rows.iter().zip(&mut self.signals) {
+            if *row >= data.nrows() {
+                continue;
+            }
+            let values = data.row(*row);
+            let finite = values.iter().filter(|v| v.is_finite());
+            let min = finite.clone().fold(f64::INFINITY, |a, b| a.min(*b));
+            let max = finite.fold(f64::NEG_INFINITY, |a, b| a.max(*b));
+            if min > max {
+                continue;
+            }
+            // a range is required, flat signals get one around their value
+            let (min, max) = if min == max { (min - 1.0, max + 1.0) } else { (min, max) };
+            signal.physical_min = min;
+            signal.physical_max = max;
+        }
+        self
+    }
+
+    /// Signals written to the file, in file order.
+    pub fn signals(&self) -> &[EdfSignal] {
+        &self.signals
+    }
+
+    /// Fraction of a second of the start time.
+    fn start_offset(&self) -> f64 {
+        let start_time = self.start_time.unwrap_or(UNIX_EPOCH);
+        start_time.duration_since(UNIX_EPOCH).map(|d| d.subsec_micros() as f64 / 1e6).unwrap_or(0.0)
+    }
+
+    fn annotation_samples(&self) -> usize {
+        let bytes = TIME_KEEPING_BYTES + self.max_markers_per_record * MARKER_BYTES;
+        bytes.div_ceil(self.format.bytes_per_sample())
+    }
+
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.sampling_rate == 0 {
+            return Err(Error::InvalidEdf("sampling rate must not be 0".to_string()));
+        }
+        if let Some(row) = self.rows.iter().find(|r| **r >= self.num_rows) {
+            return Err(Error::InvalidEdf(format!("row {} is out of the {} board data rows", row, self.num_rows)));
+        }
+        for signal in &self.signals {
+            if signal.label.len() > 16 || !signal.label.is_ascii() {
+                return Err(Error::InvalidEdf(format!("label `{}` is not 16 ascii characters", signal.label)));
+            }
+            let (min, max) = (signal.physical_min, signal.physical_max);
+            if min.is_nan() || max.is_nan() || min >= max {
+                return Err(Error::InvalidEdf(format!("{} has an empty physical range", signal.label)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A signal with the conversion to digital values.
+struct SignalWriter {
+    row: usize,
+    physical_min: f64,
+    scale: f64,
+}
+
+/// Writes board data to an EDF+ or BDF+ file, one second per data record.
+///
+/// Data can be written in chunks of any size, e.g. from [crate::board_shim::BoardShim::get_board_data]
+/// during a session. An incomplete last record is padded with zeros.
+pub struct EdfWriter<W: Write + Seek> {
+    writer: W,
+    header: EdfHeader,
+    signals: Vec<SignalWriter>,
+    /// Samples of the current record, one vector per signal.
+    pending: Vec<Vec<f64>>,
+    /// Marker annotations not written yet, as onset in samples and value.
+    markers: Vec<(usize, f64)>,
+    num_samples: usize,
+    num_records: usize,
+}
+
+impl EdfWriter<BufWriter<File>> {
+    /// Create the file at `path` and write the header.
+    pub fn create<P: AsRef<Path>>(path: P, header: EdfHeader) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), header)
+    }
+}
+
+impl<W: Write + Seek> EdfWriter<W> {
+    /// Write the header to `writer`.
+    ///
+    /// Without a start time, the header is written with the first data.
+    pub fn new(writer: W, mut header: EdfHeader) -> Result<Self> {
+        header.check()?;
+        let (digital_min, digital_max) = header.format.digital_range();
+        let signals = header
+            .rows
+            .iter()
+            .zip(&mut header.signals)
+            .map(|(row, signal)| {
+                // the stored range is what the header can represent
+                signal.physical_min = header_number(signal.physical_min, f64::floor).1;
+                signal.physical_max = header_number(signal.physical_max, f64::ceil).1;
+                SignalWriter {
+                    row: *row,
+                    physical_min: signal.physical_min,
+                    scale: (digital_max - digital_min) as f64 / (signal.physical_max - signal.physical_min),
+                }
+            })
+            .collect::<Vec<_>>();
+        let pending = vec![Vec::with_capacity(header.sampling_rate); signals.len()];
+        let mut edf = Self {
+            writer,
+            header,
+            signals,
+            pending,
+            markers: Vec::new(),
+            num_samples: 0,
+            num_records: 0,
+        };
+        if edf.header.start_time.is_some() {
+            edf.write_header()?;
+        }
+        Ok(edf)
+    }
+
+    /// Header of the file.
+    pub fn header(&self) -> &EdfHeader {
+        &self.header
+    }
+
+    /// Append board data with one sample per column and the rows of the board description.
+    ///
+    /// Nothing is written if a value is out of the physical range of its signal.
+    pub fn write(&mut self, data: &Array2<f64>) -> Result<()> {
+        if data.nrows() != self.header.num_rows {
+            return Err(Error::InvalidEdf(format!(
+                "board data has {} rows instead of {}",
+                data.nrows(),
+                self.header.num_rows
+            )));
+        }
+        if data.ncols() == 0 {
+            return Ok(());
+        }
+        for (row, signal) in self.header.rows.iter().zip(&self.header.signals) {
+            let (min, max) = (signal.physical_min, signal.physical_max);
+            if let Some(value) = data.row(*row).iter().find(|v| **v < min || **v > max) {
+                return Err(Error::InvalidEdf(format!(
+                    "{} {} of {} is out of the physical range {} to {}",
+                    value, signal.physical_dimension, signal.label, min, max
+                )));
+            }
+        }
+        if self.header.start_time.is_none() {
+            let first_timestamp = self.header.timestamp_channel.map(|c| data[[c, 0]]);
+            let start_time = first_timestamp
+                .filter(|t| *t > 0.0)
+                .and_then(|t| Duration::try_from_secs_f64(t).ok())
+                .and_then(|t| UNIX_EPOCH.checked_add(t));
+            self.header.start_time = Some(start_time.unwrap_or_else(SystemTime::now));
+            self.write_header()?;
+        }
+        for column in data.columns() {
+            if let Some(marker) = self.header.marker_channel.map(|c| column[c]).filter(|m| *m != 0.0) {
+                self.markers.push((self.num_samples, marker));
+            }
+            for (signal, pending) in self.signals.iter().zip(&mut self.pending) {
+                pending.push(column[signal.row]);
+            }
+            self.num_samples += 1;
+            if self.pending[0].len() == self.header.sampling_rate {
+                self.write_record()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Pad and write the last record, write the number of records to the header and return the writer.
+    pub fn finish(mut self) -> Result<W> {
+        if self.header.start_time.is_none() {
+            self.header.start_time = Some(SystemTime::now());
+            self.write_header()?;
+        }
+        while self.pending.first().is_some_and(|p| !p.is_empty()) || !self.markers.is_empty() {
+            let sampling_rate = self.header.sampling_rate;
+            for pending in &mut self.pending {
+                pending.resize(sampling_rate, 0.0);
+            }
+            self.write_record()?;
+        }
+        self.writer.seek(SeekFrom::Start(NUM_RECORDS_OFFSET))?;
+        write_field(&mut self.writer, &self.num_records.to_string(), 8)?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        let header = &self.header;
+        let format = header.format;
+        let (year, month, day, hours, minutes, seconds) = utc_date_time(header.start_time.unwrap_or(UNIX_EPOCH));
+        let num_signals = header.signals.len() + 1;
+        let w = &mut self.writer;
+
+        match format {
+            EdfFormat::Edf => write_field(w, "0", 8)?,
+            EdfFormat::Bdf => {
+                w.write_all(&[0xff])?;
+                write_field(w, "BIOSEMI", 7)?;
+            }
+        }
+        write_field(w, &header.patient, 80)?;
+        const MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+        let recording = match header.recording.as_str() {
+            "" => "X X X".to_string(),
+            recording => recording.to_string(),
+        };
+        let recording = format!("Startdate {:02}-{}-{} {}", day, MONTHS[month as usize - 1], year, recording);
+        write_field(w, &recording, 80)?;
+        write_field(w, &format!("{:02}.{:02}.{:02}", day, month, year % 100), 8)?;
+        write_field(w, &format!("{:02}.{:02}.{:02}", hours, minutes, seconds), 8)?;
+        write_field(w, &(256 * (num_signals + 1)).to_string(), 8)?;
+        let reserved = match format {
+            EdfFormat::Edf => "EDF+C",
+            EdfFormat::Bdf => "BDF+C",
+        };
+        write_field(w, reserved, 44)?;
+        write_field(w, "-1", 8)?;
+        write_field(w, "1", 8)?;
+        write_field(w, &num_signals.to_string(), 4)?;
+
+        let (digital_min, digital_max) = format.digital_range();
+        let annotations = EdfSignal::new(format.annotations_label(), "", -1.0, 1.0);
+        let signals = header.signals.iter().chain(std::iter::once(&annotations)).collect::<Vec<_>>();
+        for signal in &signals {
+            write_field(w, &signal.label, 16)?;
+        }
+        for signal in &signals {
+            write_field(w, &signal.transducer, 80)?;
+        }
+        for signal in &signals {
+            write_field(w, &signal.physical_dimension, 8)?;
+        }
+        for signal in &signals {
+            write_field(w, &header_number(signal.physical_min, f64::floor).0, 8)?;
+        }
+        for signal in &signals {
+            write_field(w, &header_number(signal.physical_max, f64::ceil).0, 8)?;
+        }
+        for _ in &signals {
+            write_field(w, &digital_min.to_string(), 8)?;
+        }
+        for _ in &signals {
+            write_field(w, &digital_max.to_string(), 8)?;
+        }
+        for signal in &signals {
+            write_field(w, &signal.prefiltering, 80)?;
+        }
+        for _ in &header.signals {
+            write_field(w, &header.sampling_rate.to_string(), 8)?;
+        }
+        write_field(w, &header.annotation_samples().to_string(), 8)?;
+        for _ in &signals {
+            write_field(w, "", 32)?;
+        }
+        Ok(())
+    }
+
+    fn write_record(&mut self) -> Result<()> {
+        let format = self.header.format;
+        let (digital_min, digital_max) = format.digital_range();
+        let mut record = Vec::new();
+        for (signal, pending) in self.signals.iter().zip(&mut self.pending) {
+            for value in pending.drain(..) {
+                let digital = if value.is_nan() {
+                    0
+                } else {
+                    let digital = digital_min as f64 + (value - signal.physical_min) * signal.scale;
+                    digital.round().clamp(digital_min as f64, digital_max as f64) as i32
+                };
+                record.extend_from_slice(&digital.to_le_bytes()[..format.bytes_per_sample()]);
+            }
+        }
+
+        let annotation_bytes = self.header.annotation_samples() * format.bytes_per_sample();
+        // onsets are relative to the start time in the header, which has no fractions of a second
+        let start_offset = self.header.start_offset();
+        let record_onset = trim_number(format!("{:.6}", self.num_records as f64 + start_offset));
+        let mut annotations = format!("+{}\x14\x14\0", record_onset).into_bytes();
+        let mut num_markers = 0;
+        while let Some((onset, value)) = self.markers.first() {
+            if num_markers == self.header.max_markers_per_record {
+                break;
+            }
+            let onset = start_offset + *onset as f64 / self.header.sampling_rate as f64;
+            let tal = format!("+{}\x14{}\x14\0", trim_number(format!("{:.6}", onset)), value);
+            if annotations.len() + tal.len() > annotation_bytes {
+                if num_markers == 0 {
+                    return Err(Error::InvalidEdf(format!("marker {} does not fit into a data record", value)));
+                }
+                // written to the next record
+                break;
+            }
+            annotations.extend_from_slice(tal.as_bytes());
+            self.markers.remove(0);
+            num_markers += 1;
+        }
+        annotations.resize(annotation_bytes, 0);
+        record.extend_from_slice(&annotations);
+
+        self.writer.write_all(&record)?;
+        self.num_records += 1;
+        Ok(())
+    }
+}
+
+/// Write board data to an EDF+ or BDF+ file, with the physical ranges fitted to the data.
+pub fn write_file<P: AsRef<Path>>(
+    path: P,
+    format: EdfFormat,
+    data: &Array2<f64>,
+    descr: &BoardDescription,
+) -> Result<()> {
+    let header = EdfHeader::from_description(format, descr).fit_ranges(data);
+    let mut writer = EdfWriter::create(path, header)?;
+    writer.write(data)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// A signal of a read EDF file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdfChannel {
+    pub signal: EdfSignal,
+    pub digital_min: i32,
+    pub digital_max: i32,
+    /// Samples per second.
+    pub sampling_rate: f64,
+}
+
+/// An annotation of an EDF+ or BDF+ file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdfAnnotation {
+    /// Seconds since the start time of the recording.
+    pub onset: f64,
+    pub duration: Option<f64>,
+    pub text: String,
+}
+
+impl EdfAnnotation {
+    /// The marker value of the annotation, if the text is a number other than 0.
+    pub fn marker(&self) -> Option<f64> {
+        self.text.trim().parse::<f64>().ok().filter(|v| *v != 0.0 && v.is_finite())
+    }
+}
+
+/// Contents of an EDF, EDF+, BDF or BDF+ file, see [read_file].
+///
+/// Records of discontinuous EDF+D and BDF+D files are joined without gaps.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct EdfRecording {
+    format: EdfFormat,
+    /// Whether the file is EDF+ or BDF+.
+    plus: bool,
+    patient: String,
+    recording: String,
+    /// Start of the recording, read as UTC.
+    start_time: SystemTime,
+    /// Seconds per data record.
+    record_duration: f64,
+    /// Signals except the annotations.
+    channels: Vec<EdfChannel>,
+    #[getset(skip)]
+    samples: Vec<Vec<f64>>,
+    annotations: Vec<EdfAnnotation>,
+}
+
+impl EdfRecording {
+    /// Parse the content of a file.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let layout = EdfLayout::parse(bytes)?;
+        let mut samples = layout
+            .channel_signals
+            .iter()
+            .map(|signal| Vec::with_capacity(layout.num_records * layout.samples_per_record[*signal]))
+            .collect::<Vec<Vec<f64>>>();
+        let mut annotations = Vec::new();
+        let mut start_offset = None;
+        for record in 0..layout.num_records {
+            for (signal, channel) in layout.signals.iter().enumerate() {
+                let signal_bytes = layout.signal_bytes(bytes, record, signal);
+                match channel {
+                    Some(c) => samples[*c].extend(
+                        signal_bytes
+                            .chunks_exact(layout.format.bytes_per_sample())
+                            .map(|sample| layout.physical(*c, sample)),
+                    ),
+                    None => {
+                        let (record_onset, record_annotations) = parse_annotations(signal_bytes)?;
+                        if start_offset.is_none() {
+                            start_offset = record_onset;
+                        }
+                        annotations.extend(record_annotations);
+                    }
+                }
+            }
+        }
+
+        // the first time keeping annotation holds the fraction of a second of the start time
+        let start_offset = start_offset.unwrap_or(0.0);
+        let start_time = Duration::try_from_secs_f64(start_offset.max(0.0))
+            .ok()
+            .and_then(|offset| layout.start_time.checked_add(offset))
+            .ok_or_else(|| Error::InvalidEdf(format!("record onset {} is out of range", start_offset)))?;
+        for annotation in &mut annotations {
+            annotation.onset -= start_offset;
+        }
+
+        Ok(Self {
+            format: layout.format,
+            plus: layout.plus,
+            patient: layout.patient,
+            recording: layout.recording,
+            start_time,
+            record_duration: layout.record_duration,
+            channels: layout.channels,
+            samples,
+            annotations,
+        })
+    }
+
+    /// Highest sampling rate of all channels.
+    pub fn sampling_rate(&self) -> f64 {
+        self.channels.iter().map(|c| c.sampling_rate).fold(0.0, f64::max)
+    }
+
+    /// Samples of channel `index` in its own sampling rate.
+    pub fn channel_samples(&self, index: usize) -> Option<&[f64]> {
+        self.samples.get(index).map(|s| s.as_slice())
+    }
+
+    /// Index of the channel with label `label`.
+    pub fn channel_index(&self, label: &str) -> Option<usize> {
+        self.channels.iter().position(|c| c.signal.label == label)
+    }
+
+    /// One row per channel in [EdfRecording::sampling_rate], samples of channels with a lower rate are repeated.
+    pub fn data(&self) -> Array2<f64> {
+        let sampling_rate = self.sampling_rate();
+        let num_samples = self
+            .channels
+            .iter()
+            .zip(&self.samples)
+            .filter(|(c, _)| c.sampling_rate > 0.0)
+            .map(|(c, s)| (s.len() as f64 * sampling_rate / c.sampling_rate).round() as usize)
+            .max()
+            .unwrap_or(0);
+        let mut data = Array2::zeros((self.channels.len(), num_samples));
+        for (row, (channel, samples)) in self.channels.iter().zip(&self.samples).enumerate() {
+            let ratio = channel.sampling_rate / sampling_rate;
+            for col in 0..num_samples {
+                let index = ((col as f64 * ratio) as usize).min(samples.len().saturating_sub(1));
+                data[[row, col]] = samples.get(index).copied().unwrap_or(0.0);
+            }
+        }
+        data
+    }
+
+    /// Annotations with a number as text as marker events, given as sample in [EdfRecording::sampling_rate]
+    /// and value, like the non-zero values of a marker channel.
+    pub fn events(&self) -> Vec<(usize, f64)> {
+        let sampling_rate = self.sampling_rate();
+        self.annotations
+            .iter()
+            .filter_map(|a| a.marker().map(|marker| ((a.onset * sampling_rate).round().max(0.0) as usize, marker)))
+            .collect()
+    }
+
+    /// Rebuild board data with the layout the file was written with, see [EdfHeader].
+    ///
+    /// Signals are found by label. The marker row is filled from [EdfRecording::events] and the
+    /// timestamp row from the start time, other rows of the board stay 0.
+    pub fn to_board_data(&self, header: &EdfHeader) -> Result<Array2<f64>> {
+        if (self.sampling_rate() - header.sampling_rate as f64).abs() > f64::EPSILON {
+            return Err(Error::InvalidEdf(format!(
+                "sampling rate {} does not match {}",
+                self.sampling_rate(),
+                header.sampling_rate
+            )));
+        }
+        let data = self.data();
+        let mut board_data = Array2::zeros((header.num_rows, data.ncols()));
+        for (row, signal) in header.rows.iter().zip(&header.signals) {
+            match self.channel_index(&signal.label) {
+                Some(index) => board_data.row_mut(*row).assign(&data.row(index)),
+                None => return Err(Error::InvalidEdf(format!("no signal with label {}", signal.label))),
+            }
+        }
+        if let Some(channel) = header.marker_channel {
+            for (sample, value) in self.events() {
+                if sample < board_data.ncols() {
+                    board_data[[channel, sample]] = value;
+                }
+            }
+        }
+        if let Some(channel) = header.timestamp_channel {
+            let start = self.start_time.duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+            for (i, timestamp) in board_data.row_mut(channel).iter_mut().enumerate() {
+                *timestamp = start + i as f64 / header.sampling_rate as f64;
+            }
+        }
+        Ok(board_data)
+    }
+}
+
+/// Header of an EDF or BDF file and where its data records are, to decode samples without reading
+/// the whole file.
+pub(crate) struct EdfLayout {
+    format: EdfFormat,
+    plus: bool,
+    patient: String,
+    recording: String,
+    /// Start time of the header fields, without the fraction of the time keeping annotation.
+    start_time: SystemTime,
+    record_duration: f64,
+    pub(crate) channels: Vec<EdfChannel>,
+    /// Index of each signal among the channels, `None` for annotation signals.
+    signals: Vec<Option<usize>>,
+    /// Index of each channel among the signals.
+    channel_signals: Vec<usize>,
+    samples_per_record: Vec<usize>,
+    /// Offset of each signal in a data record.
+    signal_offsets: Vec<usize>,
+    header_bytes: usize,
+    record_bytes: usize,
+    /// Complete data records in the file.
+    pub(crate) num_records: usize,
+}
+
+impl EdfLayout {
+    /// Parse the header of a file, `bytes` must hold the whole file to count its data records.
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidEdf(reason.to_string());
+        if bytes.len() < 256 {
+            return Err(invalid("file is shorter than the header"));
+        }
+        let format = if bytes[0] == 0xff && &bytes[1..8] == b"BIOSEMI" {
+            EdfFormat::Bdf
+        } else if text(&bytes[..8]) == "0" {
+            EdfFormat::Edf
+        } else {
+            return Err(invalid("unknown version"));
+        };
+        let patient = text(&bytes[8..88]);
+        let recording = text(&bytes[88..168]);
+        let reserved = text(&bytes[192..236]);
+        let plus = reserved.starts_with("EDF+") || reserved.starts_with("BDF+");
+        let header_bytes = number::<usize>(&bytes[184..192], "header bytes")?;
+        let num_records = number::<i64>(&bytes[236..244], "number of data records")?;
+        let record_duration = number::<f64>(&bytes[244..252], "duration of a data record")?;
+        let num_signals = number::<usize>(&bytes[252..256], "number of signals")?;
+        if header_bytes != 256 * (num_signals + 1) || bytes.len() < header_bytes {
+            return Err(invalid("header size does not match the number of signals"));
+        }
+        let start_time = start_time(&bytes[168..176], &bytes[176..184], &recording)?;
+
+        let fields = |offset: usize, width: usize| -> Vec<&[u8]> {
+            let start = 256 + offset * num_signals;
+            (0..num_signals).map(|i| &bytes[start + i * width..start + (i + 1) * width]).collect()
+        };
+        let labels = fields(0, 16);
+        let transducers = fields(16, 80);
+        let dimensions = fields(96, 8);
+        let physical_mins = fields(104, 8);
+        let physical_maxs = fields(112, 8);
+        let digital_mins = fields(120, 8);
+        let digital_maxs = fields(128, 8);
+        let prefilterings = fields(136, 80);
+        let samples_per_record = fields(216, 8)
+            .into_iter()
+            .map(|f| number::<usize>(f, "samples per data record"))
+            .collect::<Result<Vec<_>>>()?;
+
+        let annotations_label = format.annotations_label();
+        let mut channels = Vec::new();
+        let mut signals = Vec::new();
+        let mut channel_signals = Vec::new();
+        for i in 0..num_signals {
+            let label = text(labels[i]);
+            if plus && label == annotations_label {
+                signals.push(None);
+                continue;
+            }
+            let digital_min = number::<i32>(digital_mins[i], "digital minimum")?;
+            let digital_max = number::<i32>(digital_maxs[i], "digital maximum")?;
+            if digital_min >= digital_max {
+                return Err(Error::InvalidEdf(format!("{} has an empty digital range", label)));
+            }
+            signals.push(Some(channels.len()));
+            channel_signals.push(i);
+            channels.push(EdfChannel {
+                signal: EdfSignal {
+                    label,
+                    transducer: text(transducers[i]),
+                    physical_dimension: text(dimensions[i]),
+                    physical_min: number(physical_mins[i], "physical minimum")?,
+                    physical_max: number(physical_maxs[i], "physical maximum")?,
+                    prefiltering: text(prefilterings[i]),
+                },
+                digital_min,
+                digital_max,
+                sampling_rate: if record_duration > 0.0 {
+                    samples_per_record[i] as f64 / record_duration
+                } else {
+                    0.0
+                },
+            });
+        }
+
+        let bytes_per_sample = format.bytes_per_sample();
+        let signal_offsets = samples_per_record
+            .iter()
+            .scan(0, |offset, n| {
+                let start = *offset;
+                *offset += n * bytes_per_sample;
+                Some(start)
+            })
+            .collect();
+        let record_bytes = samples_per_record.iter().sum::<usize>() * bytes_per_sample;
+        let data_bytes = bytes.len() - header_bytes;
+        let num_records = match (num_records, record_bytes) {
+            (_, 0) => 0,
+            // the number of records is only written when the recording is complete
+            (-1, _) => data_bytes / record_bytes,
+            (n, _) if n >= 0 && n as usize * record_bytes <= data_bytes => n as usize,
+            _ => return Err(invalid("file is shorter than its data records")),
+        };
+        Ok(Self {
+            format,
+            plus,
+            patient,
+            recording,
+            start_time,
+            record_duration,
+            channels,
+            signals,
+            channel_signals,
+            samples_per_record,
+            signal_offsets,
+            header_bytes,
+            record_bytes,
+            num_records,
+        })
+    }
+
+    /// Bytes of `signal` in data record `record`.
+    fn signal_bytes<'a>(&self, bytes: &'a [u8], record: usize, signal: usize) -> &'a [u8] {
+        let start = self.header_bytes + record * self.record_bytes + self.signal_offsets[signal];
+        &bytes[start..start + self.samples_per_record[signal] * self.format.bytes_per_sample()]
+    }
+
+    /// Physical value of the digital `sample` of `channel`.
+    fn physical(&self, channel: usize, sample: &[u8]) -> f64 {
+        let channel = &self.channels[channel];
+        let signal = &channel.signal;
+        let scale = (signal.physical_max - signal.physical_min) / (channel.digital_max - channel.digital_min) as f64;
+        let digital = match self.format {
+            EdfFormat::Edf => i16::from_le_bytes([sample[0], sample[1]]) as i32,
+            EdfFormat::Bdf => i32::from_le_bytes([0, sample[0], sample[1], sample[2]]) >> 8,
+        };
+        signal.physical_min + (digital - channel.digital_min) as f64 * scale
+    }
+
+    /// Highest samples per data record of all channels.
+    fn max_samples_per_record(&self) -> usize {
+        self.channel_signals.iter().map(|s| self.samples_per_record[*s]).max().unwrap_or(0)
+    }
+
+    /// Highest sampling rate of all channels, like [EdfRecording::sampling_rate].
+    pub(crate) fn sampling_rate(&self) -> f64 {
+        self.channels.iter().map(|c| c.sampling_rate).fold(0.0, f64::max)
+    }
+
+    /// Samples of the complete data records in the highest sampling rate.
+    pub(crate) fn num_samples(&self) -> usize {
+        self.num_records * self.max_samples_per_record()
+    }
+
+    /// Value of `channel` at `sample` of the highest sampling rate, samples of channels with a lower
+    /// rate are repeated like in [EdfRecording::data].
+    pub(crate) fn value(&self, bytes: &[u8], channel: usize, sample: usize) -> f64 {
+        let signal = self.channel_signals[channel];
+        let samples_per_record = self.samples_per_record[signal];
+        if samples_per_record == 0 {
+            return 0.0;
+        }
+        let index = sample * samples_per_record / self.max_samples_per_record();
+        let signal_bytes = self.signal_bytes(bytes, index / samples_per_record, signal);
+        let start = index % samples_per_record * self.format.bytes_per_sample();
+        self.physical(channel, &signal_bytes[start..])
+    }
+}
+
+/// Read an EDF, EDF+, BDF or BDF+ file.
+///
+/// The whole file is loaded, [crate::recording_reader::RecordingReader] reads the samples of long
+/// recordings on demand.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<EdfRecording> {
+    EdfRecording::parse(&fs::read(path)?)
+}
+
+/// Parse the TALs of an annotation signal, returns the onset of the time keeping annotation and the others.
+fn parse_annotations(bytes: &[u8]) -> Result<(Option<f64>, Vec<EdfAnnotation>)> {
+    let mut record_onset = None;
+    let mut annotations = Vec::new();
+    for tal in bytes.split(|b| *b == 0).filter(|tal| !tal.is_empty()) {
+        let tal = String::from_utf8_lossy(tal);
+        let mut parts = tal.split('\x14');
+        let timing = parts.next().unwrap_or_default();
+        let (onset, duration) = match timing.split_once('\x15') {
+            Some((onset, duration)) => (onset, Some(duration)),
+            None => (timing, None),
+        };
+        // onsets become offsets of the start time, so infinite ones are as invalid as unparsable ones
+        let onset = onset
+            .parse::<f64>()
+            .ok()
+            .filter(|o| o.is_finite())
+            .ok_or_else(|| Error::InvalidEdf(format!("invalid annotation onset `{}`", onset)))?;
+        let duration = duration.and_then(|d| d.parse::<f64>().ok()).filter(|d| d.is_finite());
+        let texts = parts.filter(|t| !t.is_empty()).collect::<Vec<_>>();
+        if texts.is_empty() {
+            record_onset = record_onset.or(Some(onset));
+        }
+        annotations.extend(texts.into_iter().map(|text| EdfAnnotation {
+            onset,
+            duration,
+            text: text.to_string(),
+        }));
+    }
+    Ok((record_onset, annotations))
+}
+
+/// A header field without the padding.
+fn text(field: &[u8]) -> String {
+    String::from_utf8_lossy(field).trim().to_string()
+}
+
+/// A number in a header field.
+fn number<T: std::str::FromStr>(field: &[u8], name: &str) -> Result<T> {
+    let value = text(field);
+    value.parse().map_err(|_| Error::InvalidEdf(format!("invalid {} `{}`", name, value)))
+}
+
+/// Start time from the `dd.mm.yy` and `hh.mm.ss` header fields, with the four digit year of the EDF+
+/// recording field if it has one.
+fn start_time(date: &[u8], time: &[u8], recording: &str) -> Result<SystemTime> {
+    let parts = |field: &[u8], name: &str| -> Result<Vec<u32>> {
+        let parts = text(field).split('.').map(|p| p.parse::<u32>()).collect::<std::result::Result<Vec<_>, _>>();
+        match parts {
+            Ok(parts) if parts.len() == 3 => Ok(parts),
+            _ => Err(Error::InvalidEdf(format!("invalid start {} `{}`", name, text(field)))),
+        }
+    };
+    let date = parts(date, "date")?;
+    let time = parts(time, "time")?;
+    let year = recording
+        .strip_prefix("Startdate ")
+        .and_then(|r| r.get(7..11))
+        .and_then(|y| y.parse::<i64>().ok())
+        .unwrap_or(if date[2] >= 85 { 1900 + date[2] as i64 } else { 2000 + date[2] as i64 });
+    let days = days_from_civil(year, date[1], date[0]);
+    let secs = days * 86400 + (time[0] * 3600 + time[1] * 60 + time[2]) as i64;
+    Ok(UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64))
+}
+
+/// Write `value` left aligned and padded with spaces to `width` bytes.
+fn write_field<W: Write>(writer: &mut W, value: &str, width: usize) -> Result<()> {
+    let mut field = value.as_bytes()[..value.len().min(width)].to_vec();
+    field.resize(width, b' ');
+    writer.write_all(&field)?;
+    Ok(())
+}
+
+/// Format `value` for an 8 character header field with as many decimals as fit, rounded with `round`.
+/// Returns the text and the value it represents.
+fn header_number(value: f64, round: fn(f64) -> f64) -> (String, f64) {
+    for decimals in (0..=6).rev() {
+        let factor = 10f64.powi(decimals);
+        let rounded = round(value * factor) / factor;
+        let text = trim_number(format!("{:.*}", decimals as usize, rounded));
+        if text.len() <= 8 {
+            return (text, rounded);
+        }
+    }
+    // out of the range of the header, e.g. 1e9
+    let clamped = value.clamp(-9999999.0, 99999999.0);
+    (format!("{}", round(clamped)), round(clamped))
+}
+
+/// Remove trailing zeros after the decimal point.
+fn trim_number(text: String) -> String {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+/// Days since the unix epoch of a date, http://howardhinnant.github.io/date_algorithms.html
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Year, month, day, hours, minutes and seconds of `time` in UTC.
+pub(crate) fn utc_date_time(time: SystemTime) -> (i64, u32, u32, u32, u32, u32) {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let (days, secs_of_day) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    // civil from days, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    let secs_of_day = secs_of_day as u32;
+    (year, month, day, secs_of_day / 3600, secs_of_day / 60 % 60, secs_of_day % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+    use std::{
+        env, fs,
+        io::Cursor,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use super::{header_number, parse_annotations, utc_date_time, EdfFormat, EdfHeader, EdfRecording, EdfSignal, EdfWriter};
+    use crate::{board_description::BoardDescription, BoardIds, BrainFlowPresets};
+
+    fn field(bytes: &[u8], start: usize, width: usize) -> String {
+        String::from_utf8_lossy(&bytes[start..start + width]).trim().to_string()
+    }
+
+    #[test]
+    fn test_header_numbers() {
+        assert_eq!(("-187500".to_string(), -187500.0), header_number(-187500.0, f64::floor));
+        assert_eq!(("1.234568".to_string(), 1.234568), header_number(1.2345671, f64::ceil));
+        assert_eq!(("-12.3457".to_string(), -12.3457), header_number(-12.345678, f64::floor));
+        assert_eq!((2024, 2, 29, 13, 5, 9), utc_date_time(UNIX_EPOCH + Duration::from_secs(1709211909)));
+    }
+
+    #[test]
+    fn test_parse_annotations() {
+        let (record_onset, annotations) = parse_annotations(b"+1.5\x14\x14\x00+2\x150.5\x14S  1\x14\x00").unwrap();
+        assert_eq!(Some(1.5), record_onset);
+        assert_eq!(1, annotations.len());
+        assert_eq!((2.0, Some(0.5), "S  1"), (annotations[0].onset, annotations[0].duration, annotations[0].text.as_str()));
+        for onset in ["+inf", "-inf", "NaN", "+1e400", "x"].iter() {
+            let tal = format!("{}\x14\x14\x00", onset);
+            assert!(parse_annotations(tal.as_bytes()).is_err(), "{}", onset);
+        }
+    }
+
+    #[test]
+    fn test_write_records_and_annotations() {
+        let descr = BoardDescription::load(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        let sampling_rate = *descr.sampling_rate();
+        let marker_channel = descr.marker_channel().unwrap();
+        let timestamp_channel = descr.timestamp_channel().unwrap();
+        // within the physical ranges of all kinds of signals
+        let mut data = Array2::from_shape_fn((*descr.num_rows(), sampling_rate + 10), |(row, col)| {
+            ((row + col) % 10) as f64 / 10.0
+        });
+        data.row_mut(timestamp_channel).fill(1709211909.5);
+        data.row_mut(marker_channel).fill(0.0);
+        data[[marker_channel, 3]] = 2.0;
+        data[[marker_channel, sampling_rate + 5]] = 7.5;
+
+        let header = EdfHeader::from_description(EdfFormat::Edf, &descr)
+            .signal(0, EdfSignal::new("Package", "", 0.0, 255.0));
+        let num_signals = header.signals().len() + 1;
+        assert_eq!("EEG Fz", header.signals()[0].label);
+        let mut writer = EdfWriter::new(Cursor::new(Vec::new()), header).unwrap();
+        writer.write(&data.slice(ndarray::s![.., ..100]).to_owned()).unwrap();
+        let mut out_of_range = data.slice(ndarray::s![.., 100..]).to_owned();
+        out_of_range[[1, 0]] = 200000.0;
+        assert!(writer.write(&out_of_range).is_err());
+        writer.write(&data.slice(ndarray::s![.., 100..]).to_owned()).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let header_bytes = 256 * (num_signals + 1);
+        assert_eq!("0", field(&bytes, 0, 8));
+        assert!(field(&bytes, 88, 80).starts_with("Startdate 29-FEB-2024"));
+        assert_eq!("29.02.24", field(&bytes, 168, 8));
+        assert_eq!("13.05.09", field(&bytes, 176, 8));
+        assert_eq!(header_bytes.to_string(), field(&bytes, 184, 8));
+        assert_eq!("EDF+C", field(&bytes, 192, 44));
+        assert_eq!("2", field(&bytes, 236, 8));
+        assert_eq!(num_signals.to_string(), field(&bytes, 252, 4));
+        assert_eq!("EDF Annotations", field(&bytes, 256 + 16 * (num_signals - 1), 16));
+
+        let annotation_samples = (20 + 8 * 32) / 2;
+        let record_bytes = 2 * (sampling_rate * (num_signals - 1) + annotation_samples);
+        assert_eq!(header_bytes + 2 * record_bytes, bytes.len());
+        let first = String::from_utf8_lossy(&bytes[header_bytes + record_bytes - 2 * annotation_samples..]);
+        assert!(first.starts_with("+0.5\x14\x14\0+0.512\x142\x14\0"));
+        let second = String::from_utf8_lossy(&bytes[header_bytes + 2 * record_bytes - 2 * annotation_samples..]);
+        assert!(second.starts_with("+1.5\x14\x14\0+1.52\x147.5\x14\0"));
+    }
+
+    #[test]
+    fn test_write_file() {
+        let descr = BoardDescription::load(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        let mut data = Array2::from_shape_fn((*descr.num_rows(), 600), |(row, col)| (row * col) as f64);
+        data.row_mut(descr.marker_channel().unwrap()).fill(0.0);
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("edf_write_file.bdf");
+        super::write_file(&path, EdfFormat::Bdf, &data, &descr).unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(0xff, bytes[0]);
+        assert_eq!("BIOSEMI", field(&bytes, 1, 7));
+        assert_eq!("BDF+C", field(&bytes, 192, 44));
+        assert_eq!("3", field(&bytes, 236, 8));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let descr = BoardDescription::load(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        let sampling_rate = *descr.sampling_rate();
+        let marker_channel = descr.marker_channel().unwrap();
+        let timestamp_channel = descr.timestamp_channel().unwrap();
+        let mut data = Array2::from_shape_fn((*descr.num_rows(), 2 * sampling_rate), |(row, col)| {
+            ((row + col) as f64 / 10.0).sin() * 100.0
+        });
+        data.row_mut(marker_channel).fill(0.0);
+        data[[marker_channel, 10]] = 3.0;
+        data[[marker_channel, sampling_rate + 1]] = 4.0;
+        for (i, timestamp) in data.row_mut(timestamp_channel).iter_mut().enumerate() {
+            *timestamp = 1709211909.25 + i as f64 / sampling_rate as f64;
+        }
+
+        for format in [EdfFormat::Edf, EdfFormat::Bdf].iter() {
+            let header = EdfHeader::from_description(*format, &descr).fit_ranges(&data);
+            let mut writer = EdfWriter::new(Cursor::new(Vec::new()), header.clone()).unwrap();
+            writer.write(&data).unwrap();
+            let bytes = writer.finish().unwrap().into_inner();
+
+            let recording = EdfRecording::parse(&bytes).unwrap();
+            assert_eq!(*format, *recording.format());
+            assert!(*recording.plus());
+            assert_eq!(header.signals().len(), recording.channels().len());
+            assert_eq!(sampling_rate as f64, recording.sampling_rate());
+            assert_eq!(vec![(10, 3.0), (sampling_rate + 1, 4.0)], recording.events());
+
+            let read = recording.to_board_data(&header).unwrap();
+            assert_eq!(data.dim(), read.dim());
+            for (row, signal) in header.rows.iter().zip(header.signals()) {
+                let resolution = (signal.physical_max - signal.physical_min) / 65535.0;
+                for (a, b) in data.row(*row).iter().zip(read.row(*row)) {
+                    assert!((a - b).abs() <= resolution, "{} != {} in {}", a, b, signal.label);
+                }
+            }
+            assert_eq!(data.row(marker_channel), read.row(marker_channel));
+            for (a, b) in data.row(timestamp_channel).iter().zip(read.row(timestamp_channel)) {
+                assert!((a - b).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_mixed_sampling_rates() {
+        // EDF without annotations, 2 records of 1 second with signals at 4 and 2 Hz
+        let mut bytes = Vec::new();
+        let fields: &[(&str, usize)] = &[
+            ("0", 8),
+            ("patient", 80),
+            ("recording", 80),
+            ("01.02.03", 8),
+            ("04.05.06", 8),
+            ("768", 8),
+            ("", 44),
+            ("2", 8),
+            ("1", 8),
+            ("2", 4),
+        ];
+        let signals: &[(&str, usize)] = &[
+            ("Fast", 16),
+            ("Slow", 16),
+            ("", 80),
+            ("", 80),
+            ("uV", 8),
+            ("mV", 8),
+            ("-100", 8),
+            ("0", 8),
+            ("100", 8),
+            ("10", 8),
+            ("-100", 8),
+            ("0", 8),
+            ("100", 8),
+            ("10", 8),
+            ("", 80),
+            ("", 80),
+            ("4", 8),
+            ("2", 8),
+            ("", 32),
+            ("", 32),
+        ];
+        for (value, width) in fields.iter().chain(signals) {
+            super::write_field(&mut bytes, value, *width).unwrap();
+        }
+        for digital in [-100i16, -50, 0, 50, 1, 2, 100, 50, 0, -50, 3, 4].iter() {
+            bytes.extend_from_slice(&digital.to_le_bytes());
+        }
+
+        let recording = EdfRecording::parse(&bytes).unwrap();
+        assert!(!recording.plus());
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(1044072306), *recording.start_time());
+        assert_eq!(2.0, recording.channels()[1].sampling_rate);
+        assert_eq!(Some(&[-100.0, -50.0, 0.0, 50.0, 100.0, 50.0, 0.0, -50.0][..]), recording.channel_samples(0));
+        assert_eq!(Some(&[1.0, 2.0, 3.0, 4.0][..]), recording.channel_samples(1));
+
+        let data = recording.data();
+        assert_eq!((2, 8), data.dim());
+        assert_eq!(&[1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0], data.row(1).as_slice().unwrap());
+        assert!(EdfRecording::parse(&bytes[..300]).is_err());
+    }
+}
@@ -1,11 +1,24 @@
-use std::str::Utf8Error;
+use getset::Getters;
+use std::{
+    fmt,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::Utf8Error,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
 
 use thiserror::Error;
 
+use crate::{BoardIds, BrainFlowPresets};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("{0}")]
-    BrainFlowError(#[from] BrainFlowError),
+    BrainFlowError(#[from] NativeError),
 
     #[error("Interior nul byte found")]
     NulError(#[from] std::ffi::NulError),
@@ -14,6 +27,9 @@ pub enum Error {
     #[error("JSON error")]
     JsonError(#[from] serde_json::Error),
 
+    #[error("{0}")]
+    TomlError(#[from] toml::de::Error),
+
     #[error("Cannot convert from Utf8")]
     Utf8Error(#[from] Utf8Error),
 
@@ -22,79 +38,403 @@ pub enum Error {
 
     #[error("{0}")]
     FromPrimitive(#[from] std::num::ParseIntError),
+
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Unknown {0} name `{1}`")]
+    UnknownName(&'static str, String),
+
+    #[error("Invalid input params for {0}: {1}")]
+    InvalidInputParams(BoardIds, String),
+
+    #[error("Invalid streamer params: {0}")]
+    InvalidStreamerParams(String),
+
+    #[error("Invalid board config: {0}")]
+    InvalidBoardConfig(String),
+
+    #[error("Invalid session config: {0}")]
+    InvalidSessionConfig(String),
+
+    #[error("Invalid board data: {0}")]
+    InvalidBoardData(String),
+
+    #[error("Invalid recording: {0}")]
+    InvalidRecording(String),
+
+    #[error("Invalid EDF: {0}")]
+    InvalidEdf(String),
+
+    #[error("Invalid BrainVision files: {0}")]
+    InvalidBrainVision(String),
+
+    #[error("Invalid XDF: {0}")]
+    InvalidXdf(String),
+
+    #[error("Invalid BIDS export: {0}")]
+    InvalidBids(String),
+
+    #[error("Cannot merge board data: {0}")]
+    InvalidMergeInput(String),
+
+    /// Failure of a pure Rust [crate::board::Board], e.g. [crate::memory_board::MemoryBoard],
+    /// or of a Rust worker driving a board, e.g. [crate::hub::BoardHub].
+    #[error("{operation} failed for {board_id}: {reason}")]
+    Board {
+        board_id: BoardIds,
+        operation: &'static str,
+        reason: String,
+    },
+
+    #[cfg(feature = "async")]
+    #[error("{0}")]
+    JoinError(#[from] tokio::task::JoinError),
+
+    #[cfg(feature = "runtime_load")]
+    #[error("Unable to load native library {0}: {1}")]
+    LibraryNotFound(String, String),
+
+    #[cfg(feature = "runtime_load")]
+    #[error("Incompatible native libraries: {0}")]
+    IncompatibleLibraries(String),
 }
 
-#[derive(Debug, Error)]
+/// Exit codes of the native library, see [crate::BrainFlowExitCodes].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
 pub enum BrainFlowError {
-    #[error("")]
+    #[error("port is already open")]
     PortAlreadyOpenError,
 
-    #[error("")]
+    #[error("unable to open port")]
     UnableToOpenPortError,
 
-    #[error("")]
+    #[error("unable to set port settings")]
     SetPortError,
 
-    #[error("")]
+    #[error("unable to write to the board")]
     BoardWriteError,
 
-    #[error("")]
+    #[error("invalid message received from the board")]
     IncommingMsgError,
 
-    #[error("")]
+    #[error("invalid initial message from the board")]
     InitialMsgError,
 
-    #[error("")]
+    #[error("board is not ready, prepare the session first")]
     BoardNotReadyError,
 
-    #[error("")]
+    #[error("stream is already running")]
     StreamAlreadyRunError,
 
-    #[error("")]
+    #[error("invalid buffer size")]
     InvalidBufferSizeError,
 
-    #[error("")]
+    #[error("unable to start the stream thread")]
     StreamThreadError,
 
-    #[error("")]
+    #[error("stream is not running")]
     StreamThreadIsNotRunning,
 
-    #[error("")]
+    #[error("buffer is empty")]
     EmptyBufferError,
 
-    #[error("")]
+    #[error("invalid arguments")]
     InvalidArgumentsError,
 
-    #[error("")]
+    #[error("board is not supported")]
     UnsupportedBoardError,
 
-    #[error("")]
+    #[error("board session is not created")]
     BoardNotCreatedError,
 
-    #[error("")]
+    #[error("another board session is created with the same params")]
     AnotherBoardIsCreatedError,
 
-    #[error("")]
+    #[error("general error")]
     GeneralError,
 
-    #[error("")]
+    #[error("timeout while waiting for the board")]
     SyncTimeoutError,
 
-    #[error("")]
+    #[error("board description not found")]
     JsonNotFoundError,
 
-    #[error("")]
+    #[error("no such data in the board description")]
     NoSuchDataInJsonError,
 
-    #[error("")]
+    #[error("classifier is not prepared")]
     ClassifierIsNotPreparedError,
 
-    #[error("")]
+    #[error("another classifier is prepared")]
     AnotherClassifierIsPreparedError,
 
-    #[error("")]
+    #[error("unsupported classifier and metric combination")]
     UnsupportedClassifierAndMetricCombinationError,
 
-    #[error("This error code is not (yet) supported by this Rust binding")]
-    ErrorIsNotSupportedInRustError,
+    #[error("unknown exit code {0}, not (yet) supported by this Rust binding")]
+    ErrorIsNotSupportedInRustError(i32),
+}
+
+impl BrainFlowError {
+    /// Error for a non zero exit code of the native library.
+    pub fn from_exit_code(code: i32) -> Self {
+        match code {
+            1 => BrainFlowError::PortAlreadyOpenError,
+            2 => BrainFlowError::UnableToOpenPortError,
+            3 => BrainFlowError::SetPortError,
+            4 => BrainFlowError::BoardWriteError,
+            5 => BrainFlowError::IncommingMsgError,
+            6 => BrainFlowError::InitialMsgError,
+            7 => BrainFlowError::BoardNotReadyError,
+            8 => BrainFlowError::StreamAlreadyRunError,
+            9 => BrainFlowError::InvalidBufferSizeError,
+            10 => BrainFlowError::StreamThreadError,
+            11 => BrainFlowError::StreamThreadIsNotRunning,
+            12 => BrainFlowError::EmptyBufferError,
+            13 => BrainFlowError::InvalidArgumentsError,
+            14 => BrainFlowError::UnsupportedBoardError,
+            15 => BrainFlowError::BoardNotCreatedError,
+            16 => BrainFlowError::AnotherBoardIsCreatedError,
+            17 => BrainFlowError::GeneralError,
+            18 => BrainFlowError::SyncTimeoutError,
+            19 => BrainFlowError::JsonNotFoundError,
+            20 => BrainFlowError::NoSuchDataInJsonError,
+            21 => BrainFlowError::ClassifierIsNotPreparedError,
+            22 => BrainFlowError::AnotherClassifierIsPreparedError,
+            23 => BrainFlowError::UnsupportedClassifierAndMetricCombinationError,
+            _ => BrainFlowError::ErrorIsNotSupportedInRustError(code),
+        }
+    }
+
+    /// Exit code of the native library.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BrainFlowError::PortAlreadyOpenError => 1,
+            BrainFlowError::UnableToOpenPortError => 2,
+            BrainFlowError::SetPortError => 3,
+            BrainFlowError::BoardWriteError => 4,
+            BrainFlowError::IncommingMsgError => 5,
+            BrainFlowError::InitialMsgError => 6,
+            BrainFlowError::BoardNotReadyError => 7,
+            BrainFlowError::StreamAlreadyRunError => 8,
+            BrainFlowError::InvalidBufferSizeError => 9,
+            BrainFlowError::StreamThreadError => 10,
+            BrainFlowError::StreamThreadIsNotRunning => 11,
+            BrainFlowError::EmptyBufferError => 12,
+            BrainFlowError::InvalidArgumentsError => 13,
+            BrainFlowError::UnsupportedBoardError => 14,
+            BrainFlowError::BoardNotCreatedError => 15,
+            BrainFlowError::AnotherBoardIsCreatedError => 16,
+            BrainFlowError::GeneralError => 17,
+            BrainFlowError::SyncTimeoutError => 18,
+            BrainFlowError::JsonNotFoundError => 19,
+            BrainFlowError::NoSuchDataInJsonError => 20,
+            BrainFlowError::ClassifierIsNotPreparedError => 21,
+            BrainFlowError::AnotherClassifierIsPreparedError => 22,
+            BrainFlowError::UnsupportedClassifierAndMetricCombinationError => 23,
+            BrainFlowError::ErrorIsNotSupportedInRustError(code) => *code,
+        }
+    }
+}
+
+/// Native library which returned an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NativeModule {
+    BoardController,
+    DataHandler,
+    MlModule,
+}
+
+/// Log files set with the `set_log_file` function of each module, indexed by [NativeModule].
+static LOG_FILES: Mutex<[Option<PathBuf>; 3]> = Mutex::new([None, None, None]);
+/// Number of log lines attached to a [NativeError].
+static NUM_LOG_LINES: AtomicUsize = AtomicUsize::new(0);
+
+/// Attach the last `num_lines` lines of the native log to every [NativeError], 0 disables it.
+///
+/// Lines are only available for modules which log to a file, see e.g. [crate::board_shim::set_log_file].
+pub fn set_native_log_lines(num_lines: usize) {
+    NUM_LOG_LINES.store(num_lines, Ordering::Relaxed);
+}
+
+impl NativeModule {
+    /// Remember the log file of this module to read log lines from.
+    pub(crate) fn set_log_file(self, log_file: &Path) {
+        LOG_FILES.lock().unwrap()[self as usize] = Some(log_file.to_path_buf());
+    }
+
+    /// The last `num_lines` lines of the log file of this module.
+    fn last_log_lines(self, num_lines: usize) -> Vec<String> {
+        let log_file = match &LOG_FILES.lock().unwrap()[self as usize] {
+            Some(log_file) if num_lines > 0 => log_file.clone(),
+            _ => return Vec::new(),
+        };
+        last_lines(&log_file, num_lines).unwrap_or_default()
+    }
+}
+
+/// The last `num_lines` lines of a file, read backwards from its end so that large logs are not read whole.
+fn last_lines(path: &Path, num_lines: usize) -> std::io::Result<Vec<String>> {
+    const BLOCK_SIZE: u64 = 4096;
+    let mut file = File::open(path)?;
+    let mut pos = file.seek(SeekFrom::End(0))?;
+    let mut tail = Vec::new();
+    // one more line break than lines, the last line may end with one
+    while pos > 0 && tail.iter().filter(|b| **b == b'\n').count() <= num_lines {
+        let start = pos.saturating_sub(BLOCK_SIZE);
+        let mut block = vec![0; (pos - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut block)?;
+        block.extend_from_slice(&tail);
+        tail = block;
+        pos = start;
+    }
+    let tail = String::from_utf8_lossy(&tail);
+    let lines = tail.lines().collect::<Vec<_>>();
+    Ok(lines[lines.len().saturating_sub(num_lines)..].iter().map(|l| l.to_string()).collect())
+}
+
+/// A call into the native library which failed.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct NativeError {
+    kind: BrainFlowError,
+    module: NativeModule,
+    /// Name of the native function, e.g. `prepare_session`.
+    operation: &'static str,
+    board_id: Option<BoardIds>,
+    preset: Option<BrainFlowPresets>,
+    /// Last lines of the native log, see [set_native_log_lines].
+    log_lines: Vec<String>,
+}
+
+impl NativeError {
+    /// Raw exit code of the native library.
+    pub fn exit_code(&self) -> i32 {
+        self.kind.exit_code()
+    }
+}
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed", self.operation)?;
+        match (self.board_id, self.preset) {
+            (Some(board_id), Some(preset)) => write!(f, " for {} ({:?})", board_id, preset)?,
+            (Some(board_id), None) => write!(f, " for {}", board_id)?,
+            (None, Some(preset)) => write!(f, " for {:?}", preset)?,
+            (None, None) => {}
+        }
+        write!(f, ": {} (exit code {})", self.kind, self.exit_code())?;
+        for line in &self.log_lines {
+            write!(f, "\n  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NativeError {}
+
+/// Context of a native call, turned into a [NativeError] if the call fails.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NativeCall {
+    module: NativeModule,
+    operation: &'static str,
+    board_id: Option<BoardIds>,
+    preset: Option<BrainFlowPresets>,
+}
+
+impl NativeCall {
+    pub(crate) fn board_controller(operation: &'static str) -> Self {
+        Self::new(NativeModule::BoardController, operation)
+    }
+
+    pub(crate) fn data_handler(operation: &'static str) -> Self {
+        Self::new(NativeModule::DataHandler, operation)
+    }
+
+    pub(crate) fn ml_module(operation: &'static str) -> Self {
+        Self::new(NativeModule::MlModule, operation)
+    }
+
+    pub(crate) fn new(module: NativeModule, operation: &'static str) -> Self {
+        Self {
+            module,
+            operation,
+            board_id: None,
+            preset: None,
+        }
+    }
+
+    pub(crate) fn board_id(mut self, board_id: BoardIds) -> Self {
+        self.board_id = Some(board_id);
+        self
+    }
+
+    pub(crate) fn preset(mut self, preset: BrainFlowPresets) -> Self {
+        self.preset = Some(preset);
+        self
+    }
+
+    /// The error of this call failing with `kind`.
+    pub(crate) fn error(self, kind: BrainFlowError) -> Error {
+        Error::BrainFlowError(NativeError {
+            kind,
+            module: self.module,
+            operation: self.operation,
+            board_id: self.board_id,
+            preset: self.preset,
+            log_lines: self.module.last_log_lines(NUM_LOG_LINES.load(Ordering::Relaxed)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::{last_lines, BrainFlowError, NativeCall};
+    use crate::{error::Error, BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_exit_codes() {
+        for code in 1..=23 {
+            assert_eq!(code, BrainFlowError::from_exit_code(code).exit_code());
+        }
+        assert_eq!(BrainFlowError::ErrorIsNotSupportedInRustError(42), BrainFlowError::from_exit_code(42));
+        assert_eq!(42, BrainFlowError::from_exit_code(42).exit_code());
+    }
+
+    #[test]
+    fn test_native_error_message() {
+        let call = NativeCall::board_controller("get_board_data")
+            .board_id(BoardIds::SyntheticBoard)
+            .preset(BrainFlowPresets::AuxiliaryPreset);
+        let err = call.error(BrainFlowError::BoardNotCreatedError);
+        assert_eq!(
+            "get_board_data failed for SyntheticBoard (AuxiliaryPreset): board session is not created (exit code 15)",
+            err.to_string()
+        );
+        match err {
+            Error::BrainFlowError(e) => assert_eq!(Some(BoardIds::SyntheticBoard), *e.board_id()),
+            e => panic!("unexpected {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_last_lines() {
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let log_file = dir.join("last_lines.log");
+        // lines across several blocks
+        let content = (0..1000).map(|i| format!("line {}\n", i)).collect::<String>();
+        fs::write(&log_file, &content).unwrap();
+        assert_eq!(vec!["line 998", "line 999"], last_lines(&log_file, 2).unwrap());
+        assert_eq!(1000, last_lines(&log_file, 2000).unwrap().len());
+        assert!(last_lines(&log_file, 0).unwrap().is_empty());
+
+        fs::write(&log_file, "first line\nsecond line").unwrap();
+        assert_eq!(vec!["second line"], last_lines(&log_file, 1).unwrap());
+        assert!(last_lines(&dir.join("missing.log"), 1).is_err());
+    }
 }
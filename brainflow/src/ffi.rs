@@ -1,5 +1,26 @@
 #![allow(dead_code)]
-pub mod board_controller;
 pub mod constants;
+
+#[cfg(not(feature = "runtime_load"))]
+pub mod board_controller;
+#[cfg(not(feature = "runtime_load"))]
 pub mod data_handler;
+#[cfg(not(feature = "runtime_load"))]
 pub mod ml_module;
+
+// Same functions as the linked bindings, calling into the libraries loaded by `native_library`.
+#[cfg(feature = "runtime_load")]
+#[allow(non_camel_case_types, clippy::missing_safety_doc, clippy::too_many_arguments)]
+pub mod board_controller {
+    include!(concat!(env!("OUT_DIR"), "/board_controller_runtime.rs"));
+}
+#[cfg(feature = "runtime_load")]
+#[allow(non_camel_case_types, clippy::missing_safety_doc, clippy::too_many_arguments)]
+pub mod data_handler {
+    include!(concat!(env!("OUT_DIR"), "/data_handler_runtime.rs"));
+}
+#[cfg(feature = "runtime_load")]
+#[allow(non_camel_case_types, clippy::missing_safety_doc, clippy::too_many_arguments)]
+pub mod ml_module {
+    include!(concat!(env!("OUT_DIR"), "/ml_module_runtime.rs"));
+}
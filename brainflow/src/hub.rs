@@ -0,0 +1,359 @@
+use ndarray::Array2;
+use std::{
+    collections::VecDeque,
+    sync::{
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Condvar, Mutex, MutexGuard,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    board::Board, board_description::BoardDescription, board_shim::BoardShim, error::Error, BoardIds,
+    BrainFlowPresets, Result,
+};
+
+/// Chunk of board data shared by all subscribers of a [BoardHub].
+pub type SharedChunk = Arc<Array2<f64>>;
+
+/// What a [BoardHub] does when a subscriber does not keep up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Keep at most this many chunks, dropping the oldest one.
+    DropOldest(usize),
+    /// Keep at most this many chunks, the hub waits for the subscriber.
+    /// A blocked subscriber delays all other subscribers.
+    Block(usize),
+    /// Keep all chunks.
+    Unbounded,
+}
+
+#[derive(Default)]
+struct QueueState {
+    chunks: VecDeque<SharedChunk>,
+    dropped: usize,
+    closed: bool,
+}
+
+struct Queue {
+    policy: Backpressure,
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns false if the subscriber is gone.
+    fn push(&self, chunk: &SharedChunk) -> bool {
+        let mut state = self.lock();
+        match self.policy {
+            Backpressure::DropOldest(capacity) => {
+                while !state.chunks.is_empty() && state.chunks.len() >= capacity.max(1) {
+                    state.chunks.pop_front();
+                    state.dropped += 1;
+                }
+            }
+            Backpressure::Block(capacity) => {
+                while !state.closed && state.chunks.len() >= capacity.max(1) {
+                    state = self.not_full.wait(state).unwrap_or_else(|e| e.into_inner());
+                }
+            }
+            Backpressure::Unbounded => {}
+        }
+        if state.closed {
+            return false;
+        }
+        state.chunks.push_back(Arc::clone(chunk));
+        self.not_empty.notify_one();
+        true
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+}
+
+/// Queues of the subscribers of a [BoardHub].
+#[derive(Default)]
+struct Subscribers {
+    queues: Vec<Arc<Queue>>,
+    /// Set by the hub thread when it exits, later subscribers get a closed queue.
+    stopped: bool,
+}
+
+/// Owns a streaming [BoardShim], drains it once per tick and shares the chunks with any number of
+/// [HubSubscriber]s, so that several consumers can read the same data without stealing samples.
+pub struct BoardHub {
+    board: Arc<BoardShim>,
+    subscribers: Arc<Mutex<Subscribers>>,
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl BoardHub {
+    /// Start draining `preset` of a streaming `board` every `tick`.
+    pub fn new(board: BoardShim, preset: BrainFlowPresets, tick: Duration) -> Result<Self> {
+        let board = Arc::new(board);
+        let subscribers: Arc<Mutex<Subscribers>> = Arc::default();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = {
+            let board = Arc::clone(&board);
+            let subscribers = Arc::clone(&subscribers);
+            thread::Builder::new()
+                .name(format!("brainflow-{}-hub", board.get_board_id()))
+                .spawn(move || {
+                    let drain = || -> Result<()> {
+                        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(tick) {
+                            let count = board.get_board_data_count(preset)?;
+                            if count == 0 {
+                                continue;
+                            }
+                            let chunk = Arc::new(board.get_board_data(Some(count), preset)?);
+                            // clone the list so that blocking subscribers do not hold the lock
+                            let current = subscribers.lock().unwrap_or_else(|e| e.into_inner()).queues.clone();
+                            let gone = current.iter().filter(|q| !q.push(&chunk)).count();
+                            if gone > 0 {
+                                subscribers
+                                    .lock()
+                                    .unwrap_or_else(|e| e.into_inner())
+                                    .queues
+                                    .retain(|q| !q.lock().closed);
+                            }
+                        }
+                        Ok(())
+                    };
+                    let res = drain();
+                    // subscribers must not wait for chunks after the hub stopped, also when it failed
+                    let mut subscribers = subscribers.lock().unwrap_or_else(|e| e.into_inner());
+                    subscribers.stopped = true;
+                    for queue in subscribers.queues.drain(..) {
+                        queue.close();
+                    }
+                    res
+                })?
+        };
+        Ok(Self {
+            board,
+            subscribers,
+            stop: Some(stop),
+            handle: Some(handle),
+        })
+    }
+
+    /// Board id of the drained board, see [BoardShim::get_board_id].
+    pub fn get_board_id(&self) -> BoardIds {
+        self.board.get_board_id()
+    }
+
+    /// Layout of the chunks of `preset`, see [Board::get_description].
+    pub fn get_description(&self, preset: BrainFlowPresets) -> Result<BoardDescription> {
+        self.board.get_description(preset)
+    }
+
+    /// Add a subscriber which receives all chunks drained from now on.
+    /// The subscriber is closed right away if the hub already stopped, e.g. after a board error.
+    pub fn subscribe(&self, policy: Backpressure) -> HubSubscriber {
+        let queue = Arc::new(Queue {
+            policy,
+            state: Mutex::default(),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        });
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if subscribers.stopped {
+            queue.close();
+        } else {
+            subscribers.queues.push(Arc::clone(&queue));
+        }
+        HubSubscriber { queue }
+    }
+
+    /// Number of subscribers which are still connected.
+    pub fn num_subscribers(&self) -> usize {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .queues
+            .iter()
+            .filter(|q| !q.lock().closed)
+            .count()
+    }
+
+    /// Stop draining and return the [BoardShim], or the error which stopped the hub.
+    /// Subscribers can still read the chunks they have queued.
+    pub fn stop(mut self) -> Result<BoardShim> {
+        self.shutdown()?;
+        let board = Arc::clone(&self.board);
+        drop(self);
+        // the hub thread held the only other reference and is joined
+        Ok(Arc::try_unwrap(board).unwrap_or_else(|_| unreachable!("board is shared with a running hub thread")))
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        {
+            let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+            subscribers.stopped = true;
+            for queue in subscribers.queues.drain(..) {
+                queue.close();
+            }
+        }
+        drop(self.stop.take());
+        match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(res)) => res,
+            Some(Err(_)) => Err(Error::Board {
+                board_id: self.board.get_board_id(),
+                operation: "hub",
+                reason: "hub thread panicked".to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for BoardHub {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// Receiving end of a [BoardHub].
+///
+/// Also a blocking [Iterator] which ends once the hub stopped and all queued chunks were read.
+pub struct HubSubscriber {
+    queue: Arc<Queue>,
+}
+
+impl HubSubscriber {
+    /// Wait for the next chunk, `None` if the hub stopped.
+    pub fn recv(&self) -> Option<SharedChunk> {
+        let mut state = self.queue.lock();
+        loop {
+            if let Some(chunk) = state.chunks.pop_front() {
+                self.queue.not_full.notify_one();
+                return Some(chunk);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.queue.not_empty.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Wait for the next chunk for at most `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<SharedChunk> {
+        let state = self.queue.lock();
+        let (mut state, _) = self
+            .queue
+            .not_empty
+            .wait_timeout_while(state, timeout, |s| s.chunks.is_empty() && !s.closed)
+            .unwrap_or_else(|e| e.into_inner());
+        let chunk = state.chunks.pop_front();
+        if chunk.is_some() {
+            self.queue.not_full.notify_one();
+        }
+        chunk
+    }
+
+    /// Return the next chunk if one is queued, without blocking.
+    pub fn try_recv(&self) -> Option<SharedChunk> {
+        let chunk = self.queue.lock().chunks.pop_front();
+        if chunk.is_some() {
+            self.queue.not_full.notify_one();
+        }
+        chunk
+    }
+
+    /// Number of queued chunks.
+    pub fn len(&self) -> usize {
+        self.queue.lock().chunks.len()
+    }
+
+    /// True if no chunk is queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of chunks dropped by [Backpressure::DropOldest].
+    pub fn dropped(&self) -> usize {
+        self.queue.lock().dropped
+    }
+}
+
+impl Iterator for HubSubscriber {
+    type Item = SharedChunk;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+impl Drop for HubSubscriber {
+    fn drop(&mut self) {
+        self.queue.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use super::{Backpressure, BoardHub};
+    use crate::board_shim::BoardShim;
+    use crate::brainflow_input_params::BrainFlowInputParamsBuilder;
+    use crate::{BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_subscribers_share_chunks() {
+        let params = BrainFlowInputParamsBuilder::new().other_info("subscribers_share_chunks").build();
+        let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+        board.prepare_session().unwrap();
+        board.start_stream(45000, "").unwrap();
+        let hub = BoardHub::new(board, BrainFlowPresets::DefaultPreset, Duration::from_millis(10)).unwrap();
+
+        let all = hub.subscribe(Backpressure::Unbounded);
+        let latest = hub.subscribe(Backpressure::DropOldest(1));
+        let dropped = hub.subscribe(Backpressure::Block(1));
+        drop(dropped);
+
+        thread::sleep(Duration::from_millis(200));
+        hub.stop().unwrap().release_session().unwrap();
+
+        assert!(all.len() > 1);
+        assert_eq!(1, latest.len());
+        assert_eq!(all.len() - 1, latest.dropped());
+
+        let last_of_all = all.last().unwrap();
+        let last_of_latest = latest.recv().unwrap();
+        assert!(Arc::ptr_eq(&last_of_all, &last_of_latest));
+        assert_eq!(None, latest.recv());
+    }
+
+    #[test]
+    fn test_subscribers_end_when_board_fails() {
+        let board_shim = || {
+            let params = BrainFlowInputParamsBuilder::new().other_info("subscribers_end_when_board_fails").build();
+            BoardShim::new(BoardIds::SyntheticBoard, params).unwrap()
+        };
+        let board = board_shim();
+        board.prepare_session().unwrap();
+        board.start_stream(45000, "").unwrap();
+        let hub = BoardHub::new(board, BrainFlowPresets::DefaultPreset, Duration::from_millis(10)).unwrap();
+        let subscriber = hub.subscribe(Backpressure::Unbounded);
+
+        thread::sleep(Duration::from_millis(50));
+        // a second shim with the same params refers to the same native session
+        board_shim().release_session().unwrap();
+        // the subscriber reads the chunks drained before the failure and then ends instead of blocking
+        assert!(subscriber.count() < 100);
+        // so does a subscriber added after the failure
+        assert_eq!(None, hub.subscribe(Backpressure::Unbounded).recv());
+        assert!(hub.stop().is_err());
+    }
+}
@@ -6,22 +6,76 @@ extern crate num;
 #[macro_use]
 extern crate num_derive;
 
-use error::Error;
+use error::{Error, NativeCall};
 
+/// Export of recordings to BIDS-EEG datasets.
+pub mod bids;
+/// Common interface of native and pure Rust boards.
+pub mod board;
+/// Typed board descriptions.
+pub mod board_description;
+/// Async streams of board data, enabled with the `async` feature.
+#[cfg(feature = "async")]
+pub mod board_stream;
+/// Board data tables with named channel views.
+pub mod board_data;
 /// The primary interface to all boards.
 pub mod board_shim;
 /// Input parameters for [board_shim::BoardShim].
 pub mod brainflow_input_params;
+/// BrainVision Core Data Format files.
+pub mod brainvision;
 pub mod error;
 
+/// Capabilities of all supported boards.
+pub mod catalogue;
+/// Configuration commands for OpenBCI Cyton boards.
+pub mod cyton;
+
 /// Input parameters for [ml_model::MLModel].
 pub mod brainflow_model_params;
 /// Methods for signal processig.
+#[allow(clippy::unnecessary_cast, clippy::map_flatten, clippy::type_complexity)]
 pub mod data_filter;
+/// EDF+ and BDF+ files.
+pub mod edf;
 mod ffi;
+/// Board fed with in-memory data.
+pub mod memory_board;
+/// Share one board stream between several consumers.
+pub mod hub;
 /// Used to calculate derivative metrics from raw data.
+#[allow(clippy::unnecessary_cast)]
 pub mod ml_model;
+/// Loading the native libraries at runtime, enabled with the `runtime_load` feature.
+#[cfg(feature = "runtime_load")]
+pub mod native_library;
+/// Bridge between the native loggers and the `log` crate, enabled with the `log` feature.
+#[cfg(feature = "log")]
+pub mod native_log;
+/// Acquisition from several boards at once.
+pub mod multi_board;
+/// Crash-safe chunked recording files with rotation and recovery.
+pub mod recorder;
+/// Random access to long recordings without loading them.
+pub mod recording_reader;
+/// Pure Rust board replaying recorded files in real time.
+pub mod replay_board;
+/// Board sessions which release their resources when dropped.
+pub mod session;
+/// Session configuration files.
+pub mod session_config;
+/// Pure Rust board generating seeded synthetic signals.
+pub mod signal_board;
+/// Typed streamer params.
+pub mod streamer;
+/// Background polling of new board data.
+pub mod subscription;
+/// XDF files with several streams.
+pub mod xdf;
 
+mod names;
+mod stream_buffer;
 mod test_helpers;
 /// Store all supported BrainFlow Errors.
 pub use error::BrainFlowError;
@@ -62,37 +116,16 @@ pub use ffi::constants::BrainFlowPresets;
 
 type BrainFlowExitCode = i32;
 
-/// Convert the brainflow exit code to [BrainFlowError].
-fn check_brainflow_exit_code(value: BrainFlowExitCode) -> std::result::Result<(), BrainFlowError> {
+/// Convert the brainflow exit code of a native call to an [Error] describing the call.
+fn check_brainflow_exit_code(value: BrainFlowExitCode, call: NativeCall) -> Result<()> {
+    #[cfg(feature = "runtime_load")]
+    if value == native_library::NOT_LOADED {
+        return Err(native_library::take_load_error());
+    }
     if value == 0 {
         Ok(())
     } else {
-        Err(match value {
-            1 => BrainFlowError::PortAlreadyOpenError,
-            2 => BrainFlowError::UnableToOpenPortError,
-            3 => BrainFlowError::SetPortError,
-            4 => BrainFlowError::BoardWriteError,
-            5 => BrainFlowError::IncommingMsgError,
-            6 => BrainFlowError::InitialMsgError,
-            7 => BrainFlowError::BoardNotReadyError,
-            8 => BrainFlowError::StreamAlreadyRunError,
-            9 => BrainFlowError::InvalidBufferSizeError,
-            10 => BrainFlowError::StreamThreadError,
-            11 => BrainFlowError::StreamThreadIsNotRunning,
-            12 => BrainFlowError::EmptyBufferError,
-            13 => BrainFlowError::InvalidArgumentsError,
-            14 => BrainFlowError::UnsupportedBoardError,
-            15 => BrainFlowError::BoardNotCreatedError,
-            16 => BrainFlowError::AnotherBoardIsCreatedError,
-            17 => BrainFlowError::GeneralError,
-            18 => BrainFlowError::SyncTimeoutError,
-            19 => BrainFlowError::JsonNotFoundError,
-            20 => BrainFlowError::NoSuchDataInJsonError,
-            21 => BrainFlowError::ClassifierIsNotPreparedError,
-            22 => BrainFlowError::AnotherClassifierIsPreparedError,
-            23 => BrainFlowError::UnsupportedClassifierAndMetricCombinationError,
-            _ => BrainFlowError::ErrorIsNotSupportedInRustError,
-        })
+        Err(call.error(BrainFlowError::from_exit_code(value)))
     }
 }
 
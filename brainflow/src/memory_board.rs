@@ -0,0 +1,162 @@
+use ndarray::Array2;
+use std::sync::Mutex;
+
+use crate::{
+    board::Board, board_description::BoardDescription, stream_buffer::StreamBuffer, BoardIds, BrainFlowPresets,
+    Result,
+};
+
+/// Pure Rust [Board] whose samples are pushed by the caller with [MemoryBoard::push_data],
+/// e.g. to test pipelines and GUIs with known data.
+///
+/// Sessions behave like native ones, e.g. data is only accepted while streaming, and fail with
+/// [crate::error::Error::Board].
+pub struct MemoryBoard {
+    buffer: Mutex<StreamBuffer>,
+}
+
+impl MemoryBoard {
+    /// Board producing data tables with the given layout per preset.
+    pub fn new(board_id: BoardIds, descriptions: Vec<(BrainFlowPresets, BoardDescription)>) -> Self {
+        Self {
+            buffer: Mutex::new(StreamBuffer::new(board_id, descriptions)),
+        }
+    }
+
+    /// Board with the layout of a native board, only the description is read from the native library.
+    pub fn with_native_description(board_id: BoardIds) -> Result<Self> {
+        Ok(Self::new(board_id, BoardDescription::load_all(board_id)?))
+    }
+
+    /// Append the samples of `data`, one per column, to the ring buffer of `preset`.
+    pub fn push_data(&self, data: &Array2<f64>, preset: BrainFlowPresets) -> Result<()> {
+        let mut buffer = self.buffer.lock().unwrap();
+        data.columns()
+            .into_iter()
+            .try_for_each(|sample| buffer.push(preset, sample.to_vec()))
+    }
+}
+
+impl Board for MemoryBoard {
+    fn get_board_id(&self) -> BoardIds {
+        self.buffer.lock().unwrap().board_id()
+    }
+
+    fn prepare_session(&self) -> Result<()> {
+        self.buffer.lock().unwrap().prepare();
+        Ok(())
+    }
+
+    fn is_prepared(&self) -> Result<bool> {
+        Ok(self.buffer.lock().unwrap().is_prepared())
+    }
+
+    fn start_stream(&self, buffer_size: usize, streamer_params: &str) -> Result<()> {
+        self.buffer.lock().unwrap().start(buffer_size, streamer_params)
+    }
+
+    fn stop_stream(&self) -> Result<()> {
+        self.buffer.lock().unwrap().stop()
+    }
+
+    fn release_session(&self) -> Result<()> {
+        self.buffer.lock().unwrap().release()
+    }
+
+    fn get_board_data_count(&self, preset: BrainFlowPresets) -> Result<usize> {
+        self.buffer.lock().unwrap().count(preset)
+    }
+
+    fn get_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.buffer.lock().unwrap().take(n_data_points, preset)
+    }
+
+    fn get_current_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.buffer.lock().unwrap().current(num_samples, preset)
+    }
+
+    fn insert_marker(&self, value: f64, preset: BrainFlowPresets) -> Result<()> {
+        self.buffer.lock().unwrap().insert_marker(value, preset)
+    }
+
+    fn get_description(&self, preset: BrainFlowPresets) -> Result<BoardDescription> {
+        self.buffer.lock().unwrap().description(preset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array2};
+
+    use super::MemoryBoard;
+    use crate::{board::Board, board_description::BoardDescription, error::Error, BoardIds, BrainFlowPresets};
+
+    fn board() -> MemoryBoard {
+        let descr: BoardDescription = serde_json::from_str(
+            r#"{"name": "Memory", "sampling_rate": 100, "num_rows": 3, "eeg_channels": [0],
+                "timestamp_channel": 1, "marker_channel": 2}"#,
+        )
+        .unwrap();
+        MemoryBoard::new(BoardIds::SyntheticBoard, vec![(BrainFlowPresets::DefaultPreset, descr)])
+    }
+
+    fn reason(err: Error) -> (&'static str, String) {
+        match err {
+            Error::Board { operation, reason, .. } => (operation, reason),
+            e => panic!("unexpected {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_session_rules() {
+        let board = board();
+        let preset = BrainFlowPresets::DefaultPreset;
+        assert_eq!(
+            ("start_stream", "session is not prepared".to_string()),
+            reason(board.start_stream(10, "").unwrap_err())
+        );
+        board.prepare_session().unwrap();
+        assert_eq!(
+            ("get_board_data", "no stream was started in this session".to_string()),
+            reason(board.get_board_data(None, preset).unwrap_err())
+        );
+        let data = Array2::zeros((3, 1));
+        assert_eq!(
+            ("push", "stream is not running".to_string()),
+            reason(board.push_data(&data, preset).unwrap_err())
+        );
+        board.start_stream(10, "").unwrap();
+        assert_eq!(
+            ("start_stream", "stream is already running".to_string()),
+            reason(board.start_stream(10, "").unwrap_err())
+        );
+        assert_eq!(
+            ("get_board_data", "AuxiliaryPreset is not supported".to_string()),
+            reason(board.get_board_data(None, BrainFlowPresets::AuxiliaryPreset).unwrap_err())
+        );
+        let err = board.push_data(&Array2::zeros((2, 1)), preset).unwrap_err();
+        assert_eq!("push failed for SyntheticBoard: DefaultPreset samples have 3 rows, not 2", err.to_string());
+        board.release_session().unwrap();
+        assert!(!board.is_prepared().unwrap());
+    }
+
+    #[test]
+    fn test_ring_buffer_and_markers() {
+        let board = board();
+        let preset = BrainFlowPresets::DefaultPreset;
+        board.prepare_session().unwrap();
+        board.start_stream(3, "").unwrap();
+        board.push_data(&array![[1.0, 2.0], [0.0, 0.01], [0.0, 0.0]], preset).unwrap();
+        board.insert_marker(5.0, preset).unwrap();
+        board.push_data(&array![[3.0, 4.0], [0.02, 0.03], [0.0, 0.0]], preset).unwrap();
+
+        assert_eq!(3, board.get_board_data_count(preset).unwrap());
+        assert_eq!(array![[4.0], [0.03], [0.0]], board.get_current_board_data(1, preset).unwrap());
+        let data = board.get_typed_board_data(Some(2), preset).unwrap();
+        assert_eq!(array![[2.0, 3.0], [0.01, 0.02], [0.0, 5.0]], data.data());
+        assert_eq!(vec![0], *data.eeg_channels());
+        assert_eq!(1, board.get_board_data_count(preset).unwrap());
+        board.stop_stream().unwrap();
+        board.release_session().unwrap();
+    }
+}
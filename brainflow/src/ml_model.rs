@@ -1,10 +1,14 @@
 use std::{
     ffi::{CString, CStr},
     os::raw::{c_double, c_int, c_char},
+    path::Path,
 };
 
 use crate::{
-    brainflow_model_params::BrainFlowModelParams, check_brainflow_exit_code, LogLevels, Result,
+    brainflow_model_params::BrainFlowModelParams,
+    check_brainflow_exit_code,
+    error::{NativeCall, NativeModule},
+    LogLevels, Result,
 };
 
 use crate::ffi::ml_module;
@@ -25,7 +29,7 @@ impl MlModel {
     /// Prepare classifier.
     pub fn prepare(&self) -> Result<()> {
         let res = unsafe { ml_module::prepare(self.json_model_params.as_ptr()) };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(res, NativeCall::ml_module("prepare"))
     }
 
     /// Calculate metric from data.
@@ -41,7 +45,7 @@ impl MlModel {
                 self.json_model_params.as_ptr(),
             )
         };
-        check_brainflow_exit_code(res)?;
+        check_brainflow_exit_code(res, NativeCall::ml_module("predict"))?;
         unsafe { output.set_len(output_len as usize) };
         let output_casted = output.into_iter().map(|c| c as f64).collect::<Vec<f64>>();
         Ok(output_casted)
@@ -50,7 +54,7 @@ impl MlModel {
     /// Release classifier.
     pub fn release(&self) -> Result<()> {
         let res = unsafe { ml_module::release(self.json_model_params.as_ptr()) };
-        Ok(check_brainflow_exit_code(res)?)
+        check_brainflow_exit_code(res, NativeCall::ml_module("release"))
     }
 }
 
@@ -59,7 +63,7 @@ impl MlModel {
 /// Otherwise use [enable_ml_logger], [enable_dev_ml_logger], or [disable_ml_logger].
 pub fn set_log_level(log_level: LogLevels) -> Result<()> {
     let res = unsafe { ml_module::set_log_level_ml_module(log_level as c_int) };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::ml_module("set_log_level_ml_module"))
 }
 
 /// Enable ML logger with level INFO, uses stderr for log messages by default.
@@ -82,7 +86,9 @@ pub fn set_log_file<S: AsRef<str>>(log_file: S) -> Result<()> {
     let log_file = log_file.as_ref();
     let log_file = CString::new(log_file)?;
     let res = unsafe { ml_module::set_log_file_ml_module(log_file.as_ptr()) };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::ml_module("set_log_file_ml_module"))?;
+    NativeModule::MlModule.set_log_file(Path::new(log_file.to_str()?));
+    Ok(())
 }
 
 /// Write your own log message to BrainFlow board logger, use it if you wanna have single logger for your own code and BrainFlow's code.
@@ -94,13 +100,13 @@ pub fn log_message<S: AsRef<str>>(log_level: LogLevels, message: S) -> Result<()
         let _ = CString::from_raw(message);
         res
     };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::ml_module("log_message_ml_module"))
 }
 
 /// Release all classifiers
 pub fn release_all() -> Result<()> {
     let res = unsafe { ml_module::release_all() };
-    Ok(check_brainflow_exit_code(res)?)
+    check_brainflow_exit_code(res, NativeCall::ml_module("release_all"))
 }
 
 /// Get DataFilter version.
@@ -113,7 +119,7 @@ pub fn get_version() -> Result<String> {
         let response = CStr::from_ptr(result_char_buffer.as_ptr());
         (res, response)
     };
-    check_brainflow_exit_code(res)?;
+    check_brainflow_exit_code(res, NativeCall::ml_module("get_version_ml_module"))?;
     Ok(response.to_str()?.to_string())
 }
 
@@ -0,0 +1,350 @@
+use getset::Getters;
+use ndarray::{concatenate, Array2, Axis};
+
+use crate::{
+    board_description::BoardDescription, board_shim::BoardShim, error::Error, BoardIds, BrainFlowPresets, Result,
+};
+
+/// Data of one board of a [MultiBoardSession].
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct SourceChunk {
+    /// Index of the board in the session.
+    source: usize,
+    board_id: BoardIds,
+    preset: BrainFlowPresets,
+    data: Array2<f64>,
+}
+
+impl SourceChunk {
+    /// Tag `data` read from board `source` of a session.
+    pub fn new(source: usize, board_id: BoardIds, preset: BrainFlowPresets, data: Array2<f64>) -> Self {
+        Self {
+            source,
+            board_id,
+            preset,
+            data,
+        }
+    }
+}
+
+/// Origin of a row of a [MergedTable].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MergedRow {
+    /// Index of the board in the session.
+    pub source: usize,
+    /// Row in the data table of that board.
+    pub channel: usize,
+}
+
+/// Data of several boards resampled onto a common clock.
+///
+/// Row 0 holds the common timestamps, every other row comes from the board and channel in [MergedTable::rows].
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct MergedTable {
+    data: Array2<f64>,
+    rows: Vec<MergedRow>,
+}
+
+/// Prepares, starts, stops and releases several boards together.
+///
+/// Boards which are still streaming or prepared when the session is dropped are stopped and released.
+pub struct MultiBoardSession {
+    boards: Vec<BoardShim>,
+    prepared: usize,
+    streaming: usize,
+}
+
+impl MultiBoardSession {
+    /// Create a session over the given boards, boards are referred to by their index.
+    pub fn new(boards: Vec<BoardShim>) -> Self {
+        Self {
+            boards,
+            prepared: 0,
+            streaming: 0,
+        }
+    }
+
+    /// The boards of this session.
+    pub fn boards(&self) -> &[BoardShim] {
+        &self.boards
+    }
+
+    /// Prepare all boards, already prepared boards are released again if one fails.
+    pub fn prepare_session(&mut self) -> Result<()> {
+        while self.prepared < self.boards.len() {
+            if let Err(e) = self.boards[self.prepared].prepare_session() {
+                let _ = self.release_session();
+                return Err(e);
+            }
+            self.prepared += 1;
+        }
+        Ok(())
+    }
+
+    /// Start streaming on all boards, already started boards are stopped again if one fails.
+    /// Fails if not all boards are prepared, see [MultiBoardSession::prepare_session].
+    pub fn start_stream<S: AsRef<str>>(&mut self, buffer_size: usize, streamer_params: S) -> Result<()> {
+        if self.prepared < self.boards.len() {
+            return Err(Error::Board {
+                board_id: self.boards[self.prepared].get_board_id(),
+                operation: "start_stream",
+                reason: format!("session of board {} is not prepared", self.prepared),
+            });
+        }
+        while self.streaming < self.prepared {
+            let board = &self.boards[self.streaming];
+            if let Err(e) = board.start_stream(buffer_size, streamer_params.as_ref()) {
+                let _ = self.stop_stream();
+                return Err(e);
+            }
+            self.streaming += 1;
+        }
+        Ok(())
+    }
+
+    /// Stop streaming on all boards, returns the first error after trying all boards.
+    pub fn stop_stream(&mut self) -> Result<()> {
+        let mut res = Ok(());
+        for board in self.boards[..self.streaming].iter().rev() {
+            res = res.and(board.stop_stream());
+        }
+        self.streaming = 0;
+        res
+    }
+
+    /// Release all boards, returns the first error after trying all boards.
+    pub fn release_session(&mut self) -> Result<()> {
+        let mut res = self.stop_stream();
+        for board in self.boards[..self.prepared].iter().rev() {
+            res = res.and(board.release_session());
+        }
+        self.prepared = 0;
+        res
+    }
+
+    /// Insert the same marker into all boards.
+    pub fn insert_marker(&self, value: f64, preset: BrainFlowPresets) -> Result<()> {
+        self.boards
+            .iter()
+            .try_for_each(|board| board.insert_marker(value, preset))
+    }
+
+    /// Get all board data of every board and remove it from the ringbuffers.
+    pub fn get_board_data(&self, preset: BrainFlowPresets) -> Result<Vec<SourceChunk>> {
+        self.boards
+            .iter()
+            .enumerate()
+            .map(|(source, board)| {
+                let data = board.get_board_data(None, preset)?;
+                Ok(SourceChunk::new(source, board.get_board_id(), preset, data))
+            })
+            .collect()
+    }
+}
+
+impl Drop for MultiBoardSession {
+    fn drop(&mut self) {
+        let _ = self.release_session();
+    }
+}
+
+/// Merge chunks of several boards into one table sampled at `sampling_rate` Hz.
+///
+/// Chunks of the same source are concatenated in order. The common clock covers the time span all sources
+/// have data for, according to their timestamp channels. Rows are linearly interpolated, except marker rows
+/// where each marker is moved to the nearest tick. Timestamp rows of the sources are not repeated.
+pub fn merge(chunks: &[SourceChunk], sampling_rate: f64) -> Result<MergedTable> {
+    if sampling_rate <= 0.0 {
+        return Err(Error::InvalidMergeInput(format!("sampling rate {} is not positive", sampling_rate)));
+    }
+    let mut sources: Vec<(usize, BoardIds, BrainFlowPresets, Vec<&Array2<f64>>)> = Vec::new();
+    for chunk in chunks {
+        match sources.iter_mut().find(|(source, ..)| *source == chunk.source) {
+            Some((_, _, _, tables)) => tables.push(&chunk.data),
+            None => sources.push((chunk.source, chunk.board_id, chunk.preset, vec![&chunk.data])),
+        }
+    }
+    sources.sort_by_key(|(source, ..)| *source);
+
+    let mut tables = Vec::with_capacity(sources.len());
+    for (source, board_id, preset, parts) in sources {
+        let views = parts.iter().map(|t| t.view()).collect::<Vec<_>>();
+        let data = concatenate(Axis(1), &views)?;
+        let descr = BoardDescription::load(board_id, preset)?;
+        let timestamp_channel = descr.timestamp_channel().ok_or_else(|| {
+            Error::InvalidMergeInput(format!("{} has no timestamp channel", board_id))
+        })?;
+        if data.ncols() == 0 {
+            return Err(Error::InvalidMergeInput(format!("source {} has no data", source)));
+        }
+        tables.push((source, data, timestamp_channel, *descr.marker_channel()));
+    }
+    if tables.is_empty() {
+        return Err(Error::InvalidMergeInput("no chunks to merge".to_string()));
+    }
+
+    let start = tables
+        .iter()
+        .map(|(_, data, ts, _)| data[[*ts, 0]])
+        .fold(f64::MIN, f64::max);
+    let stop = tables
+        .iter()
+        .map(|(_, data, ts, _)| data[[*ts, data.ncols() - 1]])
+        .fold(f64::MAX, f64::min);
+    if stop < start {
+        return Err(Error::InvalidMergeInput("sources do not overlap in time".to_string()));
+    }
+    let num_ticks = ((stop - start) * sampling_rate).floor() as usize + 1;
+    let clock = (0..num_ticks)
+        .map(|i| start + i as f64 / sampling_rate)
+        .collect::<Vec<f64>>();
+
+    let mut rows = Vec::new();
+    let mut values = clock.clone();
+    for (source, data, timestamp_channel, marker_channel) in &tables {
+        let timestamps = data.row(*timestamp_channel).to_vec();
+        for (channel, row) in data.outer_iter().enumerate() {
+            if channel == *timestamp_channel {
+                continue;
+            }
+            let row = row.to_vec();
+            if Some(channel) == *marker_channel {
+                values.extend(place_markers(&timestamps, &row, &clock, sampling_rate));
+            } else {
+                values.extend(interpolate(&timestamps, &row, &clock));
+            }
+            rows.push(MergedRow {
+                source: *source,
+                channel,
+            });
+        }
+    }
+    let data = Array2::from_shape_vec((rows.len() + 1, clock.len()), values)?;
+    Ok(MergedTable { data, rows })
+}
+
+/// Linear interpolation of `values` at `timestamps` onto the sorted `clock`.
+fn interpolate(timestamps: &[f64], values: &[f64], clock: &[f64]) -> Vec<f64> {
+    let mut i = 0;
+    clock
+        .iter()
+        .map(|&t| {
+            while i + 1 < timestamps.len() && timestamps[i + 1] < t {
+                i += 1;
+            }
+            if i + 1 >= timestamps.len() || t <= timestamps[i] {
+                return values[i];
+            }
+            let (t0, t1) = (timestamps[i], timestamps[i + 1]);
+            if t1 <= t0 {
+                return values[i + 1];
+            }
+            values[i] + (values[i + 1] - values[i]) * (t - t0) / (t1 - t0)
+        })
+        .collect()
+}
+
+/// Move every non zero marker to the nearest tick of `clock`, markers outside of the clock are dropped.
+fn place_markers(timestamps: &[f64], markers: &[f64], clock: &[f64], sampling_rate: f64) -> Vec<f64> {
+    let mut placed = vec![0.0; clock.len()];
+    for (t, marker) in timestamps.iter().zip(markers) {
+        if *marker == 0.0 {
+            continue;
+        }
+        let tick = ((t - clock[0]) * sampling_rate).round();
+        if tick >= 0.0 && (tick as usize) < clock.len() {
+            placed[tick as usize] = *marker;
+        }
+    }
+    placed
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+    use std::{thread, time::Duration};
+
+    use super::{interpolate, merge, MultiBoardSession, SourceChunk};
+    use crate::board_shim::{self, BoardShim};
+    use crate::error::Error;
+    use crate::brainflow_input_params::BrainFlowInputParamsBuilder;
+    use crate::{BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_interpolate() {
+        let timestamps = [0.0, 1.0, 2.0];
+        let values = [0.0, 10.0, 30.0];
+        assert_eq!(vec![0.0, 5.0, 20.0, 30.0], interpolate(&timestamps, &values, &[0.0, 0.5, 1.5, 2.0]));
+    }
+
+    #[test]
+    fn test_merge_aligns_sources() {
+        let board_id = BoardIds::SyntheticBoard;
+        let preset = BrainFlowPresets::DefaultPreset;
+        let num_rows = board_shim::get_num_rows(board_id, preset).unwrap();
+        let timestamp_channel = board_shim::get_timestamp_channel(board_id, preset).unwrap();
+        let marker_channel = board_shim::get_marker_channel(board_id, preset).unwrap();
+        let table = |offset: f64, step: f64, n: usize| {
+            let mut data = Array2::zeros((num_rows, n));
+            for i in 0..n {
+                let t = offset + i as f64 * step;
+                data[[timestamp_channel, i]] = t;
+                data[[1, i]] = t * 100.0;
+            }
+            data
+        };
+        let mut first = table(0.0, 0.1, 11);
+        first[[marker_channel, 5]] = 7.0;
+        let chunks = vec![
+            SourceChunk::new(0, board_id, preset, first),
+            SourceChunk::new(1, board_id, preset, table(0.2, 0.2, 4)),
+            SourceChunk::new(1, board_id, preset, table(1.0, 0.2, 4)),
+        ];
+
+        let merged = merge(&chunks, 10.0).unwrap();
+        let data = merged.data();
+        assert_eq!(2 * (num_rows - 1) + 1, data.nrows());
+        // common span is 0.2 to 1.0
+        assert_relative_eq!(0.2, data[[0, 0]]);
+        assert_relative_eq!(1.0, data[[0, data.ncols() - 1]], max_relative = 1e-9);
+        let row = merged.rows().iter().position(|r| r.source == 1 && r.channel == 1).unwrap() + 1;
+        for i in 0..data.ncols() {
+            assert_relative_eq!(data[[0, i]] * 100.0, data[[row, i]], max_relative = 1e-9);
+        }
+        let markers = merged.rows().iter().position(|r| r.source == 0 && r.channel == marker_channel).unwrap() + 1;
+        assert_relative_eq!(7.0, data[[markers, 3]]);
+        assert_relative_eq!(7.0, data.row(markers).sum());
+    }
+
+    #[test]
+    fn test_start_requires_prepared_boards() {
+        let params = BrainFlowInputParamsBuilder::new().other_info("multi_board_unprepared").build();
+        let mut session = MultiBoardSession::new(vec![BoardShim::new(BoardIds::SyntheticBoard, params).unwrap()]);
+        match session.start_stream(45000, "") {
+            Err(Error::Board { operation: "start_stream", board_id: BoardIds::SyntheticBoard, .. }) => {}
+            res => panic!("unexpected {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_two_synthetic_boards() {
+        let board = |other_info: &str| {
+            let params = BrainFlowInputParamsBuilder::new().other_info(other_info).build();
+            BoardShim::new(BoardIds::SyntheticBoard, params).unwrap()
+        };
+        let mut session = MultiBoardSession::new(vec![board("multi_board_a"), board("multi_board_b")]);
+        session.prepare_session().unwrap();
+        session.start_stream(45000, "").unwrap();
+        thread::sleep(Duration::from_millis(300));
+        session.stop_stream().unwrap();
+
+        let chunks = session.get_board_data(BrainFlowPresets::DefaultPreset).unwrap();
+        assert_eq!(2, chunks.len());
+        assert_eq!(1, *chunks[1].source());
+        let merged = merge(&chunks, 100.0).unwrap();
+        assert!(merged.data().ncols() > 0);
+        session.release_session().unwrap();
+    }
+}
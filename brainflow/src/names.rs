@@ -0,0 +1,336 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+use crate::{
+    error::Error, AggOperations, BoardIds, BrainFlowClassifiers, BrainFlowExitCodes, BrainFlowMetrics,
+    BrainFlowPresets, DetrendOperations, FilterTypes, IpProtocolTypes, LogLevels, NoiseEstimationLevelTypes,
+    NoiseTypes, Result, ThresholdTypes, WaveletDenoisingTypes, WaveletExtensionTypes, WaveletTypes,
+    WindowOperations,
+};
+
+/// Compare names ignoring case and underscores, so that `SyntheticBoard` and `SYNTHETIC_BOARD` match.
+fn names_match(name: &str, variant: &str) -> bool {
+    let mut name = name.chars().filter(|c| *c != '_');
+    let mut variant = variant.chars();
+    loop {
+        match (name.next(), variant.next()) {
+            (Some(a), Some(b)) if a.eq_ignore_ascii_case(&b) => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Implement `all`, `name`, [FromStr] and string based serde for a constants enum.
+///
+/// Names are the variant names, parsing also accepts the native upper snake case names.
+macro_rules! impl_names {
+    ($enum:ident { $($variant:ident),+ $(,)? }) => {
+        impl $enum {
+            /// All variants, in declaration order.
+            pub fn all() -> &'static [$enum] {
+                &[$($enum::$variant),+]
+            }
+
+            /// Name of the variant.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($enum::$variant => stringify!($variant)),+
+                }
+            }
+        }
+
+        impl FromStr for $enum {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self> {
+                $enum::all()
+                    .iter()
+                    .find(|v| names_match(s, v.name()))
+                    .copied()
+                    .ok_or_else(|| Error::UnknownName(stringify!($enum), s.to_string()))
+            }
+        }
+
+        impl Serialize for $enum {
+            fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(self.name())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $enum {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_names!(BoardIds {
+    NoBoard,
+    PlaybackFileBoard,
+    StreamingBoard,
+    SyntheticBoard,
+    CytonBoard,
+    GanglionBoard,
+    CytonDaisyBoard,
+    GaleaBoard,
+    GanglionWifiBoard,
+    CytonWifiBoard,
+    CytonDaisyWifiBoard,
+    BrainbitBoard,
+    UnicornBoard,
+    CallibriEegBoard,
+    CallibriEmgBoard,
+    CallibriEcgBoard,
+    Notion1Board,
+    Notion2Board,
+    GforceProBoard,
+    Freeeeg32Board,
+    BrainbitBledBoard,
+    GforceDualBoard,
+    GaleaSerialBoard,
+    MuseSBledBoard,
+    Muse2BledBoard,
+    CrownBoard,
+    AntNeuroEe410Board,
+    AntNeuroEe411Board,
+    AntNeuroEe430Board,
+    AntNeuroEe211Board,
+    AntNeuroEe212Board,
+    AntNeuroEe213Board,
+    AntNeuroEe214Board,
+    AntNeuroEe215Board,
+    AntNeuroEe221Board,
+    AntNeuroEe222Board,
+    AntNeuroEe223Board,
+    AntNeuroEe224Board,
+    AntNeuroEe225Board,
+    EnophoneBoard,
+    Muse2Board,
+    MuseSBoard,
+    BrainaliveBoard,
+    Muse2016Board,
+    Muse2016BledBoard,
+    Explore4ChanBoard,
+    Explore8ChanBoard,
+    GanglionNativeBoard,
+    EmotibitBoard,
+    GaleaBoardV4,
+    GaleaSerialBoardV4,
+    NtlWifiBoard,
+    AntNeuroEe511Board,
+    Freeeeg128Board,
+    AavaaV3Board,
+});
+
+impl_names!(BrainFlowExitCodes {
+    StatusOk,
+    PortAlreadyOpenError,
+    UnableToOpenPortError,
+    SetPortError,
+    BoardWriteError,
+    IncommingMsgError,
+    InitialMsgError,
+    BoardNotReadyError,
+    StreamAlreadyRunError,
+    InvalidBufferSizeError,
+    StreamThreadError,
+    StreamThreadIsNotRunning,
+    EmptyBufferError,
+    InvalidArgumentsError,
+    UnsupportedBoardError,
+    BoardNotCreatedError,
+    AnotherBoardIsCreatedError,
+    GeneralError,
+    SyncTimeoutError,
+    JsonNotFoundError,
+    NoSuchDataInJsonError,
+    ClassifierIsNotPreparedError,
+    AnotherClassifierIsPreparedError,
+    UnsupportedClassifierAndMetricCombinationError,
+});
+
+impl_names!(IpProtocolTypes {
+    NoIpProtocol,
+    Udp,
+    Tcp,
+});
+
+impl_names!(FilterTypes {
+    Butterworth,
+    ChebyshevType1,
+    Bessel,
+    ButterworthZeroPhase,
+    ChebyshevType1ZeroPhase,
+    BesselZeroPhase,
+});
+
+impl_names!(AggOperations {
+    Mean,
+    Median,
+    Each,
+});
+
+impl_names!(WindowOperations {
+    NoWindow,
+    Hanning,
+    Hamming,
+    BlackmanHarris,
+});
+
+impl_names!(DetrendOperations {
+    NoDetrend,
+    Constant,
+    Linear,
+});
+
+impl_names!(BrainFlowMetrics {
+    Mindfulness,
+    Restfulness,
+    UserDefined,
+});
+
+impl_names!(BrainFlowClassifiers {
+    DefaultClassifier,
+    DynLibClassifier,
+    OnnxClassifier,
+});
+
+impl_names!(BrainFlowPresets {
+    DefaultPreset,
+    AuxiliaryPreset,
+    AncillaryPreset,
+});
+
+impl_names!(LogLevels {
+    LevelTrace,
+    LevelDebug,
+    LevelInfo,
+    LevelWarn,
+    LevelError,
+    LevelCritical,
+    LevelOff,
+});
+
+impl_names!(NoiseTypes {
+    Fifty,
+    Sixty,
+    FiftyAndSixty,
+});
+
+impl_names!(WaveletDenoisingTypes {
+    Visushrink,
+    Sureshrink,
+});
+
+impl_names!(ThresholdTypes {
+    Soft,
+    Hard,
+});
+
+impl_names!(WaveletExtensionTypes {
+    Symmetric,
+    Periodic,
+});
+
+impl_names!(NoiseEstimationLevelTypes {
+    FirstLevel,
+    AllLevels,
+});
+
+impl_names!(WaveletTypes {
+    Haar,
+    Db1,
+    Db2,
+    Db3,
+    Db4,
+    Db5,
+    Db6,
+    Db7,
+    Db8,
+    Db9,
+    Db10,
+    Db11,
+    Db12,
+    Db13,
+    Db14,
+    Db15,
+    Bior11,
+    Bior13,
+    Bior15,
+    Bior22,
+    Bior24,
+    Bior26,
+    Bior28,
+    Bior31,
+    Bior33,
+    Bior35,
+    Bior37,
+    Bior39,
+    Bior44,
+    Bior55,
+    Bior68,
+    Coif1,
+    Coif2,
+    Coif3,
+    Coif4,
+    Coif5,
+    Sym2,
+    Sym3,
+    Sym4,
+    Sym5,
+    Sym6,
+    Sym7,
+    Sym8,
+    Sym9,
+    Sym10,
+});
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoardIds, LogLevels, NoiseTypes, WaveletTypes};
+
+    #[test]
+    fn test_all_board_ids_are_listed() {
+        let num_ids = (-100..=100)
+            .filter_map(<BoardIds as num::FromPrimitive>::from_i32)
+            .map(|id| assert!(BoardIds::all().contains(&id)))
+            .count();
+        assert_eq!(num_ids, BoardIds::all().len());
+    }
+
+    #[test]
+    fn test_parse_board_ids() {
+        for id in BoardIds::all() {
+            assert_eq!(*id, id.name().parse().unwrap());
+            assert_eq!(*id, id.to_string().parse().unwrap());
+        }
+        assert_eq!(BoardIds::SyntheticBoard, "SYNTHETIC_BOARD".parse().unwrap());
+        assert_eq!(BoardIds::AntNeuroEe410Board, "ANT_NEURO_EE_410_BOARD".parse().unwrap());
+        assert!("SyntheticBoardd".parse::<BoardIds>().is_err());
+    }
+
+    #[test]
+    fn test_serde_board_ids() {
+        assert_eq!("\"CytonDaisyBoard\"", serde_json::to_string(&BoardIds::CytonDaisyBoard).unwrap());
+        assert_eq!(BoardIds::MuseSBoard, serde_json::from_str::<BoardIds>("\"MUSE_S_BOARD\"").unwrap());
+    }
+
+    #[test]
+    fn test_parse_other_constants() {
+        assert_eq!(LogLevels::LevelWarn, "LEVEL_WARN".parse().unwrap());
+        assert_eq!(NoiseTypes::FiftyAndSixty, "FiftyAndSixty".parse().unwrap());
+        assert_eq!(WaveletTypes::Db4, "db4".parse().unwrap());
+        assert_eq!(WaveletTypes::Sym10, WaveletTypes::all()[WaveletTypes::all().len() - 1]);
+        let err = "Sixtyy".parse::<NoiseTypes>().unwrap_err();
+        assert_eq!("Unknown NoiseTypes name `Sixtyy`", err.to_string());
+    }
+}
@@ -0,0 +1,317 @@
+use libloading::{library_filename, Library};
+use std::{
+    cell::RefCell,
+    env,
+    ffi::CStr,
+    ops::Range,
+    os::raw::{c_char, c_int},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{
+    check_brainflow_exit_code,
+    error::{Error, NativeCall, NativeModule},
+    Result,
+};
+
+/// Directory searched first for the native libraries.
+pub const LIB_DIR_ENV: &str = "BRAINFLOW_LIB_DIR";
+
+/// Native versions the bindings were generated for.
+pub const SUPPORTED_VERSIONS: Range<(u32, u32, u32)> = (5, 0, 0)..(6, 0, 0);
+/// Version reported by native builds from an untagged source tree, always accepted.
+pub const DEVELOPMENT_VERSION: (u32, u32, u32) = (0, 0, 1);
+
+/// Exit code the native wrappers return when the libraries could not be loaded, not used by BrainFlow.
+pub(crate) const NOT_LOADED: c_int = c_int::MIN;
+
+static LIBRARIES: OnceLock<NativeLibraries> = OnceLock::new();
+
+thread_local! {
+    /// Why the last native call of this thread returned [NOT_LOADED].
+    static LOAD_ERROR: RefCell<Option<Error>> = const { RefCell::new(None) };
+}
+
+/// The three native libraries, loaded at runtime with matching versions.
+#[derive(Debug)]
+pub struct NativeLibraries {
+    /// Indexed by [NativeModule].
+    libraries: [Library; 3],
+    /// Path each library was opened with, a bare file name if it was found in the system paths.
+    paths: [PathBuf; 3],
+    version: String,
+}
+
+impl NativeLibraries {
+    /// Version reported by all three libraries.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Path the library of `module` was opened with.
+    pub fn path(&self, module: NativeModule) -> &Path {
+        &self.paths[module as usize]
+    }
+}
+
+/// Where to look for the native libraries.
+///
+/// Each library is taken from the first of these locations which contains it:
+/// the directory in [LIB_DIR_ENV], the configured directories, and the system library paths.
+#[derive(Debug, Clone)]
+pub struct LibraryLoader {
+    dirs: Vec<PathBuf>,
+    system_paths: bool,
+}
+
+impl Default for LibraryLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LibraryLoader {
+    /// Loader searching [LIB_DIR_ENV] and the system paths.
+    pub fn new() -> Self {
+        Self {
+            dirs: Vec::new(),
+            system_paths: true,
+        }
+    }
+
+    /// Also search `dir`, after the directories added before.
+    pub fn dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.dirs.push(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Whether the system library paths are searched last, enabled by default.
+    pub fn system_paths(mut self, system_paths: bool) -> Self {
+        self.system_paths = system_paths;
+        self
+    }
+
+    /// Load and check the libraries, unless they were loaded before.
+    ///
+    /// Libraries stay loaded for the lifetime of the process, a failed attempt can be repeated
+    /// with other directories. Once loaded, asking for directories which the libraries were not
+    /// loaded from fails with [Error::IncompatibleLibraries].
+    pub fn load(&self) -> Result<&'static NativeLibraries> {
+        let libraries = match LIBRARIES.get() {
+            Some(libraries) => libraries,
+            None => {
+                let libraries = self.open()?;
+                LIBRARIES.get_or_init(|| libraries)
+            }
+        };
+        let loaded = libraries.path(NativeModule::BoardController);
+        let file_name = PathBuf::from(library_filename("BoardController"));
+        let requested = self.clone().system_paths(false).candidates(&file_name);
+        if !self.dirs.is_empty() && !requested.iter().any(|c| c == loaded) {
+            return Err(Error::IncompatibleLibraries(format!(
+                "libraries were already loaded from {}, not from {}",
+                loaded.display(),
+                self.dirs.iter().map(|d| d.display().to_string()).collect::<Vec<_>>().join(", ")
+            )));
+        }
+        Ok(libraries)
+    }
+
+    fn open(&self) -> Result<NativeLibraries> {
+        let (board_controller, board_controller_path) = self.open_library("BoardController")?;
+        let (data_handler, data_handler_path) = self.open_library("DataHandler")?;
+        let (ml_module, ml_module_path) = self.open_library("MLModule")?;
+        let libraries = [board_controller, data_handler, ml_module];
+        let paths = [board_controller_path, data_handler_path, ml_module_path];
+
+        let modules = [NativeModule::BoardController, NativeModule::DataHandler, NativeModule::MlModule];
+        let versions = modules
+            .iter()
+            .map(|module| {
+                let version = unsafe { read_version(&libraries[*module as usize], *module)? };
+                Ok((paths[*module as usize].display().to_string(), version))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let version = check_versions(&versions)?;
+
+        Ok(NativeLibraries {
+            libraries,
+            paths,
+            version,
+        })
+    }
+
+    fn candidates(&self, file_name: &Path) -> Vec<PathBuf> {
+        let env_dir = env::var_os(LIB_DIR_ENV).filter(|dir| !dir.is_empty()).map(PathBuf::from);
+        let mut candidates = env_dir
+            .iter()
+            .chain(&self.dirs)
+            .map(|dir| dir.join(file_name))
+            .collect::<Vec<_>>();
+        if self.system_paths {
+            candidates.push(file_name.to_path_buf());
+        }
+        candidates
+    }
+
+    fn open_library(&self, name: &str) -> Result<(Library, PathBuf)> {
+        let file_name = PathBuf::from(library_filename(name));
+        let mut errors = Vec::new();
+        for candidate in self.candidates(&file_name) {
+            // loading a library runs its initializers, the BrainFlow libraries have none with preconditions
+            match unsafe { Library::new(&candidate) } {
+                Ok(library) => return Ok((library, candidate)),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+        let reason = if errors.is_empty() {
+            format!("no directory to search, set {} or add a directory", LIB_DIR_ENV)
+        } else {
+            errors.join("; ")
+        };
+        Err(Error::LibraryNotFound(file_name.display().to_string(), reason))
+    }
+}
+
+/// Load the native libraries with the default [LibraryLoader].
+///
+/// Native functions load the libraries on first use and fail with [Error::LibraryNotFound] if that
+/// fails, call this or [LibraryLoader::load] first to load them from other directories.
+pub fn load() -> Result<&'static NativeLibraries> {
+    LibraryLoader::new().load()
+}
+
+/// Symbol `name` of the library of `module`, `name` ends with a nul byte.
+///
+/// # Safety
+/// `F` must be the type of the symbol.
+pub(crate) unsafe fn symbol<F: Copy>(module: NativeModule, name: &[u8]) -> Result<F> {
+    let libraries = load()?;
+    match libraries.libraries[module as usize].get::<F>(name) {
+        Ok(symbol) => Ok(*symbol),
+        Err(e) => Err(Error::IncompatibleLibraries(e.to_string())),
+    }
+}
+
+/// Keep `error` for [take_load_error] and return [NOT_LOADED] from a native wrapper.
+pub(crate) fn not_loaded(error: Error) -> c_int {
+    LOAD_ERROR.with(|e| *e.borrow_mut() = Some(error));
+    NOT_LOADED
+}
+
+/// Why the last native call of this thread returned [NOT_LOADED].
+pub(crate) fn take_load_error() -> Error {
+    LOAD_ERROR
+        .with(|e| e.borrow_mut().take())
+        .unwrap_or_else(|| Error::LibraryNotFound(String::new(), "the native libraries are not loaded".to_string()))
+}
+
+/// Call `get_version_<module>` of `library`.
+unsafe fn read_version(library: &Library, module: NativeModule) -> Result<String> {
+    const MAX_CHARS: usize = 64;
+    type GetVersion = unsafe extern "C" fn(*mut c_char, *mut c_int, c_int) -> c_int;
+
+    let operation = match module {
+        NativeModule::BoardController => "get_version_board_controller",
+        NativeModule::DataHandler => "get_version_data_handler",
+        NativeModule::MlModule => "get_version_ml_module",
+    };
+    let get_version = library
+        .get::<GetVersion>(format!("{}\0", operation).as_bytes())
+        .map_err(|e| Error::IncompatibleLibraries(e.to_string()))?;
+
+    let mut response_len = 0;
+    let mut result_char_buffer: [c_char; MAX_CHARS] = [0; MAX_CHARS];
+    let res = get_version(result_char_buffer.as_mut_ptr(), &mut response_len, MAX_CHARS as i32);
+    check_brainflow_exit_code(res, NativeCall::new(module, operation))?;
+    Ok(CStr::from_ptr(result_char_buffer.as_ptr()).to_str()?.to_string())
+}
+
+/// Check that all libraries, given as path and version, have the same supported version.
+fn check_versions(versions: &[(String, String)]) -> Result<String> {
+    let version = &versions[0].1;
+    if versions.iter().any(|(_, v)| v != version) {
+        let versions = versions
+            .iter()
+            .map(|(path, version)| format!("{} is {}", path, version))
+            .collect::<Vec<_>>();
+        return Err(Error::IncompatibleLibraries(format!("versions differ, {}", versions.join(", "))));
+    }
+    match parse_version(version) {
+        Some(v) if SUPPORTED_VERSIONS.contains(&v) || v == DEVELOPMENT_VERSION => Ok(version.clone()),
+        _ => Err(Error::IncompatibleLibraries(format!(
+            "version {} is not supported, expected {:?} up to {:?}",
+            version, SUPPORTED_VERSIONS.start, SUPPORTED_VERSIONS.end
+        ))),
+    }
+}
+
+/// Parse a `major.minor.patch` version.
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().split('.').map(|part| part.parse::<u32>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    match parts.next() {
+        None => Some(version),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::{check_versions, load, not_loaded, parse_version, take_load_error, LibraryLoader};
+    use crate::{
+        board_shim, check_brainflow_exit_code, data_filter,
+        error::{Error, NativeCall, NativeModule},
+    };
+
+    fn versions(versions: &[&str]) -> Vec<(String, String)> {
+        versions.iter().enumerate().map(|(i, v)| (format!("lib{}", i), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_check_versions() {
+        assert_eq!(Some((5, 12, 1)), parse_version("5.12.1"));
+        assert_eq!(None, parse_version("5.12"));
+        assert_eq!(None, parse_version("5.12.1.3"));
+
+        assert_eq!("5.12.1", check_versions(&versions(&["5.12.1", "5.12.1", "5.12.1"])).unwrap());
+        assert_eq!("0.0.1", check_versions(&versions(&["0.0.1", "0.0.1", "0.0.1"])).unwrap());
+        assert!(matches!(
+            check_versions(&versions(&["5.12.1", "5.11.0", "5.12.1"])),
+            Err(Error::IncompatibleLibraries(_))
+        ));
+        assert!(matches!(
+            check_versions(&versions(&["4.9.0", "4.9.0", "4.9.0"])),
+            Err(Error::IncompatibleLibraries(_))
+        ));
+    }
+
+    #[test]
+    fn test_load() {
+        let missing = LibraryLoader::new().dir(env::temp_dir().join("brainflow_no_libs")).system_paths(false);
+        if env::var_os(super::LIB_DIR_ENV).is_none() {
+            assert!(matches!(missing.open(), Err(Error::LibraryNotFound(_, _))));
+        }
+
+        // other tests may have loaded the libraries from the system paths already
+        let libraries = load().unwrap();
+        assert_eq!(board_shim::get_version().unwrap(), libraries.version());
+        assert_eq!(data_filter::get_version().unwrap(), libraries.version());
+        if let Some(dir) = libraries.path(NativeModule::BoardController).parent().filter(|d| !d.as_os_str().is_empty()) {
+            assert!(LibraryLoader::new().dir(dir).load().is_ok());
+        }
+        let other_dir = env::temp_dir().join("brainflow_other_libs");
+        assert!(matches!(LibraryLoader::new().dir(other_dir).load(), Err(Error::IncompatibleLibraries(_))));
+    }
+
+    #[test]
+    fn test_load_error_is_returned() {
+        let code = not_loaded(Error::LibraryNotFound("libBoardController.so".to_string(), "missing".to_string()));
+        let res = check_brainflow_exit_code(code, NativeCall::board_controller("get_version_board_controller"));
+        assert!(matches!(res, Err(Error::LibraryNotFound(_, _))));
+        assert!(matches!(take_load_error(), Error::LibraryNotFound(_, _)));
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    env,
+    fs::File,
+    io::Read,
+    path::Path,
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+
+use crate::{board_shim, data_filter, error::NativeModule, ml_model, LogLevels, Result};
+
+/// Log target of messages from the board controller.
+pub const BOARD_CONTROLLER_TARGET: &str = "brainflow::board_controller";
+/// Log target of messages from the data handler.
+pub const DATA_HANDLER_TARGET: &str = "brainflow::data_handler";
+/// Log target of messages from the ml module.
+pub const ML_MODULE_TARGET: &str = "brainflow::ml_module";
+
+/// How often the managed log file is checked for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Whether the bridge was started, held while starting it so that concurrent calls start it once.
+static BRIDGE: Mutex<bool> = Mutex::new(false);
+
+/// Redirect the logs of all three native modules to a managed file in the temp directory and re-emit every
+/// line as a [log] record, see [init_with_file].
+pub fn init(level: LogLevels) -> Result<()> {
+    let log_file = env::temp_dir().join(format!("brainflow-{}.log", std::process::id()));
+    init_with_file(log_file, level)
+}
+
+/// Redirect the logs of all three native modules to `log_file` and re-emit every line as a [log] record
+/// with target [BOARD_CONTROLLER_TARGET], [DATA_HANDLER_TARGET] or [ML_MODULE_TARGET].
+///
+/// The file is truncated and read by a background thread. Tracing subscribers receive the records through
+/// `tracing-log`. Only the first call starts the bridge, later calls only set the level.
+pub fn init_with_file<P: AsRef<Path>>(log_file: P, level: LogLevels) -> Result<()> {
+    let log_file = log_file.as_ref();
+    let mut started = BRIDGE.lock().unwrap_or_else(|e| e.into_inner());
+    if !*started {
+        File::create(log_file)?;
+        let path = log_file.to_str().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "log file path is not valid unicode")
+        })?;
+        board_shim::set_log_file(path)?;
+        data_filter::set_log_file(path)?;
+        ml_model::set_log_file(path)?;
+        let mut file = File::open(log_file)?;
+        thread::spawn(move || tail(&mut file));
+        *started = true;
+    }
+    drop(started);
+    set_level(level)
+}
+
+/// Set the level of all three native loggers.
+pub fn set_level(level: LogLevels) -> Result<()> {
+    board_shim::set_log_level(level)?;
+    data_filter::set_log_level(level)?;
+    ml_model::set_log_level(level)
+}
+
+/// Emit every line appended to `file`, forever.
+fn tail(file: &mut File) {
+    let mut pending = Vec::new();
+    let mut last = (NativeModule::BoardController, log::Level::Info);
+    loop {
+        match file.read_to_end(&mut pending) {
+            Ok(0) | Err(_) => thread::sleep(POLL_INTERVAL),
+            Ok(_) => {
+                while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+                    let line = String::from_utf8_lossy(&pending[..end]).into_owned();
+                    pending.drain(..=end);
+                    // continuation lines of multi line messages belong to the previous message
+                    let (module, level, message) = match parse_line(&line) {
+                        Some(parsed) => parsed,
+                        None => (last.0, last.1, line.as_str()),
+                    };
+                    last = (module, level);
+                    log::log!(target: target(module), level, "{}", message);
+                }
+            }
+        }
+    }
+}
+
+/// Split a spdlog line like `[2024-01-01 10:00:00.000] [board_logger] [info] message`
+/// into module, level and message.
+fn parse_line(line: &str) -> Option<(NativeModule, log::Level, &str)> {
+    let rest = line.strip_prefix('[')?;
+    let (_, rest) = rest.split_once("] [")?;
+    let (logger, rest) = rest.split_once("] [")?;
+    let (level, message) = rest.split_once("] ").or_else(|| rest.strip_suffix(']').map(|l| (l, "")))?;
+    let module = match logger {
+        "board_logger" => NativeModule::BoardController,
+        "data_logger" => NativeModule::DataHandler,
+        "ml_logger" => NativeModule::MlModule,
+        _ => return None,
+    };
+    let level = match level {
+        "trace" => log::Level::Trace,
+        "debug" => log::Level::Debug,
+        "info" => log::Level::Info,
+        "warning" | "warn" => log::Level::Warn,
+        "error" | "critical" => log::Level::Error,
+        _ => return None,
+    };
+    Some((module, level, message))
+}
+
+/// Log target of the messages of `module`.
+fn target(module: NativeModule) -> &'static str {
+    match module {
+        NativeModule::BoardController => BOARD_CONTROLLER_TARGET,
+        NativeModule::DataHandler => DATA_HANDLER_TARGET,
+        NativeModule::MlModule => ML_MODULE_TARGET,
+    }
+}
+
+/// A [log::Log] which forwards records to the native logger of a module through its `log_message`,
+/// so that Rust and native messages end up in the same log.
+///
+/// Records with the targets of the bridge are dropped, so it can be used together with [init].
+pub struct BrainFlowLogger {
+    module: NativeModule,
+}
+
+impl BrainFlowLogger {
+    /// Logger which forwards to the native logger of `module`.
+    pub fn new(module: NativeModule) -> Self {
+        Self { module }
+    }
+
+    /// Install a logger forwarding to the board controller as the global logger.
+    pub fn install() -> std::result::Result<(), log::SetLoggerError> {
+        log::set_boxed_logger(Box::new(Self::new(NativeModule::BoardController)))?;
+        log::set_max_level(log::LevelFilter::Trace);
+        Ok(())
+    }
+}
+
+impl log::Log for BrainFlowLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        ![BOARD_CONTROLLER_TARGET, DATA_HANDLER_TARGET, ML_MODULE_TARGET].contains(&metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = match record.level() {
+            log::Level::Trace => LogLevels::LevelTrace,
+            log::Level::Debug => LogLevels::LevelDebug,
+            log::Level::Info => LogLevels::LevelInfo,
+            log::Level::Warn => LogLevels::LevelWarn,
+            log::Level::Error => LogLevels::LevelError,
+        };
+        let message = format!("{}: {}", record.target(), record.args());
+        let _ = match self.module {
+            NativeModule::BoardController => board_shim::log_message(level, message),
+            NativeModule::DataHandler => data_filter::log_message(level, message),
+            NativeModule::MlModule => ml_model::log_message(level, message),
+        };
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        sync::Mutex,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::{init_with_file, parse_line, BOARD_CONTROLLER_TARGET};
+    use crate::{board_shim, error::NativeModule, LogLevels};
+
+    /// Records seen by [CapturingLogger], as target and message.
+    static RECORDS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            RECORDS.lock().unwrap().push((record.target().to_string(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            Some((NativeModule::DataHandler, log::Level::Warn, "low battery")),
+            parse_line("[2024-01-01 10:00:00.000] [data_logger] [warning] low battery")
+        );
+        assert_eq!(
+            Some((NativeModule::BoardController, log::Level::Error, "")),
+            parse_line("[2024-01-01 10:00:00.000] [board_logger] [critical]")
+        );
+        assert_eq!(None, parse_line("[2024-01-01 10:00:00.000] [other] [info] x"));
+        assert_eq!(None, parse_line("continued message"));
+    }
+
+    #[test]
+    fn test_bridge_emits_native_messages() {
+        log::set_logger(&CapturingLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+        let log_file = env::temp_dir().join("brainflow_tests/rust/native_log_bridge.log");
+        std::fs::create_dir_all(log_file.parent().unwrap()).unwrap();
+        init_with_file(&log_file, LogLevels::LevelInfo).unwrap();
+        board_shim::log_message(LogLevels::LevelWarn, "bridged message").unwrap();
+
+        let start = Instant::now();
+        let expected = (BOARD_CONTROLLER_TARGET.to_string(), "bridged message".to_string());
+        while !RECORDS.lock().unwrap().contains(&expected) {
+            assert!(start.elapsed() < Duration::from_secs(5), "message was not bridged");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
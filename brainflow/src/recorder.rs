@@ -0,0 +1,673 @@
+use getset::Getters;
+use ndarray::{concatenate, Array2, Axis};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    board::Board, board_description::BoardDescription, board_shim::BoardShim, data_filter, error::Error, BoardIds,
+    BrainFlowPresets, Result,
+};
+
+/// Extension of chunked recording files.
+pub const EXTENSION: &str = "bfrec";
+
+pub(crate) const FILE_MAGIC: &[u8; 8] = b"BFREC\x00\x01\x00";
+const CHUNK_MAGIC: &[u8; 4] = b"CHNK";
+/// Magic, number of rows and number of samples.
+const CHUNK_HEADER_BYTES: usize = 12;
+const CHECKSUM_BYTES: usize = 4;
+
+/// When a recorder asks the operating system to write its data to the disk.
+///
+/// Every chunk is handed to the operating system when it is written, so a crash of the process loses
+/// nothing, syncing also protects the data against power loss.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Sync after every chunk.
+    EveryChunk,
+    /// Sync after the first chunk written this long after the last sync.
+    Interval(Duration),
+    /// Sync only when a file is finished.
+    OnClose,
+}
+
+/// Where and how a [Recorder] or [ChunkWriter] writes its files.
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    dir: PathBuf,
+    name: String,
+    sync: SyncPolicy,
+    max_file_size: Option<u64>,
+    max_file_duration: Option<Duration>,
+    poll_interval: Duration,
+}
+
+impl RecorderConfig {
+    /// Files `<name>_0000.bfrec`, `<name>_0001.bfrec`, ... in `dir`, synced every second and never rotated.
+    pub fn new<P: AsRef<Path>, S: Into<String>>(dir: P, name: S) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            name: name.into(),
+            sync: SyncPolicy::Interval(Duration::from_secs(1)),
+            max_file_size: None,
+            max_file_duration: None,
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+
+    /// When to sync the data to the disk.
+    pub fn sync(mut self, sync: SyncPolicy) -> Self {
+        self.sync = sync;
+        self
+    }
+
+    /// Start a new file before a chunk would make the current one larger than `bytes`.
+    ///
+    /// A file holds at least one chunk, so chunks larger than `bytes` get a file of their own.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Start a new file once the current one was started this long ago.
+    pub fn max_file_duration(mut self, duration: Duration) -> Self {
+        self.max_file_duration = Some(duration);
+        self
+    }
+
+    /// How often a [Recorder] drains the board, 100 ms by default.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Path of the file with index `index`.
+    pub fn file_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}_{:04}.{}", self.name, index, EXTENSION))
+    }
+}
+
+/// Metadata header of every chunked recording file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct RecordingMetadata {
+    board_id: BoardIds,
+    preset: BrainFlowPresets,
+    description: BoardDescription,
+    /// Unix time the file was started, in seconds.
+    created: f64,
+    /// Index of the file among the rotated files of the recording, starting at 0.
+    file_index: usize,
+    /// Version of the crate which wrote the file.
+    version: String,
+}
+
+/// Writes board data chunks to append-only files with a metadata header and a checksum per chunk.
+///
+/// A file cut off at any point, e.g. by a crash or power loss, keeps all chunks written before,
+/// see [recover].
+pub struct ChunkWriter {
+    config: RecorderConfig,
+    board_id: BoardIds,
+    preset: BrainFlowPresets,
+    description: BoardDescription,
+    file: File,
+    file_size: u64,
+    file_chunks: usize,
+    file_started: Instant,
+    last_sync: Instant,
+    files: Vec<PathBuf>,
+}
+
+impl ChunkWriter {
+    /// Create the first file of a recording of `preset` of a board with the given description.
+    pub fn create(
+        config: RecorderConfig,
+        board_id: BoardIds,
+        preset: BrainFlowPresets,
+        description: BoardDescription,
+    ) -> Result<Self> {
+        if config.name.is_empty() {
+            return Err(Error::InvalidRecording("recording name is empty".to_string()));
+        }
+        fs::create_dir_all(&config.dir)?;
+        let (file, file_size) = create_file(&config, board_id, preset, &description, 0)?;
+        Ok(Self {
+            files: vec![config.file_path(0)],
+            config,
+            board_id,
+            preset,
+            description,
+            file,
+            file_size,
+            file_chunks: 0,
+            file_started: Instant::now(),
+            last_sync: Instant::now(),
+        })
+    }
+
+    /// Files written so far, the current one last.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Append a chunk with the rows of the board description and one sample per column.
+    pub fn write(&mut self, chunk: &Array2<f64>) -> Result<()> {
+        let num_rows = *self.description.num_rows();
+        if chunk.nrows() != num_rows {
+            return Err(Error::InvalidRecording(format!(
+                "chunk has {} rows instead of {}",
+                chunk.nrows(),
+                num_rows
+            )));
+        }
+        if chunk.ncols() == 0 {
+            return Ok(());
+        }
+        let bytes = encode_chunk(chunk);
+        if self.file_chunks > 0 && self.should_rotate(bytes.len() as u64) {
+            self.rotate()?;
+        }
+        self.file.write_all(&bytes)?;
+        self.file_size += bytes.len() as u64;
+        self.file_chunks += 1;
+        let sync = match self.config.sync {
+            SyncPolicy::EveryChunk => true,
+            SyncPolicy::Interval(interval) => self.last_sync.elapsed() >= interval,
+            SyncPolicy::OnClose => false,
+        };
+        if sync {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Sync the current file to the disk.
+    pub fn sync(&mut self) -> Result<()> {
+        self.file.sync_data()?;
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+
+    /// Sync the current file and return all files of the recording.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>> {
+        self.sync()?;
+        Ok(self.files)
+    }
+
+    fn should_rotate(&self, chunk_bytes: u64) -> bool {
+        let too_large = self.config.max_file_size.is_some_and(|max| self.file_size + chunk_bytes > max);
+        let too_long = self.config.max_file_duration.is_some_and(|max| self.file_started.elapsed() >= max);
+        too_large || too_long
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        self.sync()?;
+        let index = self.files.len();
+        let (file, file_size) = create_file(&self.config, self.board_id, self.preset, &self.description, index)?;
+        self.file = file;
+        self.file_size = file_size;
+        self.file_chunks = 0;
+        self.file_started = Instant::now();
+        self.files.push(self.config.file_path(index));
+        Ok(())
+    }
+}
+
+/// Create a file with its metadata header, synced together with its directory entry.
+fn create_file(
+    config: &RecorderConfig,
+    board_id: BoardIds,
+    preset: BrainFlowPresets,
+    description: &BoardDescription,
+    file_index: usize,
+) -> Result<(File, u64)> {
+    let metadata = RecordingMetadata {
+        board_id,
+        preset,
+        description: description.clone(),
+        created: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0),
+        file_index,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let json = serde_json::to_vec(&metadata)?;
+    let mut header = Vec::with_capacity(FILE_MAGIC.len() + 4 + json.len() + CHECKSUM_BYTES);
+    header.extend_from_slice(FILE_MAGIC);
+    header.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    header.extend_from_slice(&json);
+    header.extend_from_slice(&crc32(&json).to_le_bytes());
+
+    let path = config.file_path(file_index);
+    let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+    file.write_all(&header)?;
+    file.sync_all()?;
+    // the new directory entry is only durable once the directory is synced
+    #[cfg(unix)]
+    File::open(&config.dir)?.sync_all()?;
+    Ok((file, header.len() as u64))
+}
+
+/// Chunk header, samples one after the other as little-endian f64 and the checksum of both.
+fn encode_chunk(chunk: &Array2<f64>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(CHUNK_HEADER_BYTES + chunk.len() * 8 + CHECKSUM_BYTES);
+    bytes.extend_from_slice(CHUNK_MAGIC);
+    bytes.extend_from_slice(&(chunk.nrows() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(chunk.ncols() as u32).to_le_bytes());
+    for column in chunk.columns() {
+        for value in column {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    let checksum = crc32(&bytes[CHUNK_MAGIC.len()..]);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    bytes
+}
+
+/// Drains a streaming [BoardShim] on a background thread and writes the data with a [ChunkWriter].
+///
+/// The recorder borrows the board and cannot outlive it. Releasing the session while recording ends the
+/// recording, [Recorder::stop] then returns the error of the board.
+pub struct Recorder<'a> {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<Result<Vec<PathBuf>>>>,
+    board: PhantomData<&'a BoardShim>,
+}
+
+impl<'a> Recorder<'a> {
+    /// Start recording `preset` of a streaming `board`.
+    pub fn start(board: &'a BoardShim, preset: BrainFlowPresets, config: RecorderConfig) -> Result<Self> {
+        let description = board.get_description(preset)?;
+        let poll_interval = config.poll_interval;
+        let mut writer = ChunkWriter::create(config, board.get_board_id(), preset, description)?;
+        let board = board.handle();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = thread::Builder::new()
+            .name(format!("brainflow-{}-recorder", board.get_board_id()))
+            .spawn(move || {
+                let mut drain = || -> Result<()> {
+                    let count = board.get_board_data_count(preset)?;
+                    if count > 0 {
+                        writer.write(&board.get_board_data(Some(count), preset)?)?;
+                    }
+                    Ok(())
+                };
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(poll_interval) {
+                    drain()?;
+                }
+                drain()?;
+                writer.finish()
+            })?;
+        Ok(Self {
+            stop: Some(stop),
+            handle: Some(handle),
+            board: PhantomData,
+        })
+    }
+
+    /// Write the data still in the board buffer, finish the current file and return all files,
+    /// or the error which stopped the recording.
+    pub fn stop(mut self) -> Result<Vec<PathBuf>> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Result<Vec<PathBuf>> {
+        drop(self.stop.take());
+        match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(res)) => res,
+            Some(Err(_)) => Err(Error::InvalidRecording("recorder thread panicked".to_string())),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl Drop for Recorder<'_> {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// What [read_file] found in a chunked recording file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Complete chunks with a valid checksum.
+    pub chunks: usize,
+    /// Samples in those chunks.
+    pub samples: usize,
+    /// Bytes skipped between valid chunks because they were damaged.
+    pub damaged_bytes: u64,
+    /// Bytes of an incomplete chunk at the end of the file.
+    pub truncated_bytes: u64,
+}
+
+impl RecoveryReport {
+    /// Whether the file was complete and undamaged.
+    pub fn is_intact(&self) -> bool {
+        self.damaged_bytes == 0 && self.truncated_bytes == 0
+    }
+}
+
+/// Data of a chunked recording file read with [read_file].
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct ChunkedRecording {
+    metadata: RecordingMetadata,
+    /// All complete chunks concatenated, one sample per column.
+    data: Array2<f64>,
+    report: RecoveryReport,
+}
+
+/// Read every complete chunk of a chunked recording file, skipping damaged ones.
+///
+/// Fails only if the metadata header is damaged.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<ChunkedRecording> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)?;
+    let invalid = |reason: &str| Error::InvalidRecording(format!("{}: {}", path.display(), reason));
+    let (metadata, header_len) = parse_header(&bytes).ok_or_else(|| invalid("no valid metadata header"))?;
+
+    let num_rows = *metadata.description.num_rows();
+    let (index, report) = index_chunks(&bytes, header_len, num_rows);
+    let chunks = index.iter().map(|chunk| chunk.decode(&bytes, num_rows)).collect::<Vec<_>>();
+    let views = chunks.iter().map(|c| c.view()).collect::<Vec<_>>();
+    let data = match views.is_empty() {
+        true => Array2::zeros((num_rows, 0)),
+        false => concatenate(Axis(1), &views)?,
+    };
+    Ok(ChunkedRecording { metadata, data, report })
+}
+
+/// Salvage all complete chunks of a damaged file into a new file at `output`.
+pub fn recover<P: AsRef<Path>, Q: AsRef<Path>>(path: P, output: Q) -> Result<RecoveryReport> {
+    let recording = read_file(path.as_ref())?;
+    let bytes = fs::read(path.as_ref())?;
+    let header_len = parse_header(&bytes).map(|(_, len)| len).unwrap_or(0);
+    let mut file = OpenOptions::new().write(true).create_new(true).open(output.as_ref())?;
+    file.write_all(&bytes[..header_len])?;
+    // the salvaged data is written as one chunk, a clean file needs no chunk boundaries
+    if recording.data.ncols() > 0 {
+        file.write_all(&encode_chunk(&recording.data))?;
+    }
+    file.sync_all()?;
+    Ok(recording.report)
+}
+
+/// Export the complete chunks of chunked recording files, in the given order, to one file in the
+/// csv layout of [data_filter::write_file].
+pub fn export_csv<P: AsRef<Path>, Q: AsRef<Path>>(paths: &[P], output: Q) -> Result<RecoveryReport> {
+    let output = output.as_ref();
+    let output = output
+        .to_str()
+        .ok_or_else(|| Error::InvalidRecording(format!("{} is not valid unicode", output.display())))?;
+    let mut total = RecoveryReport::default();
+    let mut mode = "w";
+    for path in paths {
+        let recording = read_file(path)?;
+        if recording.data.ncols() > 0 {
+            data_filter::write_file(&recording.data, output, mode)?;
+            mode = "a";
+        }
+        total.chunks += recording.report.chunks;
+        total.samples += recording.report.samples;
+        total.damaged_bytes += recording.report.damaged_bytes;
+        total.truncated_bytes += recording.report.truncated_bytes;
+    }
+    if mode == "w" {
+        fs::write(output, "")?;
+    }
+    Ok(total)
+}
+
+/// Metadata and length of the header at the start of `bytes`.
+pub(crate) fn parse_header(bytes: &[u8]) -> Option<(RecordingMetadata, usize)> {
+    if bytes.len() < FILE_MAGIC.len() + 4 || &bytes[..FILE_MAGIC.len()] != FILE_MAGIC {
+        return None;
+    }
+    let json_len = u32::from_le_bytes(bytes[8..12].try_into().ok()?) as usize;
+    let json = bytes.get(12..12 + json_len)?;
+    let checksum = bytes.get(12 + json_len..12 + json_len + CHECKSUM_BYTES)?;
+    if crc32(json) != u32::from_le_bytes(checksum.try_into().ok()?) {
+        return None;
+    }
+    let metadata = serde_json::from_slice(json).ok()?;
+    Some((metadata, 12 + json_len + CHECKSUM_BYTES))
+}
+
+/// Position of a valid chunk in a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChunkIndex {
+    /// Offset of the first value.
+    pub(crate) offset: usize,
+    pub(crate) num_samples: usize,
+}
+
+impl ChunkIndex {
+    /// Value of `row` of sample `sample` of the chunk.
+    pub(crate) fn value(&self, bytes: &[u8], num_rows: usize, sample: usize, row: usize) -> f64 {
+        let start = self.offset + (sample * num_rows + row) * 8;
+        f64::from_le_bytes(bytes[start..start + 8].try_into().unwrap())
+    }
+
+    fn decode(&self, bytes: &[u8], num_rows: usize) -> Array2<f64> {
+        Array2::from_shape_fn((num_rows, self.num_samples), |(row, sample)| {
+            self.value(bytes, num_rows, sample, row)
+        })
+    }
+}
+
+enum Chunk {
+    /// Chunk and its length in bytes.
+    Valid(ChunkIndex, usize),
+    Incomplete,
+    Damaged,
+}
+
+/// Valid chunks of a file with a header of `header_len` bytes, and what was skipped.
+pub(crate) fn index_chunks(bytes: &[u8], header_len: usize, num_rows: usize) -> (Vec<ChunkIndex>, RecoveryReport) {
+    let mut report = RecoveryReport::default();
+    let mut chunks = Vec::new();
+    let mut offset = header_len;
+    while offset < bytes.len() {
+        match check_chunk(&bytes[offset..], num_rows) {
+            Chunk::Valid(chunk, len) => {
+                report.chunks += 1;
+                report.samples += chunk.num_samples;
+                chunks.push(ChunkIndex {
+                    offset: offset + chunk.offset,
+                    num_samples: chunk.num_samples,
+                });
+                offset += len;
+            }
+            Chunk::Incomplete => match find_chunk(bytes, offset + 1) {
+                // a damaged length can make a chunk look incomplete although more chunks follow
+                Some(next) => {
+                    report.damaged_bytes += (next - offset) as u64;
+                    offset = next;
+                }
+                None => {
+                    report.truncated_bytes = (bytes.len() - offset) as u64;
+                    break;
+                }
+            },
+            Chunk::Damaged => {
+                let next = find_chunk(bytes, offset + 1).unwrap_or(bytes.len());
+                report.damaged_bytes += (next - offset) as u64;
+                offset = next;
+            }
+        }
+    }
+    (chunks, report)
+}
+
+/// Check the chunk at the start of `bytes`, the returned index is relative to it.
+fn check_chunk(bytes: &[u8], num_rows: usize) -> Chunk {
+    if bytes.len() < CHUNK_HEADER_BYTES {
+        return match CHUNK_MAGIC.starts_with(bytes) || bytes.starts_with(CHUNK_MAGIC) {
+            true => Chunk::Incomplete,
+            false => Chunk::Damaged,
+        };
+    }
+    let rows = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    let samples = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+    if &bytes[..4] != CHUNK_MAGIC || rows != num_rows || samples == 0 {
+        return Chunk::Damaged;
+    }
+    let end = CHUNK_HEADER_BYTES + rows * samples * 8;
+    if bytes.len() < end + CHECKSUM_BYTES {
+        return Chunk::Incomplete;
+    }
+    let checksum = u32::from_le_bytes(bytes[end..end + CHECKSUM_BYTES].try_into().unwrap());
+    if crc32(&bytes[CHUNK_MAGIC.len()..end]) != checksum {
+        return Chunk::Damaged;
+    }
+    let chunk = ChunkIndex {
+        offset: CHUNK_HEADER_BYTES,
+        num_samples: samples,
+    };
+    Chunk::Valid(chunk, end + CHECKSUM_BYTES)
+}
+
+/// Offset of the next chunk magic at or after `from`.
+fn find_chunk(bytes: &[u8], from: usize) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(CHUNK_MAGIC.len())
+        .position(|w| w == CHUNK_MAGIC)
+        .map(|p| from + p)
+}
+
+/// CRC-32 (IEEE) lookup table.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes
+        .iter()
+        .fold(!0u32, |crc, b| CRC32_TABLE[((crc ^ *b as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{s, Array2};
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        thread,
+        time::Duration,
+    };
+
+    use super::{crc32, export_csv, read_file, recover, ChunkWriter, Recorder, RecorderConfig, SyncPolicy};
+    use crate::{
+        board_description::BoardDescription, board_shim::BoardShim,
+        brainflow_input_params::BrainFlowInputParamsBuilder, data_filter, BoardIds, BrainFlowPresets,
+    };
+
+    fn dir(name: &str) -> std::path::PathBuf {
+        let dir = env::temp_dir().join("brainflow_tests/rust").join(name);
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+        assert_eq!(0, crc32(b""));
+    }
+
+    #[test]
+    fn test_rotation_and_recovery() {
+        let descr: BoardDescription =
+            serde_json::from_str(r#"{"name": "Test", "sampling_rate": 100, "num_rows": 3, "eeg_channels": [0]}"#)
+                .unwrap();
+        let data = Array2::from_shape_fn((3, 100), |(row, col)| (row * 1000 + col) as f64);
+        let dir = dir("recorder_rotation");
+        // a chunk of 10 samples takes 256 bytes and the header about 200, two chunks fit into a file
+        let config = RecorderConfig::new(&dir, "rec").sync(SyncPolicy::EveryChunk).max_file_size(800);
+        let mut writer =
+            ChunkWriter::create(config, BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset, descr).unwrap();
+        for i in 0..10 {
+            writer.write(&data.slice(s![.., i * 10..(i + 1) * 10]).to_owned()).unwrap();
+        }
+        assert!(writer.write(&Array2::zeros((2, 5))).is_err());
+        let files = writer.finish().unwrap();
+        assert_eq!(5, files.len());
+
+        let first = read_file(&files[0]).unwrap();
+        assert!(first.report().is_intact());
+        assert_eq!(0, *first.metadata().file_index());
+        assert_eq!(data.slice(s![.., ..20]), first.data());
+        assert_eq!(4, *read_file(&files[4]).unwrap().metadata().file_index());
+
+        // damage the first chunk and cut the second one short
+        let mut bytes = fs::read(&files[0]).unwrap();
+        let len = bytes.len();
+        let first_chunk = len - 2 * 256;
+        bytes[first_chunk + 100] ^= 0xFF;
+        bytes.truncate(len - 10);
+        bytes.extend_from_slice(b"CH");
+        fs::write(&files[0], &bytes).unwrap();
+        let damaged = read_file(&files[0]).unwrap();
+        assert_eq!(0, damaged.report().chunks);
+        assert_eq!(256, damaged.report().damaged_bytes);
+        assert_eq!(256 - 10 + 2, damaged.report().truncated_bytes);
+
+        let salvaged = dir.join("salvaged.bfrec");
+        assert_eq!(*damaged.report(), recover(&files[0], &salvaged).unwrap());
+        assert_eq!(0, read_file(&salvaged).unwrap().data().ncols());
+        assert!(recover(&files[0], &salvaged).is_err());
+
+        let csv = dir.join("export.csv");
+        let report = export_csv(&files, &csv).unwrap();
+        assert_eq!((8, 80), (report.chunks, report.samples));
+        let exported = data_filter::read_file(csv.to_str().unwrap()).unwrap();
+        assert_eq!(data.slice(s![.., 20..]), exported);
+    }
+
+    #[test]
+    fn test_records_board() {
+        let params = BrainFlowInputParamsBuilder::new().other_info("records_board").build();
+        let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+        board.prepare_session().unwrap();
+        board.start_stream(45000, "").unwrap();
+        let dir = dir("recorder_board");
+        let config = RecorderConfig::new(&dir, "synthetic").poll_interval(Duration::from_millis(20));
+        let recorder = Recorder::start(&board, BrainFlowPresets::DefaultPreset, config).unwrap();
+        thread::sleep(Duration::from_millis(300));
+        let files = recorder.stop().unwrap();
+        board.stop_stream().unwrap();
+        board.release_session().unwrap();
+
+        assert_eq!(vec![dir.join("synthetic_0000.bfrec")], files);
+        let recording = read_file(&files[0]).unwrap();
+        assert!(recording.report().is_intact());
+        assert!(recording.report().chunks > 1);
+        assert_eq!(BoardIds::SyntheticBoard, *recording.metadata().board_id());
+        assert_eq!(32, recording.data().nrows());
+        assert!(recording.data().ncols() > 0);
+        // a file left open by a crash is read up to its last complete chunk
+        let file = OpenOptions::new().append(true).open(&files[0]).unwrap();
+        file.set_len(fs::metadata(&files[0]).unwrap().len() - 1).unwrap();
+        assert!(!read_file(&files[0]).unwrap().report().is_intact());
+    }
+}
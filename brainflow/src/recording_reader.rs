@@ -0,0 +1,485 @@
+use getset::Getters;
+use memmap2::Mmap;
+use ndarray::Array2;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{
+    board_description::BoardDescription,
+    edf::EdfLayout,
+    error::Error,
+    recorder::{self, ChunkIndex, RecoveryReport},
+    Result,
+};
+
+/// Lines of a csv file between two entries of its index.
+const CSV_INDEX_STRIDE: usize = 64;
+/// Bytes at the start of a file checked to tell the format.
+const TEXT_CHECK_BYTES: usize = 4096;
+
+enum Source {
+    /// Memory mapped file of a [crate::recorder::ChunkWriter], with the valid chunks and the first sample of each.
+    Chunked {
+        map: Mmap,
+        chunks: Vec<ChunkIndex>,
+        first_samples: Vec<usize>,
+    },
+    /// Memory mapped EDF or BDF file with its header.
+    Edf { map: Mmap, layout: EdfLayout },
+    /// File of [crate::data_filter::write_file] with the offset of every [CSV_INDEX_STRIDE]th sample.
+    Csv {
+        reader: Mutex<BufReader<File>>,
+        offsets: Vec<u64>,
+    },
+}
+
+/// Random access to recordings too long to load at once.
+///
+/// Opening a recording indexes it once, queries then read only the requested samples. Files of a
+/// [crate::recorder::ChunkWriter] and EDF or BDF files are memory mapped, csv files of
+/// [crate::data_filter::write_file] are read through an index of line offsets.
+#[derive(Getters)]
+pub struct RecordingReader {
+    #[getset(get = "pub")]
+    path: PathBuf,
+    #[getset(get = "pub")]
+    num_rows: usize,
+    /// Samples in the recording, complete chunks only for damaged chunked files and complete data
+    /// records only for EDF and BDF files.
+    #[getset(get = "pub")]
+    num_samples: usize,
+    /// Description stored in a chunked file or given when opening.
+    #[getset(get = "pub")]
+    description: Option<BoardDescription>,
+    /// What was skipped while indexing a chunked file, `None` for other formats.
+    #[getset(get = "pub")]
+    report: Option<RecoveryReport>,
+    source: Source,
+}
+
+impl RecordingReader {
+    /// Open and index a chunked recording file, an EDF or BDF file or a BrainFlow csv file.
+    ///
+    /// A chunked, EDF or BDF file must not be truncated while it is open, appending to it is fine but the
+    /// appended chunks or data records are not seen. EDF and BDF files have one row per signal except
+    /// annotations, in the highest sampling rate of all signals like [crate::edf::EdfRecording::data].
+    ///
+    /// Other formats, e.g. XDF or BrainVision files, fail with [Error::InvalidRecording], read them with
+    /// their modules.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = File::open(&path)?;
+        let mut start = Vec::with_capacity(TEXT_CHECK_BYTES);
+        (&mut file).take(TEXT_CHECK_BYTES as u64).read_to_end(&mut start)?;
+        file.seek(SeekFrom::Start(0))?;
+        if start.starts_with(recorder::FILE_MAGIC) {
+            return Self::open_chunked(path, file);
+        }
+        let unsupported = |format: &str| {
+            Err(Error::InvalidRecording(format!("{}: unsupported format {}", path.display(), format)))
+        };
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        if (start.len() >= 256 && start.starts_with(b"0       ")) || start.starts_with(b"\xffBIOSEMI") {
+            Self::open_edf(path, file)
+        } else if start.starts_with(b"XDF:") {
+            unsupported("XDF")
+        } else if start.starts_with(b"Brain Vision") || start.starts_with(b"BrainVision") || extension == "vhdr" {
+            unsupported("BrainVision")
+        } else if !is_text(&start) {
+            unsupported("binary")
+        } else {
+            Self::open_csv(path, file)
+        }
+    }
+
+    /// Open a recording of a board with the given description, which overrides a stored one.
+    ///
+    /// Csv, EDF and BDF files have no description, it is needed for [RecordingReader::time_range].
+    pub fn open_with_description<P: AsRef<Path>>(path: P, description: BoardDescription) -> Result<Self> {
+        let mut reader = Self::open(path)?;
+        if *description.num_rows() != reader.num_rows && reader.num_samples > 0 {
+            return Err(Error::InvalidRecording(format!(
+                "{} has {} rows, the description {}",
+                reader.path.display(),
+                reader.num_rows,
+                description.num_rows()
+            )));
+        }
+        reader.description = Some(description);
+        Ok(reader)
+    }
+
+    fn open_chunked(path: PathBuf, file: File) -> Result<Self> {
+        // the recorder only appends, the mapped part of the file does not change
+        let map = unsafe { Mmap::map(&file)? };
+        let (metadata, header_len) = recorder::parse_header(&map)
+            .ok_or_else(|| Error::InvalidRecording(format!("{}: no valid metadata header", path.display())))?;
+        let description = metadata.description().clone();
+        let num_rows = *description.num_rows();
+        let (chunks, report) = recorder::index_chunks(&map, header_len, num_rows);
+        let first_samples = chunks
+            .iter()
+            .scan(0, |first, chunk| {
+                let start = *first;
+                *first += chunk.num_samples;
+                Some(start)
+            })
+            .collect();
+        Ok(Self {
+            path,
+            num_rows,
+            num_samples: report.samples,
+            description: Some(description),
+            report: Some(report),
+            source: Source::Chunked {
+                map,
+                chunks,
+                first_samples,
+            },
+        })
+    }
+
+    fn open_edf(path: PathBuf, file: File) -> Result<Self> {
+        // like chunked files, EDF files are only appended to while they are written
+        let map = unsafe { Mmap::map(&file)? };
+        let layout = EdfLayout::parse(&map)
+            .map_err(|e| Error::InvalidRecording(format!("{}: {}", path.display(), e)))?;
+        Ok(Self {
+            num_rows: layout.channels.len(),
+            num_samples: layout.num_samples(),
+            path,
+            description: None,
+            report: None,
+            source: Source::Edf { map, layout },
+        })
+    }
+
+    fn open_csv(path: PathBuf, file: File) -> Result<Self> {
+        let mut reader = BufReader::new(file);
+        let mut offsets = Vec::new();
+        let mut lines = CsvLines::new(&path);
+        let mut num_samples = 0;
+        let mut offset = 0;
+        let mut line = String::new();
+        for line_number in 1.. {
+            line.clear();
+            let len = reader.read_line(&mut line)?;
+            if len == 0 {
+                break;
+            }
+            let start = offset;
+            offset += len as u64;
+            if lines.split(line_number, &line)?.is_none() {
+                continue;
+            }
+            if num_samples % CSV_INDEX_STRIDE == 0 {
+                offsets.push(start);
+            }
+            num_samples += 1;
+        }
+        Ok(Self {
+            num_rows: lines.num_rows(),
+            path,
+            num_samples,
+            description: None,
+            report: None,
+            source: Source::Csv {
+                reader: Mutex::new(reader),
+                offsets,
+            },
+        })
+    }
+
+    /// Duration in seconds at the sampling rate of the description, or of the header of an EDF or BDF
+    /// file without one.
+    pub fn duration(&self) -> Option<f64> {
+        let sampling_rate = match (&self.description, &self.source) {
+            (Some(description), _) => *description.sampling_rate() as f64,
+            (None, Source::Edf { layout, .. }) => layout.sampling_rate(),
+            (None, _) => return None,
+        };
+        (sampling_rate > 0.0).then(|| self.num_samples as f64 / sampling_rate)
+    }
+
+    /// Rows `rows` of the samples in `samples`, one sample per column.
+    ///
+    /// The range is clipped to the samples of the recording.
+    pub fn sample_range(&self, samples: Range<usize>, rows: &[usize]) -> Result<Array2<f64>> {
+        if let Some(row) = rows.iter().find(|r| **r >= self.num_rows) {
+            return Err(Error::InvalidRecording(format!(
+                "row {} is out of the {} rows of {}",
+                row,
+                self.num_rows,
+                self.path.display()
+            )));
+        }
+        let end = samples.end.min(self.num_samples);
+        let start = samples.start.min(end);
+        let mut data = Array2::zeros((rows.len(), end - start));
+        match &self.source {
+            Source::Chunked {
+                map,
+                chunks,
+                first_samples,
+            } => {
+                let mut chunk = first_samples.partition_point(|first| *first <= start).saturating_sub(1);
+                for (col, sample) in (start..end).enumerate() {
+                    while sample >= first_samples[chunk] + chunks[chunk].num_samples {
+                        chunk += 1;
+                    }
+                    for (i, row) in rows.iter().enumerate() {
+                        data[[i, col]] = chunks[chunk].value(map, self.num_rows, sample - first_samples[chunk], *row);
+                    }
+                }
+            }
+            Source::Edf { map, layout } => {
+                for (col, sample) in (start..end).enumerate() {
+                    for (i, row) in rows.iter().enumerate() {
+                        data[[i, col]] = layout.value(map, *row, sample);
+                    }
+                }
+            }
+            Source::Csv { reader, offsets } => {
+                if start == end {
+                    return Ok(data);
+                }
+                let mut reader = reader.lock().unwrap_or_else(|e| e.into_inner());
+                reader.seek(SeekFrom::Start(offsets[start / CSV_INDEX_STRIDE]))?;
+                let mut sample = start - start % CSV_INDEX_STRIDE;
+                let mut line = String::new();
+                while sample < end {
+                    line.clear();
+                    if reader.read_line(&mut line)? == 0 {
+                        return Err(Error::InvalidRecording(format!("{} was truncated", self.path.display())));
+                    }
+                    let values = match csv_values(&line) {
+                        Some(values) => values,
+                        None => continue,
+                    };
+                    if sample >= start {
+                        for (i, row) in rows.iter().enumerate() {
+                            data[[i, sample - start]] = values[*row].parse().map_err(|e| {
+                                Error::InvalidRecording(format!("{} sample {}: {}", self.path.display(), sample, e))
+                            })?;
+                        }
+                    }
+                    sample += 1;
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// Rows `rows` of the samples with a timestamp from `start` up to `end`, in seconds since the epoch.
+    ///
+    /// Needs the timestamp channel of the description, the timestamps must not decrease.
+    pub fn time_range(&self, start: f64, end: f64, rows: &[usize]) -> Result<Array2<f64>> {
+        let first = self.sample_at(start)?;
+        let last = self.sample_at(end)?;
+        self.sample_range(first..last.max(first), rows)
+    }
+
+    /// First sample with a timestamp of at least `timestamp`, found by binary search.
+    pub fn sample_at(&self, timestamp: f64) -> Result<usize> {
+        let channel = self
+            .description
+            .as_ref()
+            .and_then(|d| *d.timestamp_channel())
+            .ok_or_else(|| Error::InvalidRecording(format!("{} has no timestamp channel", self.path.display())))?;
+        let (mut low, mut high) = (0, self.num_samples);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.sample_range(mid..mid + 1, &[channel])?[[0, 0]] < timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+}
+
+/// Values of a line of a csv file of [crate::data_filter::write_file], `None` for a blank line.
+fn csv_values(line: &str) -> Option<Vec<&str>> {
+    (!line.trim().is_empty()).then(|| line.split('\t').map(str::trim).collect())
+}
+
+/// Splits the lines of a csv file of [crate::data_filter::write_file] and checks that all have as many
+/// values as the first.
+pub(crate) struct CsvLines<'a> {
+    path: &'a Path,
+    num_rows: Option<usize>,
+}
+
+impl<'a> CsvLines<'a> {
+    pub(crate) fn new(path: &'a Path) -> Self {
+        Self { path, num_rows: None }
+    }
+
+    /// Values of line `line_number`, counted from 1, `None` for a blank line.
+    pub(crate) fn split<'l>(&mut self, line_number: usize, line: &'l str) -> Result<Option<Vec<&'l str>>> {
+        let values = match csv_values(line) {
+            Some(values) => values,
+            None => return Ok(None),
+        };
+        match self.num_rows {
+            None => self.num_rows = Some(values.len()),
+            Some(n) if n != values.len() => {
+                return Err(Error::InvalidRecording(format!(
+                    "{} line {} has {} values instead of {}",
+                    self.path.display(),
+                    line_number,
+                    values.len(),
+                    n
+                )))
+            }
+            Some(_) => {}
+        }
+        Ok(Some(values))
+    }
+
+    /// Values per line, 0 if there was no line with values.
+    pub(crate) fn num_rows(&self) -> usize {
+        self.num_rows.unwrap_or(0)
+    }
+}
+
+/// Whether `bytes` look like the start of a text file, a multi-byte character may be cut off at the end.
+fn is_text(bytes: &[u8]) -> bool {
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    valid && !bytes.iter().any(|b| b.is_ascii_control() && !b"\t\r\n".contains(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{s, Array2};
+    use std::{env, fs};
+
+    use super::RecordingReader;
+    use crate::{
+        board_description::BoardDescription,
+        brainvision::{self, BinaryFormat},
+        data_filter,
+        edf::{self, EdfFormat},
+        error::Error,
+        recorder::{ChunkWriter, RecorderConfig},
+        BoardIds, BrainFlowPresets,
+    };
+
+    /// Two seconds of a 100 Hz board with rows value, timestamp and marker.
+    fn recording() -> (BoardDescription, Array2<f64>) {
+        let descr = serde_json::from_str(
+            r#"{"name": "Reader", "sampling_rate": 100, "num_rows": 3, "eeg_channels": [0],
+                "timestamp_channel": 1, "marker_channel": 2}"#,
+        )
+        .unwrap();
+        let data = Array2::from_shape_fn((3, 200), |(row, i)| match row {
+            0 => i as f64 * 0.5,
+            1 => 1000.0 + i as f64 / 100.0,
+            _ => (i % 50 == 0) as u8 as f64,
+        });
+        (descr, data)
+    }
+
+    fn check_queries(reader: &RecordingReader, data: &Array2<f64>) {
+        assert_eq!(3, *reader.num_rows());
+        assert_eq!(200, *reader.num_samples());
+        assert_eq!(Some(2.0), reader.duration());
+        for range in [0..1, 60..140, 63..65, 150..400, 300..400].iter() {
+            let read = reader.sample_range(range.clone(), &[2, 0]).unwrap();
+            let end = range.end.min(200);
+            let start = range.start.min(end);
+            let expected = data.select(ndarray::Axis(0), &[2, 0]).slice(s![.., start..end]).to_owned();
+            assert_eq!(expected.shape(), read.shape());
+            assert!(expected.iter().zip(read.iter()).all(|(a, b)| (a - b).abs() < 1e-6));
+        }
+        assert_eq!(50, reader.sample_at(1000.495).unwrap());
+        let window = reader.time_range(1000.5, 1001.0, &[0]).unwrap();
+        assert_eq!(50, window.ncols());
+        assert!((window[[0, 0]] - 25.0).abs() < 1e-6);
+        assert!(reader.sample_range(0..10, &[3]).is_err());
+    }
+
+    #[test]
+    fn test_reads_chunked_file() {
+        let (descr, data) = recording();
+        let dir = env::temp_dir().join("brainflow_tests/rust/recording_reader");
+        let _ = fs::remove_dir_all(&dir);
+        let config = RecorderConfig::new(&dir, "reader");
+        let mut writer =
+            ChunkWriter::create(config, BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset, descr).unwrap();
+        for i in 0..7 {
+            writer.write(&data.slice(s![.., i * 30..((i + 1) * 30).min(200)]).to_owned()).unwrap();
+        }
+        let files = writer.finish().unwrap();
+
+        let reader = RecordingReader::open(&files[0]).unwrap();
+        assert!(reader.report().unwrap().is_intact());
+        check_queries(&reader, &data);
+    }
+
+    #[test]
+    fn test_reads_csv_file() {
+        let (descr, data) = recording();
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("recording_reader.csv");
+        data_filter::write_file(&data, path.to_str().unwrap(), "w").unwrap();
+
+        let reader = RecordingReader::open(&path).unwrap();
+        assert!(reader.sample_at(1000.0).is_err());
+        let reader = RecordingReader::open_with_description(&path, descr).unwrap();
+        assert!(reader.report().is_none());
+        check_queries(&reader, &data);
+    }
+
+    #[test]
+    fn test_reads_edf_files() {
+        let (descr, data) = recording();
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        for format in [EdfFormat::Edf, EdfFormat::Bdf].iter() {
+            let path = dir.join(format!("recording_reader_{:?}.edf", format));
+            edf::write_file(&path, *format, &data, &descr).unwrap();
+            let expected = edf::read_file(&path).unwrap().data();
+
+            let reader = RecordingReader::open(&path).unwrap();
+            assert_eq!(expected.nrows(), *reader.num_rows());
+            assert_eq!(expected.ncols(), *reader.num_samples());
+            assert_eq!(Some(2.0), reader.duration());
+            assert_eq!(expected.slice(s![.., 60..140]), reader.sample_range(60..140, &[0]).unwrap());
+            assert_eq!(expected.slice(s![.., 150..]), reader.sample_range(150..400, &[0]).unwrap());
+            assert!(reader.sample_at(1000.0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_other_formats() {
+        let (descr, data) = recording();
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let vhdr_path = dir.join("recording_reader.vhdr");
+        brainvision::write_files(&vhdr_path, BinaryFormat::IeeeFloat32, &data, &descr).unwrap();
+
+        for (path, format) in [
+            (vhdr_path.clone(), "BrainVision"),
+            (vhdr_path.with_extension("eeg"), "binary"),
+        ]
+        .iter()
+        {
+            match RecordingReader::open(path) {
+                Err(Error::InvalidRecording(reason)) => assert!(reason.ends_with(format), "{}", reason),
+                Ok(_) => panic!("{} was read", path.display()),
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+}
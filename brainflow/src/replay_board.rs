@@ -0,0 +1,437 @@
+use ndarray::Array2;
+use std::{
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    board::Board, board_description::BoardDescription, error::Error, recording_reader::CsvLines,
+    stream_buffer::StreamBuffer, BoardIds, BrainFlowPresets, Result,
+};
+
+/// How often the streaming thread of a [ReplayBoard] adds samples.
+const PUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Read a file written by [crate::data_filter::write_file] without the native library.
+///
+/// The file has one tab separated sample per line, the result has one sample per column.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Array2<f64>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let mut lines = CsvLines::new(path);
+    let mut values = Vec::new();
+    let mut num_samples = 0;
+    for (i, line) in content.lines().enumerate() {
+        let sample = match lines.split(i + 1, line)? {
+            Some(sample) => sample,
+            None => continue,
+        };
+        for value in sample {
+            values.push(
+                value
+                    .parse::<f64>()
+                    .map_err(|e| Error::InvalidRecording(format!("{} line {}: {}", path.display(), i + 1, e)))?,
+            );
+        }
+        num_samples += 1;
+    }
+    Ok(Array2::from_shape_vec((num_samples, lines.num_rows()), values)?.reversed_axes().as_standard_layout().to_owned())
+}
+
+/// Builder for [ReplayBoard].
+pub struct ReplayBoardBuilder {
+    board_id: BoardIds,
+    description: BoardDescription,
+    data: Array2<f64>,
+    speed: f64,
+    looping: bool,
+    new_timestamps: bool,
+}
+
+impl ReplayBoardBuilder {
+    /// Replay `data` recorded from a board with the layout of `description`, reported as `board_id`.
+    pub fn from_data(board_id: BoardIds, description: BoardDescription, data: Array2<f64>) -> Result<Self> {
+        if data.nrows() != *description.num_rows() {
+            return Err(Error::InvalidRecording(format!(
+                "recording has {} rows, {} has {}",
+                data.nrows(),
+                board_id,
+                description.num_rows()
+            )));
+        }
+        Ok(Self {
+            board_id,
+            description,
+            data,
+            speed: 1.0,
+            looping: false,
+            new_timestamps: true,
+        })
+    }
+
+    /// Replay a file written by [crate::data_filter::write_file].
+    pub fn open<P: AsRef<Path>>(path: P, board_id: BoardIds, description: BoardDescription) -> Result<Self> {
+        Self::from_data(board_id, description, read_file(path)?)
+    }
+
+    /// Replay a file recorded from a native board, only the description is read from the native library.
+    pub fn open_for_board<P: AsRef<Path>>(path: P, board_id: BoardIds) -> Result<Self> {
+        let description = BoardDescription::load(board_id, BrainFlowPresets::DefaultPreset)?;
+        Self::open(path, board_id, description)
+    }
+
+    /// Replay speed, 2.0 replays twice as fast as recorded.
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Start again from the beginning at the end of the recording.
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Replace recorded timestamps by the current time, like native boards, enabled by default.
+    pub fn new_timestamps(mut self, new_timestamps: bool) -> Self {
+        self.new_timestamps = new_timestamps;
+        self
+    }
+
+    /// Build the board.
+    pub fn build(self) -> Result<ReplayBoard> {
+        if self.speed.is_nan() || self.speed <= 0.0 {
+            return Err(Error::InvalidRecording(format!("speed {} is not positive", self.speed)));
+        }
+        let sampling_rate = *self.description.sampling_rate() as f64;
+        // recording time of every sample, from the timestamps or the sampling rate
+        let times = match *self.description.timestamp_channel() {
+            Some(channel) => self.data.row(channel).to_vec(),
+            None => (0..self.data.ncols()).map(|i| i as f64 / sampling_rate).collect(),
+        };
+        let buffer = StreamBuffer::new(self.board_id, vec![(BrainFlowPresets::DefaultPreset, self.description.clone())]);
+        Ok(ReplayBoard {
+            shared: Arc::new(Shared {
+                buffer: Mutex::new(buffer),
+                player: Mutex::new(Player {
+                    timestamp_channel: if self.new_timestamps { *self.description.timestamp_channel() } else { None },
+                    data: self.data,
+                    times,
+                    speed: self.speed,
+                    looping: self.looping,
+                    paused: false,
+                    next: 0,
+                    position: 0.0,
+                }),
+            }),
+            worker: Mutex::new(None),
+        })
+    }
+}
+
+/// Replay position and settings.
+struct Player {
+    data: Array2<f64>,
+    times: Vec<f64>,
+    /// Channel to write new timestamps to.
+    timestamp_channel: Option<usize>,
+    speed: f64,
+    looping: bool,
+    paused: bool,
+    /// Index of the next sample.
+    next: usize,
+    /// Seconds since the start of the recording.
+    position: f64,
+}
+
+impl Player {
+    fn start_time(&self) -> f64 {
+        self.times.first().copied().unwrap_or(0.0)
+    }
+
+    fn duration(&self) -> f64 {
+        self.times.last().copied().unwrap_or(0.0) - self.start_time()
+    }
+
+    fn seek(&mut self, position: f64) {
+        self.position = position.max(0.0).min(self.duration());
+        let time = self.start_time() + self.position;
+        self.next = self.times.partition_point(|t| *t < time);
+    }
+
+    /// Advance by `elapsed` seconds of wall time and return the samples which became due.
+    fn advance(&mut self, elapsed: f64) -> Vec<Vec<f64>> {
+        let mut due = Vec::new();
+        if self.paused || self.times.is_empty() {
+            return due;
+        }
+        self.position += elapsed * self.speed;
+        loop {
+            let time = self.start_time() + self.position;
+            while self.next < self.times.len() && self.times[self.next] <= time {
+                due.push(self.data.column(self.next).to_vec());
+                self.next += 1;
+            }
+            if self.next < self.times.len() {
+                break;
+            }
+            let duration = self.duration();
+            if !self.looping || duration <= 0.0 {
+                self.position = self.position.min(duration);
+                break;
+            }
+            // the first sample of the next loop follows the last one by one sample period
+            let period = duration / (self.times.len() - 1) as f64;
+            self.position -= duration + period;
+            self.next = 0;
+            if self.position < 0.0 {
+                break;
+            }
+        }
+        due
+    }
+}
+
+struct Shared {
+    buffer: Mutex<StreamBuffer>,
+    player: Mutex<Player>,
+}
+
+/// Pure Rust [Board] which replays a recording in real time for [BrainFlowPresets::DefaultPreset],
+/// paced by its timestamp channel.
+///
+/// Position, speed and pause can be changed at any time, also while streaming.
+pub struct ReplayBoard {
+    shared: Arc<Shared>,
+    worker: Mutex<Option<(Arc<AtomicBool>, JoinHandle<()>)>>,
+}
+
+impl ReplayBoard {
+    /// Seconds since the start of the recording of the next sample.
+    pub fn position(&self) -> f64 {
+        self.shared.player.lock().unwrap().position
+    }
+
+    /// Duration of the recording in seconds.
+    pub fn duration(&self) -> f64 {
+        self.shared.player.lock().unwrap().duration()
+    }
+
+    /// Continue from `position` seconds since the start of the recording.
+    pub fn seek(&self, position: f64) {
+        self.shared.player.lock().unwrap().seek(position);
+    }
+
+    /// Change the replay speed, speeds which are not positive are ignored.
+    pub fn set_speed(&self, speed: f64) {
+        if speed > 0.0 {
+            self.shared.player.lock().unwrap().speed = speed;
+        }
+    }
+
+    /// Stop adding samples until [ReplayBoard::resume].
+    pub fn pause(&self) {
+        self.shared.player.lock().unwrap().paused = true;
+    }
+
+    pub fn resume(&self) {
+        self.shared.player.lock().unwrap().paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.shared.player.lock().unwrap().paused
+    }
+
+    /// True if the recording was replayed until its end and does not loop.
+    pub fn is_finished(&self) -> bool {
+        let player = self.shared.player.lock().unwrap();
+        !player.looping && player.next == player.times.len()
+    }
+
+    fn stop_worker(&self) {
+        if let Some((stop, handle)) = self.worker.lock().unwrap().take() {
+            stop.store(true, Ordering::Relaxed);
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Push samples as they become due until `stop` is set.
+fn replay_in_real_time(shared: Arc<Shared>, stop: Arc<AtomicBool>) {
+    let mut last = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        thread::sleep(PUSH_INTERVAL);
+        let elapsed = last.elapsed();
+        last += elapsed;
+        let mut player = shared.player.lock().unwrap();
+        let samples = player.advance(elapsed.as_secs_f64());
+        let timestamp_channel = player.timestamp_channel;
+        drop(player);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        let mut buffer = shared.buffer.lock().unwrap();
+        for mut sample in samples {
+            if let Some(channel) = timestamp_channel {
+                sample[channel] = now;
+            }
+            if buffer.push(BrainFlowPresets::DefaultPreset, sample).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Board for ReplayBoard {
+    fn get_board_id(&self) -> BoardIds {
+        self.shared.buffer.lock().unwrap().board_id()
+    }
+
+    fn prepare_session(&self) -> Result<()> {
+        self.shared.buffer.lock().unwrap().prepare();
+        Ok(())
+    }
+
+    fn is_prepared(&self) -> Result<bool> {
+        Ok(self.shared.buffer.lock().unwrap().is_prepared())
+    }
+
+    fn start_stream(&self, buffer_size: usize, streamer_params: &str) -> Result<()> {
+        self.shared.buffer.lock().unwrap().start(buffer_size, streamer_params)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let shared = self.shared.clone();
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || replay_in_real_time(shared, stop)
+        });
+        *self.worker.lock().unwrap() = Some((stop, handle));
+        Ok(())
+    }
+
+    fn stop_stream(&self) -> Result<()> {
+        self.stop_worker();
+        self.shared.buffer.lock().unwrap().stop()
+    }
+
+    fn release_session(&self) -> Result<()> {
+        self.stop_worker();
+        self.shared.buffer.lock().unwrap().release()
+    }
+
+    fn get_board_data_count(&self, preset: BrainFlowPresets) -> Result<usize> {
+        self.shared.buffer.lock().unwrap().count(preset)
+    }
+
+    fn get_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.shared.buffer.lock().unwrap().take(n_data_points, preset)
+    }
+
+    fn get_current_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.shared.buffer.lock().unwrap().current(num_samples, preset)
+    }
+
+    fn insert_marker(&self, value: f64, preset: BrainFlowPresets) -> Result<()> {
+        self.shared.buffer.lock().unwrap().insert_marker(value, preset)
+    }
+
+    fn get_description(&self, preset: BrainFlowPresets) -> Result<BoardDescription> {
+        self.shared.buffer.lock().unwrap().description(preset)
+    }
+}
+
+impl Drop for ReplayBoard {
+    fn drop(&mut self) {
+        self.stop_worker();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+    use std::{env, fs, thread, time::Duration};
+
+    use super::{read_file, ReplayBoardBuilder};
+    use crate::{board::Board, board_description::BoardDescription, data_filter, BoardIds, BrainFlowPresets};
+
+    /// One second of a 100 Hz board with rows value, timestamp and marker.
+    fn recording() -> (BoardDescription, Array2<f64>) {
+        let descr = serde_json::from_str(
+            r#"{"name": "Replay", "sampling_rate": 100, "num_rows": 3, "eeg_channels": [0],
+                "timestamp_channel": 1, "marker_channel": 2}"#,
+        )
+        .unwrap();
+        let data = Array2::from_shape_fn((3, 100), |(row, i)| match row {
+            0 => i as f64,
+            1 => 1000.0 + i as f64 / 100.0,
+            _ => 0.0,
+        });
+        (descr, data)
+    }
+
+    #[test]
+    fn test_read_written_file() {
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("replay_read_file.csv");
+        let (_, data) = recording();
+        data_filter::write_file(&data, path.to_str().unwrap(), "w").unwrap();
+        let read = read_file(&path).unwrap();
+        assert_eq!(data.shape(), read.shape());
+        assert!(data.iter().zip(read.iter()).all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_replays_at_speed() {
+        let (descr, data) = recording();
+        let board = ReplayBoardBuilder::from_data(BoardIds::SyntheticBoard, descr, data)
+            .unwrap()
+            .speed(4.0)
+            .new_timestamps(false)
+            .build()
+            .unwrap();
+        board.prepare_session().unwrap();
+        board.start_stream(1000, "").unwrap();
+        thread::sleep(Duration::from_millis(100));
+        board.pause();
+        let count = board.get_board_data_count(BrainFlowPresets::DefaultPreset).unwrap();
+        // 100 ms at 4x are 40 samples
+        assert!((20..=60).contains(&count), "{} samples", count);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(count, board.get_board_data_count(BrainFlowPresets::DefaultPreset).unwrap());
+
+        board.seek(0.9);
+        board.resume();
+        thread::sleep(Duration::from_millis(100));
+        assert!(board.is_finished());
+        board.stop_stream().unwrap();
+        let data = board.get_board_data(None, BrainFlowPresets::DefaultPreset).unwrap();
+        assert_relative_eq!(99.0, data[[0, data.ncols() - 1]]);
+        assert_relative_eq!(1000.99, data[[1, data.ncols() - 1]]);
+        board.release_session().unwrap();
+    }
+
+    #[test]
+    fn test_loops() {
+        let (descr, data) = recording();
+        let board = ReplayBoardBuilder::from_data(BoardIds::SyntheticBoard, descr, data)
+            .unwrap()
+            .speed(10.0)
+            .looping(true)
+            .build()
+            .unwrap();
+        board.prepare_session().unwrap();
+        board.start_stream(1000, "").unwrap();
+        thread::sleep(Duration::from_millis(250));
+        board.stop_stream().unwrap();
+        let data = board.get_board_data(None, BrainFlowPresets::DefaultPreset).unwrap();
+        assert!(data.ncols() > 100);
+        assert_relative_eq!(0.0, data[[0, 100]]);
+        assert!(data[[1, 0]] > 1.0e9);
+        assert!(!board.is_finished());
+        board.release_session().unwrap();
+    }
+}
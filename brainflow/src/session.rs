@@ -0,0 +1,254 @@
+use ndarray::Array2;
+use std::time::Duration;
+
+use crate::{
+    board_data::BoardData, board_shim::BoardShim, streamer::StreamerSpec, subscription::BoardSubscription, BoardIds,
+    BrainFlowPresets, Result,
+};
+
+/// A [BoardShim] with a prepared session.
+///
+/// Created by [BoardShim::prepare]. The session is released when this value is dropped,
+/// use [PreparedSession::release] to release it explicitly and get the [BoardShim] back.
+pub struct PreparedSession {
+    board: Option<BoardShim>,
+}
+
+impl PreparedSession {
+    /// Prepare the session of the given board.
+    pub fn new(board: BoardShim) -> Result<Self> {
+        board.prepare_session()?;
+        Ok(Self { board: Some(board) })
+    }
+
+    fn board(&self) -> &BoardShim {
+        self.board.as_ref().expect("board is only taken on release")
+    }
+
+    /// Get's the actual board id, can be different than provided.
+    pub fn get_board_id(&self) -> BoardIds {
+        self.board().get_board_id()
+    }
+
+    /// Start streaming data, this methods stores data in ringbuffer.
+    pub fn start_stream<S: AsRef<str>>(
+        self,
+        buffer_size: usize,
+        streamer_params: S,
+    ) -> Result<StreamingSession> {
+        self.board().start_stream(buffer_size, streamer_params)?;
+        Ok(StreamingSession {
+            session: Some(self),
+        })
+    }
+
+    /// Start streaming data and write it to the given streamer, see [BoardShim::start_stream_with_spec].
+    pub fn start_stream_with_spec(self, buffer_size: usize, streamer: &StreamerSpec) -> Result<StreamingSession> {
+        streamer.validate()?;
+        self.start_stream(buffer_size, streamer.to_string())
+    }
+
+    /// Add streamer from BrainFlow to file or streaming board.
+    pub fn add_streamer<S: AsRef<str>>(&self, streamer_params: S, preset: BrainFlowPresets) -> Result<()> {
+        self.board().add_streamer(streamer_params, preset)
+    }
+
+    /// Delete streamer registered streamer.
+    pub fn delete_streamer<S: AsRef<str>>(&self, streamer_params: S, preset: BrainFlowPresets) -> Result<()> {
+        self.board().delete_streamer(streamer_params, preset)
+    }
+
+    /// Add a typed streamer, see [BoardShim::add_streamer].
+    pub fn add_streamer_with_spec(&self, streamer: &StreamerSpec, preset: BrainFlowPresets) -> Result<()> {
+        self.board().add_streamer_with_spec(streamer, preset)
+    }
+
+    /// Delete a typed streamer, see [BoardShim::delete_streamer].
+    pub fn delete_streamer_with_spec(&self, streamer: &StreamerSpec, preset: BrainFlowPresets) -> Result<()> {
+        self.board().delete_streamer_with_spec(streamer, preset)
+    }
+
+    /// Use this method carefully and only if you understand what you are doing.
+    pub fn config_board<S: AsRef<str>>(&self, config: S) -> Result<String> {
+        self.board().config_board(config)
+    }
+
+    /// Get num of elements in ringbuffer.
+    pub fn get_board_data_count(&self, preset: BrainFlowPresets) -> Result<usize> {
+        self.board().get_board_data_count(preset)
+    }
+
+    /// Get board data and remove data from ringbuffer.
+    pub fn get_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.board().get_board_data(n_data_points, preset)
+    }
+
+    /// Get specified amount of data or less if there is not enough data, doesnt remove data from ringbuffer.
+    pub fn get_current_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.board().get_current_board_data(num_samples, preset)
+    }
+
+    /// Get board data as [BoardData] and remove data from ringbuffer.
+    pub fn get_typed_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<BoardData> {
+        self.board().get_typed_board_data(n_data_points, preset)
+    }
+
+    /// Get current board data as [BoardData], doesnt remove data from ringbuffer.
+    pub fn get_current_typed_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<BoardData> {
+        self.board().get_current_typed_board_data(num_samples, preset)
+    }
+
+    /// Release all resources and return the [BoardShim].
+    pub fn release(mut self) -> Result<BoardShim> {
+        let board = self.board.take().expect("board is only taken on release");
+        board.release_session()?;
+        Ok(board)
+    }
+}
+
+impl Drop for PreparedSession {
+    fn drop(&mut self) {
+        if let Some(board) = self.board.take() {
+            let _ = board.release_session();
+        }
+    }
+}
+
+/// A [PreparedSession] which is streaming data.
+///
+/// Created by [PreparedSession::start_stream]. The stream is stopped and the session released
+/// when this value is dropped, use [StreamingSession::stop_stream] to go back to a [PreparedSession].
+pub struct StreamingSession {
+    session: Option<PreparedSession>,
+}
+
+impl StreamingSession {
+    fn session(&self) -> &PreparedSession {
+        self.session.as_ref().expect("session is only taken on stop")
+    }
+
+    fn board(&self) -> &BoardShim {
+        self.session().board()
+    }
+
+    /// Get's the actual board id, can be different than provided.
+    pub fn get_board_id(&self) -> BoardIds {
+        self.board().get_board_id()
+    }
+
+    /// Add streamer from BrainFlow to file or streaming board.
+    pub fn add_streamer<S: AsRef<str>>(&self, streamer_params: S, preset: BrainFlowPresets) -> Result<()> {
+        self.board().add_streamer(streamer_params, preset)
+    }
+
+    /// Delete streamer registered streamer.
+    pub fn delete_streamer<S: AsRef<str>>(&self, streamer_params: S, preset: BrainFlowPresets) -> Result<()> {
+        self.board().delete_streamer(streamer_params, preset)
+    }
+
+    /// Add a typed streamer, see [BoardShim::add_streamer].
+    pub fn add_streamer_with_spec(&self, streamer: &StreamerSpec, preset: BrainFlowPresets) -> Result<()> {
+        self.board().add_streamer_with_spec(streamer, preset)
+    }
+
+    /// Delete a typed streamer, see [BoardShim::delete_streamer].
+    pub fn delete_streamer_with_spec(&self, streamer: &StreamerSpec, preset: BrainFlowPresets) -> Result<()> {
+        self.board().delete_streamer_with_spec(streamer, preset)
+    }
+
+    /// Use this method carefully and only if you understand what you are doing.
+    pub fn config_board<S: AsRef<str>>(&self, config: S) -> Result<String> {
+        self.board().config_board(config)
+    }
+
+    /// Insert Marker to Data Stream.
+    pub fn insert_marker(&self, value: f64, preset: BrainFlowPresets) -> Result<()> {
+        self.board().insert_marker(value, preset)
+    }
+
+    /// Get num of elements in ringbuffer.
+    pub fn get_board_data_count(&self, preset: BrainFlowPresets) -> Result<usize> {
+        self.board().get_board_data_count(preset)
+    }
+
+    /// Get board data and remove data from ringbuffer.
+    pub fn get_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.board().get_board_data(n_data_points, preset)
+    }
+
+    /// Get specified amount of data or less if there is not enough data, doesnt remove data from ringbuffer.
+    pub fn get_current_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.board().get_current_board_data(num_samples, preset)
+    }
+
+    /// Get board data as [BoardData] and remove data from ringbuffer.
+    pub fn get_typed_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<BoardData> {
+        self.board().get_typed_board_data(n_data_points, preset)
+    }
+
+    /// Get current board data as [BoardData], doesnt remove data from ringbuffer.
+    pub fn get_current_typed_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<BoardData> {
+        self.board().get_current_typed_board_data(num_samples, preset)
+    }
+
+    /// Poll new data in a background thread every `poll_interval`, see [BoardSubscription].
+    pub fn subscribe(&self, preset: BrainFlowPresets, poll_interval: Duration) -> Result<BoardSubscription<'_>> {
+        self.board().subscribe(preset, poll_interval)
+    }
+
+    /// Stop streaming data, the data left in the ringbuffer can still be read from the returned session.
+    pub fn stop_stream(mut self) -> Result<PreparedSession> {
+        let session = self.session.take().expect("session is only taken on stop");
+        session.board().stop_stream()?;
+        Ok(session)
+    }
+}
+
+impl Drop for StreamingSession {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            let _ = session.board().stop_stream();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::board_shim::BoardShim;
+    use crate::brainflow_input_params::BrainFlowInputParamsBuilder;
+    use crate::{BoardIds, BrainFlowPresets};
+
+    fn board_shim(other_info: &str) -> BoardShim {
+        let params = BrainFlowInputParamsBuilder::new().other_info(other_info).build();
+        BoardShim::new(BoardIds::SyntheticBoard, params).unwrap()
+    }
+
+    #[test]
+    fn test_session_lifecycle() {
+        let session = board_shim("session_lifecycle").prepare().unwrap();
+        assert!(session.board().is_prepared().unwrap());
+
+        let streaming = session.start_stream(45000, "").unwrap();
+        thread::sleep(Duration::from_millis(200));
+        streaming.insert_marker(1.0, BrainFlowPresets::DefaultPreset).unwrap();
+
+        let session = streaming.stop_stream().unwrap();
+        let data = session.get_board_data(None, BrainFlowPresets::DefaultPreset).unwrap();
+        assert!(data.ncols() > 0);
+
+        let board = session.release().unwrap();
+        assert!(!board.is_prepared().unwrap());
+    }
+
+    #[test]
+    fn test_drop_releases_session() {
+        let streaming = board_shim("drop_releases_session").prepare().unwrap().start_stream(45000, "").unwrap();
+        // a second shim with the same params refers to the same native session
+        let board = board_shim("drop_releases_session");
+        assert!(board.is_prepared().unwrap());
+        drop(streaming);
+        assert!(!board.is_prepared().unwrap());
+    }
+}
@@ -0,0 +1,325 @@
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::{
+    board_shim::{self, BoardShim},
+    brainflow_input_params::{BrainFlowInputParams, BrainFlowInputParamsBuilder},
+    data_filter,
+    error::Error,
+    ml_model,
+    session::{PreparedSession, StreamingSession},
+    streamer::StreamerSpec,
+    BoardIds, BrainFlowPresets, IpProtocolTypes, LogLevels, Result,
+};
+
+/// Ring buffer size used by the examples, about 3 minutes of data for most boards.
+pub const DEFAULT_BUFFER_SIZE: usize = 45000;
+
+/// Board, input params, streamers and logging of a session, loadable from TOML or JSON.
+///
+/// Enums are given by name, e.g.
+/// ```toml
+/// board_id = "CytonBoard"
+/// buffer_size = 45000
+/// presets = ["DefaultPreset"]
+///
+/// [input_params]
+/// serial_port = "/dev/ttyUSB0"
+///
+/// [[streamers]]
+/// streamer = "file://data.csv:w"
+///
+/// [log_levels]
+/// board_controller = "LevelInfo"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters)]
+#[getset(get = "pub")]
+#[serde(deny_unknown_fields)]
+pub struct SessionConfig {
+    board_id: BoardIds,
+    #[serde(default)]
+    input_params: InputParamsConfig,
+    #[serde(default = "default_buffer_size")]
+    buffer_size: usize,
+    #[serde(default)]
+    streamers: Vec<StreamerConfig>,
+    /// Presets to read data from.
+    #[serde(default = "default_presets")]
+    presets: Vec<BrainFlowPresets>,
+    #[serde(default)]
+    log_levels: LogLevelsConfig,
+}
+
+/// All fields of [BrainFlowInputParams], with enums given by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputParamsConfig {
+    pub serial_port: String,
+    pub mac_address: String,
+    pub ip_address: String,
+    pub ip_address_aux: String,
+    pub ip_address_anc: String,
+    pub ip_port: usize,
+    pub ip_port_aux: usize,
+    pub ip_port_anc: usize,
+    pub ip_protocol: Option<IpProtocolTypes>,
+    pub other_info: String,
+    pub timeout: usize,
+    pub serial_number: String,
+    pub file: String,
+    pub file_aux: String,
+    pub file_anc: String,
+    pub master_board: Option<BoardIds>,
+}
+
+impl InputParamsConfig {
+    /// Build the native input params.
+    pub fn to_input_params(&self) -> BrainFlowInputParams {
+        let mut builder = BrainFlowInputParamsBuilder::new()
+            .serial_port(&self.serial_port)
+            .mac_address(&self.mac_address)
+            .ip_address(&self.ip_address)
+            .ip_address_aux(&self.ip_address_aux)
+            .ip_address_anc(&self.ip_address_anc)
+            .ip_port(self.ip_port)
+            .ip_port_aux(self.ip_port_aux)
+            .ip_port_anc(self.ip_port_anc)
+            .other_info(&self.other_info)
+            .timeout(self.timeout)
+            .serial_number(&self.serial_number)
+            .file(&self.file)
+            .file_aux(&self.file_aux)
+            .file_anc(&self.file_anc);
+        if let Some(protocol) = self.ip_protocol {
+            builder = builder.ip_protocol(protocol);
+        }
+        if let Some(master_board) = self.master_board {
+            builder = builder.master_board(master_board);
+        }
+        builder.build()
+    }
+}
+
+/// A streamer added when the stream starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StreamerConfig {
+    pub streamer: StreamerSpec,
+    #[serde(default = "default_preset")]
+    pub preset: BrainFlowPresets,
+}
+
+/// Log levels of the native modules, modules without a level keep their current one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogLevelsConfig {
+    pub board_controller: Option<LogLevels>,
+    pub data_handler: Option<LogLevels>,
+    pub ml_module: Option<LogLevels>,
+}
+
+impl LogLevelsConfig {
+    /// Set the configured log levels.
+    pub fn apply(&self) -> Result<()> {
+        if let Some(level) = self.board_controller {
+            board_shim::set_log_level(level)?;
+        }
+        if let Some(level) = self.data_handler {
+            data_filter::set_log_level(level)?;
+        }
+        if let Some(level) = self.ml_module {
+            ml_model::set_log_level(level)?;
+        }
+        Ok(())
+    }
+}
+
+fn default_buffer_size() -> usize {
+    DEFAULT_BUFFER_SIZE
+}
+
+fn default_preset() -> BrainFlowPresets {
+    BrainFlowPresets::DefaultPreset
+}
+
+fn default_presets() -> Vec<BrainFlowPresets> {
+    vec![default_preset()]
+}
+
+impl SessionConfig {
+    /// Config for `board_id` with default input params and settings.
+    pub fn new(board_id: BoardIds) -> Self {
+        Self {
+            board_id,
+            input_params: Default::default(),
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            streamers: Vec::new(),
+            presets: default_presets(),
+            log_levels: Default::default(),
+        }
+    }
+
+    /// Parse a TOML config.
+    pub fn from_toml_str(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// Parse a JSON config.
+    pub fn from_json_str(s: &str) -> Result<Self> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Load a config file, the format is chosen by the `.toml` or `.json` extension.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let parse = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str,
+            Some("json") => Self::from_json_str,
+            _ => {
+                return Err(Error::InvalidSessionConfig(format!(
+                    "{} should have a .toml or .json extension",
+                    path.display()
+                )))
+            }
+        };
+        parse(&fs::read_to_string(path)?)
+    }
+
+    /// Serialize the config as TOML.
+    pub fn to_toml_string(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| Error::InvalidSessionConfig(e.to_string()))
+    }
+
+    /// Check the input params for the board and that all streamers can be opened.
+    pub fn validate(&self) -> Result<()> {
+        if self.buffer_size == 0 {
+            return Err(Error::InvalidSessionConfig("buffer_size must not be 0".to_string()));
+        }
+        self.input_params.to_input_params().validate_for(self.board_id)?;
+        self.streamers.iter().try_for_each(|s| s.streamer.validate())
+    }
+
+    /// Validate the config, apply the log levels and create the board.
+    fn board(&self) -> Result<BoardShim> {
+        self.validate()?;
+        self.log_levels.apply()?;
+        BoardShim::new(self.board_id, self.input_params.to_input_params())
+    }
+
+    /// Apply the log levels, then create and prepare the board.
+    pub fn open(&self) -> Result<BoardShim> {
+        let board = self.board()?;
+        board.prepare_session()?;
+        Ok(board)
+    }
+
+    /// Like [SessionConfig::open], but the session is released when the returned [PreparedSession] is dropped.
+    pub fn prepare(&self) -> Result<PreparedSession> {
+        self.board()?.prepare()
+    }
+
+    /// Prepare the board, start streaming and add the configured streamers.
+    pub fn start(&self) -> Result<StreamingSession> {
+        let streaming = self.prepare()?.start_stream(self.buffer_size, "")?;
+        for streamer in &self.streamers {
+            streaming.add_streamer_with_spec(&streamer.streamer, streamer.preset)?;
+        }
+        Ok(streaming)
+    }
+
+    /// Start streaming on a board returned by [SessionConfig::open] and add the configured streamers.
+    pub fn start_stream(&self, board: &BoardShim) -> Result<()> {
+        board.start_stream(self.buffer_size, "")?;
+        for streamer in &self.streamers {
+            board.add_streamer_with_spec(&streamer.streamer, streamer.preset)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, thread, time::Duration};
+
+    use super::SessionConfig;
+    use crate::{error::Error, streamer::StreamerSpec, BoardIds, BrainFlowPresets, IpProtocolTypes, LogLevels};
+
+    const CONFIG: &str = r#"
+        board_id = "STREAMING_BOARD"
+        buffer_size = 1000
+        presets = ["DefaultPreset", "AuxiliaryPreset"]
+
+        [input_params]
+        ip_address = "225.1.1.1"
+        ip_port = 6677
+        ip_protocol = "Udp"
+        master_board = "CytonDaisyBoard"
+
+        [[streamers]]
+        streamer = "file://data.csv:w"
+        preset = "AuxiliaryPreset"
+
+        [log_levels]
+        board_controller = "LevelWarn"
+    "#;
+
+    #[test]
+    fn test_parse_toml() {
+        let config = SessionConfig::from_toml_str(CONFIG).unwrap();
+        assert_eq!(BoardIds::StreamingBoard, *config.board_id());
+        assert_eq!(1000, *config.buffer_size());
+        assert_eq!(&[BrainFlowPresets::DefaultPreset, BrainFlowPresets::AuxiliaryPreset], config.presets().as_slice());
+        assert_eq!(Some(IpProtocolTypes::Udp), config.input_params().ip_protocol);
+        assert_eq!(Some(BoardIds::CytonDaisyBoard), config.input_params().master_board);
+        assert_eq!("file://data.csv:w".parse::<StreamerSpec>().unwrap(), config.streamers()[0].streamer);
+        assert_eq!(Some(LogLevels::LevelWarn), config.log_levels().board_controller);
+        config.validate().unwrap();
+
+        let params = config.input_params().to_input_params();
+        assert_eq!(BoardIds::CytonDaisyBoard as usize, *params.master_board());
+        assert_eq!(config, SessionConfig::from_toml_str(&config.to_toml_string().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_parse_json_defaults() {
+        let config = SessionConfig::from_json_str(r#"{"board_id": "SyntheticBoard"}"#).unwrap();
+        assert_eq!(SessionConfig::new(BoardIds::SyntheticBoard), config);
+        assert!(SessionConfig::from_json_str(r#"{"board_id": "NoSuchBoard"}"#).is_err());
+        assert!(SessionConfig::from_json_str(r#"{"board_id": "SyntheticBoard", "buffer": 10}"#).is_err());
+    }
+
+    #[test]
+    fn test_load_and_open() {
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session_config.toml");
+        fs::write(
+            &path,
+            "board_id = \"SyntheticBoard\"\n[input_params]\nother_info = \"session_config\"\n",
+        )
+        .unwrap();
+        let config = SessionConfig::load(&path).unwrap();
+        // a yaml file is rejected by its extension, not by failing to parse it
+        let yaml = dir.join("session_config.yaml");
+        fs::write(&yaml, "board_id: SyntheticBoard\n").unwrap();
+        match SessionConfig::load(&yaml) {
+            Err(Error::InvalidSessionConfig(message)) => assert!(message.contains(".toml or .json")),
+            res => panic!("unexpected {:?}", res),
+        }
+
+        let board = config.open().unwrap();
+        config.start_stream(&board).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        board.stop_stream().unwrap();
+        for preset in config.presets() {
+            assert!(board.get_board_data(None, *preset).unwrap().ncols() > 0);
+        }
+        board.release_session().unwrap();
+
+        let streaming = config.start().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        let session = streaming.stop_stream().unwrap();
+        assert!(session.get_board_data(None, BrainFlowPresets::DefaultPreset).unwrap().ncols() > 0);
+    }
+}
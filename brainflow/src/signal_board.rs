@@ -0,0 +1,543 @@
+use ndarray::Array2;
+use std::{
+    f64::consts::PI,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    board::Board, board_description::BoardDescription, stream_buffer::StreamBuffer, BoardIds, BrainFlowPresets,
+    NoiseTypes, Result,
+};
+
+/// How often the streaming thread of a [SignalBoard] adds samples.
+const PUSH_INTERVAL: Duration = Duration::from_millis(10);
+/// Duration of a simulated blink in seconds.
+const BLINK_DURATION: f64 = 0.3;
+
+/// One sine component of a channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sine {
+    /// Frequency in Hz.
+    pub frequency: f64,
+    pub amplitude: f64,
+    /// Phase in radians.
+    pub phase: f64,
+}
+
+impl Sine {
+    /// Sine with zero phase.
+    pub fn new(frequency: f64, amplitude: f64) -> Self {
+        Self {
+            frequency,
+            amplitude,
+            phase: 0.0,
+        }
+    }
+}
+
+/// Settings of a [SignalGenerator], see [SignalBoardBuilder].
+#[derive(Debug, Clone, PartialEq)]
+struct SignalConfig {
+    seed: u64,
+    /// Sine components per row of the data table.
+    sines: Vec<(usize, Sine)>,
+    white_noise: f64,
+    pink_noise: f64,
+    line_noise: Option<(NoiseTypes, f64)>,
+    /// Blinks per second and their amplitude.
+    blinks: Option<(f64, f64)>,
+    blink_channels: Option<Vec<usize>>,
+    /// Bursts per second, their duration in seconds and amplitude.
+    emg_bursts: Option<(f64, f64, f64)>,
+    /// Battery level at the start and its drain per second.
+    battery: (f64, f64),
+    /// Markers with their time in seconds since the start of the stream.
+    markers: Vec<(f64, f64)>,
+}
+
+/// Small seeded random number generator (SplitMix64), so that data only depends on the seed.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal distribution, by the Box-Muller transform.
+    fn gaussian(&mut self) -> f64 {
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+}
+
+/// Generates the samples of a [SignalBoard], usable on its own to create data offline.
+///
+/// Signals are added to the EEG channels of the description, or to the EXG channels if it has none.
+#[derive(Debug, Clone)]
+pub struct SignalGenerator {
+    description: BoardDescription,
+    config: SignalConfig,
+    signal_channels: Vec<usize>,
+    blink_channels: Vec<usize>,
+    rng: Rng,
+    /// Pink noise filter state per signal channel.
+    pink_state: Vec<[f64; 3]>,
+    /// Start samples of active blinks.
+    blinks: Vec<usize>,
+    /// Last sample of the active EMG burst.
+    emg_burst_end: Option<usize>,
+    sample: usize,
+}
+
+impl SignalGenerator {
+    fn new(description: BoardDescription, config: SignalConfig) -> Self {
+        let signal_channels = if description.eeg_channels().is_empty() {
+            description.exg_channels().clone()
+        } else {
+            description.eeg_channels().clone()
+        };
+        let blink_channels = config
+            .blink_channels
+            .clone()
+            .unwrap_or_else(|| signal_channels.iter().take(2).copied().collect());
+        Self {
+            rng: Rng(config.seed),
+            pink_state: vec![[0.0; 3]; signal_channels.len()],
+            description,
+            config,
+            signal_channels,
+            blink_channels,
+            blinks: Vec::new(),
+            emg_burst_end: None,
+            sample: 0,
+        }
+    }
+
+    /// Restart at time 0 with the initial seed.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.description.clone(), self.config.clone());
+    }
+
+    /// Layout of the generated data tables.
+    pub fn description(&self) -> &BoardDescription {
+        &self.description
+    }
+
+    /// Time of the next sample in seconds since the start.
+    fn time(&self) -> f64 {
+        self.sample as f64 / *self.description.sampling_rate() as f64
+    }
+
+    /// Generate the next sample, its timestamp is the time since the start in seconds.
+    pub fn next_sample(&mut self) -> Vec<f64> {
+        let sampling_rate = *self.description.sampling_rate() as f64;
+        let t = self.time();
+        let mut sample = vec![0.0; *self.description.num_rows()];
+
+        for (row, sine) in &self.config.sines {
+            if let Some(value) = sample.get_mut(*row) {
+                *value += sine.amplitude * (2.0 * PI * sine.frequency * t + sine.phase).sin();
+            }
+        }
+
+        if let Some((rate, _)) = self.config.blinks {
+            let current = self.sample;
+            self.blinks.retain(|start| (current - start) as f64 / sampling_rate < BLINK_DURATION);
+            if self.rng.uniform() < rate / sampling_rate {
+                self.blinks.push(self.sample);
+            }
+        }
+        if let Some((rate, duration, _)) = self.config.emg_bursts {
+            if self.emg_burst_end.is_some_and(|end| self.sample > end) {
+                self.emg_burst_end = None;
+            }
+            if self.emg_burst_end.is_none() && self.rng.uniform() < rate / sampling_rate {
+                self.emg_burst_end = Some(self.sample + (duration * sampling_rate) as usize);
+            }
+        }
+
+        for (i, &row) in self.signal_channels.iter().enumerate() {
+            let mut value = 0.0;
+            if self.config.white_noise > 0.0 {
+                value += self.config.white_noise * self.rng.gaussian();
+            }
+            if self.config.pink_noise > 0.0 {
+                // Paul Kellet's economy filter, roughly unit variance for unit white noise
+                let white = self.rng.gaussian();
+                let state = &mut self.pink_state[i];
+                state[0] = 0.99765 * state[0] + white * 0.0990460;
+                state[1] = 0.96300 * state[1] + white * 0.2965164;
+                state[2] = 0.57000 * state[2] + white * 1.0526913;
+                value += self.config.pink_noise * (state[0] + state[1] + state[2] + white * 0.1848) / 3.0;
+            }
+            if let Some((noise_type, amplitude)) = self.config.line_noise {
+                let frequencies: &[f64] = match noise_type {
+                    NoiseTypes::Fifty => &[50.0],
+                    NoiseTypes::Sixty => &[60.0],
+                    NoiseTypes::FiftyAndSixty => &[50.0, 60.0],
+                };
+                for frequency in frequencies {
+                    value += amplitude * (2.0 * PI * frequency * t).sin();
+                }
+            }
+            if let Some((_, amplitude)) = self.config.blinks {
+                if self.blink_channels.contains(&row) {
+                    for start in &self.blinks {
+                        // gaussian bump centered in the blink
+                        let dt = (self.sample - start) as f64 / sampling_rate - BLINK_DURATION / 2.0;
+                        value += amplitude * (-0.5 * (dt / (BLINK_DURATION / 6.0)).powi(2)).exp();
+                    }
+                }
+            }
+            if let (Some((_, _, amplitude)), Some(_)) = (self.config.emg_bursts, self.emg_burst_end) {
+                value += amplitude * self.rng.gaussian();
+            }
+            sample[row] += value;
+        }
+
+        if let Some(channel) = *self.description.package_num_channel() {
+            sample[channel] = (self.sample % 256) as f64;
+        }
+        if let Some(channel) = *self.description.battery_channel() {
+            let (start, drain) = self.config.battery;
+            sample[channel] = (start - drain * t).max(0.0);
+        }
+        if let Some(channel) = *self.description.marker_channel() {
+            let next = t + 1.0 / sampling_rate;
+            if let Some((_, value)) = self.config.markers.iter().find(|(time, _)| *time >= t && *time < next) {
+                sample[channel] = *value;
+            }
+        }
+        if let Some(channel) = *self.description.timestamp_channel() {
+            sample[channel] = t;
+        }
+        self.sample += 1;
+        sample
+    }
+
+    /// Generate the next `num_samples` samples as a data table.
+    pub fn generate(&mut self, num_samples: usize) -> Array2<f64> {
+        let mut data = Array2::zeros((*self.description.num_rows(), num_samples));
+        for i in 0..num_samples {
+            for (row, value) in self.next_sample().into_iter().enumerate() {
+                data[[row, i]] = value;
+            }
+        }
+        data
+    }
+}
+
+/// Builder for [SignalBoard] and [SignalGenerator].
+pub struct SignalBoardBuilder {
+    board_id: BoardIds,
+    description: BoardDescription,
+    config: SignalConfig,
+}
+
+impl SignalBoardBuilder {
+    /// Generate data tables with the layout of `description`, reported as `board_id`.
+    pub fn new(board_id: BoardIds, description: BoardDescription) -> Self {
+        Self {
+            board_id,
+            description,
+            config: SignalConfig {
+                seed: 0,
+                sines: Vec::new(),
+                white_noise: 0.0,
+                pink_noise: 0.0,
+                line_noise: None,
+                blinks: None,
+                blink_channels: None,
+                emg_bursts: None,
+                battery: (100.0, 0.0),
+                markers: Vec::new(),
+            },
+        }
+    }
+
+    /// Generate data tables with the layout of a native board, only the description is read from the native
+    /// library.
+    pub fn for_board(board_id: BoardIds) -> Result<Self> {
+        Ok(Self::new(board_id, BoardDescription::load(board_id, BrainFlowPresets::DefaultPreset)?))
+    }
+
+    /// Seed of all random signals.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    /// Add a sine to the channel in row `channel` of the data table.
+    pub fn sine(mut self, channel: usize, sine: Sine) -> Self {
+        self.config.sines.push((channel, sine));
+        self
+    }
+
+    /// Gaussian white noise with standard deviation `amplitude` on all signal channels.
+    pub fn white_noise(mut self, amplitude: f64) -> Self {
+        self.config.white_noise = amplitude;
+        self
+    }
+
+    /// 1/f noise of roughly standard deviation `amplitude` on all signal channels.
+    pub fn pink_noise(mut self, amplitude: f64) -> Self {
+        self.config.pink_noise = amplitude;
+        self
+    }
+
+    /// Power line interference on all signal channels.
+    pub fn line_noise(mut self, noise_type: NoiseTypes, amplitude: f64) -> Self {
+        self.config.line_noise = Some((noise_type, amplitude));
+        self
+    }
+
+    /// Blinks at random times, `rate` per second on average.
+    pub fn blinks(mut self, rate: f64, amplitude: f64) -> Self {
+        self.config.blinks = Some((rate, amplitude));
+        self
+    }
+
+    /// Rows with blinks, by default the first two signal channels.
+    pub fn blink_channels(mut self, channels: Vec<usize>) -> Self {
+        self.config.blink_channels = Some(channels);
+        self
+    }
+
+    /// Broadband muscle artifacts on all signal channels, `rate` per second on average.
+    pub fn emg_bursts(mut self, rate: f64, duration: f64, amplitude: f64) -> Self {
+        self.config.emg_bursts = Some((rate, duration, amplitude));
+        self
+    }
+
+    /// Battery level which starts at `start` and drains by `drain` per second.
+    pub fn battery(mut self, start: f64, drain: f64) -> Self {
+        self.config.battery = (start, drain);
+        self
+    }
+
+    /// Put marker `value` into the first sample at or after `time` seconds since the start of the stream.
+    pub fn marker(mut self, time: f64, value: f64) -> Self {
+        self.config.markers.push((time, value));
+        self
+    }
+
+    /// Build a generator for offline use.
+    pub fn build_generator(self) -> SignalGenerator {
+        SignalGenerator::new(self.description, self.config)
+    }
+
+    /// Build a board which generates data in real time while streaming.
+    pub fn build(self) -> SignalBoard {
+        let buffer = StreamBuffer::new(self.board_id, vec![(BrainFlowPresets::DefaultPreset, self.description.clone())]);
+        SignalBoard {
+            shared: Arc::new(Shared {
+                buffer: Mutex::new(buffer),
+                generator: Mutex::new(SignalGenerator::new(self.description, self.config)),
+            }),
+            worker: Mutex::new(None),
+        }
+    }
+}
+
+struct Shared {
+    buffer: Mutex<StreamBuffer>,
+    generator: Mutex<SignalGenerator>,
+}
+
+/// Pure Rust [Board] producing seeded synthetic signals in real time for [BrainFlowPresets::DefaultPreset].
+///
+/// Every stream starts again from the seed, timestamps are unix times like for native boards.
+pub struct SignalBoard {
+    shared: Arc<Shared>,
+    worker: Mutex<Option<(Arc<AtomicBool>, JoinHandle<()>)>>,
+}
+
+impl SignalBoard {
+    fn stop_worker(&self) {
+        if let Some((stop, handle)) = self.worker.lock().unwrap().take() {
+            stop.store(true, Ordering::Relaxed);
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Push samples generated since `start` until `stop` is set.
+fn generate_in_real_time(shared: Arc<Shared>, stop: Arc<AtomicBool>) {
+    let start = Instant::now();
+    let start_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+    let mut generator = shared.generator.lock().unwrap();
+    generator.reset();
+    let sampling_rate = *generator.description().sampling_rate() as f64;
+    let timestamp_channel = *generator.description().timestamp_channel();
+    let mut pushed = 0;
+    while !stop.load(Ordering::Relaxed) {
+        let due = (start.elapsed().as_secs_f64() * sampling_rate) as usize;
+        let mut buffer = shared.buffer.lock().unwrap();
+        while pushed < due {
+            let mut sample = generator.next_sample();
+            if let Some(channel) = timestamp_channel {
+                sample[channel] += start_time;
+            }
+            if buffer.push(BrainFlowPresets::DefaultPreset, sample).is_err() {
+                return;
+            }
+            pushed += 1;
+        }
+        drop(buffer);
+        thread::sleep(PUSH_INTERVAL);
+    }
+}
+
+impl Board for SignalBoard {
+    fn get_board_id(&self) -> BoardIds {
+        self.shared.buffer.lock().unwrap().board_id()
+    }
+
+    fn prepare_session(&self) -> Result<()> {
+        self.shared.buffer.lock().unwrap().prepare();
+        Ok(())
+    }
+
+    fn is_prepared(&self) -> Result<bool> {
+        Ok(self.shared.buffer.lock().unwrap().is_prepared())
+    }
+
+    fn start_stream(&self, buffer_size: usize, streamer_params: &str) -> Result<()> {
+        self.shared.buffer.lock().unwrap().start(buffer_size, streamer_params)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let shared = self.shared.clone();
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || generate_in_real_time(shared, stop)
+        });
+        *self.worker.lock().unwrap() = Some((stop, handle));
+        Ok(())
+    }
+
+    fn stop_stream(&self) -> Result<()> {
+        self.stop_worker();
+        self.shared.buffer.lock().unwrap().stop()
+    }
+
+    fn release_session(&self) -> Result<()> {
+        self.stop_worker();
+        self.shared.buffer.lock().unwrap().release()
+    }
+
+    fn get_board_data_count(&self, preset: BrainFlowPresets) -> Result<usize> {
+        self.shared.buffer.lock().unwrap().count(preset)
+    }
+
+    fn get_board_data(&self, n_data_points: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.shared.buffer.lock().unwrap().take(n_data_points, preset)
+    }
+
+    fn get_current_board_data(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.shared.buffer.lock().unwrap().current(num_samples, preset)
+    }
+
+    fn insert_marker(&self, value: f64, preset: BrainFlowPresets) -> Result<()> {
+        self.shared.buffer.lock().unwrap().insert_marker(value, preset)
+    }
+
+    fn get_description(&self, preset: BrainFlowPresets) -> Result<BoardDescription> {
+        self.shared.buffer.lock().unwrap().description(preset)
+    }
+}
+
+impl Drop for SignalBoard {
+    fn drop(&mut self) {
+        self.stop_worker();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f64::consts::PI, thread, time::Duration};
+
+    use super::{Sine, SignalBoardBuilder};
+    use crate::{board::Board, board_description::BoardDescription, BoardIds, BrainFlowPresets, NoiseTypes};
+
+    fn builder() -> SignalBoardBuilder {
+        SignalBoardBuilder::for_board(BoardIds::SyntheticBoard).unwrap()
+    }
+
+    #[test]
+    fn test_sines_and_line_noise() {
+        let descr = BoardDescription::load(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        let channel = descr.eeg_channels()[0];
+        let mut generator = builder()
+            .sine(channel, Sine::new(10.0, 2.0))
+            .line_noise(NoiseTypes::Fifty, 0.5)
+            .build_generator();
+        let data = generator.generate(250);
+        for i in 0..250 {
+            let t = i as f64 / 250.0;
+            let expected = 2.0 * (2.0 * PI * 10.0 * t).sin() + 0.5 * (2.0 * PI * 50.0 * t).sin();
+            assert_relative_eq!(expected, data[[channel, i]], epsilon = 1e-9);
+            assert_relative_eq!(t, data[[descr.timestamp_channel().unwrap(), i]]);
+        }
+        assert_relative_eq!(0.0, data[[descr.eeg_channels()[1], 100]], epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_seeded_noise_is_reproducible() {
+        let generate = |seed| {
+            builder()
+                .seed(seed)
+                .white_noise(1.0)
+                .pink_noise(1.0)
+                .blinks(1.0, 100.0)
+                .emg_bursts(0.5, 0.2, 5.0)
+                .build_generator()
+                .generate(1000)
+        };
+        assert_eq!(generate(7), generate(7));
+        assert_ne!(generate(7), generate(8));
+    }
+
+    #[test]
+    fn test_battery_and_markers() {
+        let descr = BoardDescription::load(BoardIds::SyntheticBoard, BrainFlowPresets::DefaultPreset).unwrap();
+        let mut generator = builder().battery(90.0, 10.0).marker(0.5, 3.0).build_generator();
+        let data = generator.generate(500);
+        let battery = descr.battery_channel().unwrap();
+        assert_relative_eq!(90.0, data[[battery, 0]]);
+        assert_relative_eq!(80.0, data[[battery, 250]]);
+        let markers = data.row(descr.marker_channel().unwrap());
+        assert_relative_eq!(3.0, markers[125]);
+        assert_relative_eq!(3.0, markers.sum());
+    }
+
+    #[test]
+    fn test_streams_in_real_time() {
+        let board = builder().seed(1).white_noise(1.0).build();
+        board.prepare_session().unwrap();
+        board.start_stream(45000, "").unwrap();
+        thread::sleep(Duration::from_millis(200));
+        board.insert_marker(1.0, BrainFlowPresets::DefaultPreset).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        board.stop_stream().unwrap();
+        let data = board.get_typed_board_data(None, BrainFlowPresets::DefaultPreset).unwrap();
+        assert!(data.num_samples() > 20);
+        assert_relative_eq!(1.0, data.markers().unwrap().sum());
+        assert!(data.timestamps().unwrap()[0] > 1.0e9);
+        board.release_session().unwrap();
+    }
+}
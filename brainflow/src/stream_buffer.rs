@@ -0,0 +1,213 @@
+use ndarray::Array2;
+use std::collections::VecDeque;
+
+use crate::{
+    board_description::BoardDescription,
+    error::Error,
+    BoardIds, BrainFlowPresets, Result,
+};
+
+/// Ring buffer of one preset.
+struct PresetBuffer {
+    preset: BrainFlowPresets,
+    description: BoardDescription,
+    samples: VecDeque<Vec<f64>>,
+    /// Marker added to the next sample.
+    pending_marker: Option<f64>,
+}
+
+/// Session state and ring buffers of a pure Rust [crate::board::Board], following the rules of the native
+/// board controller, e.g. data can only be read from a prepared session.
+pub(crate) struct StreamBuffer {
+    board_id: BoardIds,
+    presets: Vec<PresetBuffer>,
+    prepared: bool,
+    streaming: bool,
+    /// Buffer size of the last started stream, `None` if no stream was started in this session.
+    capacity: Option<usize>,
+}
+
+impl StreamBuffer {
+    pub(crate) fn new(board_id: BoardIds, descriptions: Vec<(BrainFlowPresets, BoardDescription)>) -> Self {
+        let presets = descriptions
+            .into_iter()
+            .map(|(preset, description)| PresetBuffer {
+                preset,
+                description,
+                samples: VecDeque::new(),
+                pending_marker: None,
+            })
+            .collect();
+        Self {
+            board_id,
+            presets,
+            prepared: false,
+            streaming: false,
+            capacity: None,
+        }
+    }
+
+    pub(crate) fn board_id(&self) -> BoardIds {
+        self.board_id
+    }
+
+    pub(crate) fn is_prepared(&self) -> bool {
+        self.prepared
+    }
+
+    fn error(&self, operation: &'static str, reason: impl Into<String>) -> Error {
+        Error::Board {
+            board_id: self.board_id,
+            operation,
+            reason: reason.into(),
+        }
+    }
+
+    fn check_prepared(&self, operation: &'static str) -> Result<()> {
+        if self.prepared {
+            Ok(())
+        } else {
+            Err(self.error(operation, "session is not prepared"))
+        }
+    }
+
+    fn preset(&self, operation: &'static str, preset: BrainFlowPresets) -> Result<&PresetBuffer> {
+        self.presets
+            .iter()
+            .find(|p| p.preset == preset)
+            .ok_or_else(|| self.error(operation, format!("{:?} is not supported", preset)))
+    }
+
+    fn preset_mut(&mut self, operation: &'static str, preset: BrainFlowPresets) -> Result<&mut PresetBuffer> {
+        self.preset(operation, preset)?;
+        Ok(self.presets.iter_mut().find(|p| p.preset == preset).unwrap())
+    }
+
+    pub(crate) fn description(&self, preset: BrainFlowPresets) -> Result<BoardDescription> {
+        Ok(self.preset("get_board_descr", preset)?.description.clone())
+    }
+
+    pub(crate) fn prepare(&mut self) {
+        self.prepared = true;
+    }
+
+    pub(crate) fn start(&mut self, buffer_size: usize, streamer_params: &str) -> Result<()> {
+        self.check_prepared("start_stream")?;
+        if self.streaming {
+            return Err(self.error("start_stream", "stream is already running"));
+        }
+        if buffer_size == 0 {
+            return Err(self.error("start_stream", "buffer size must be positive"));
+        }
+        if !streamer_params.is_empty() {
+            return Err(self.error("start_stream", "streamers are not supported"));
+        }
+        for preset in &mut self.presets {
+            preset.samples.clear();
+            preset.pending_marker = None;
+        }
+        self.capacity = Some(buffer_size);
+        self.streaming = true;
+        Ok(())
+    }
+
+    pub(crate) fn stop(&mut self) -> Result<()> {
+        self.check_prepared("stop_stream")?;
+        if !self.streaming {
+            return Err(self.error("stop_stream", "stream is not running"));
+        }
+        self.streaming = false;
+        Ok(())
+    }
+
+    pub(crate) fn release(&mut self) -> Result<()> {
+        self.check_prepared("release_session")?;
+        self.streaming = false;
+        self.prepared = false;
+        self.capacity = None;
+        for preset in &mut self.presets {
+            preset.samples.clear();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn insert_marker(&mut self, value: f64, preset: BrainFlowPresets) -> Result<()> {
+        self.check_prepared("insert_marker")?;
+        if !self.streaming {
+            return Err(self.error("insert_marker", "stream is not running"));
+        }
+        // 0 means no marker in the data table
+        if value == 0.0 {
+            return Err(self.error("insert_marker", "marker value must not be 0"));
+        }
+        self.preset_mut("insert_marker", preset)?.pending_marker = Some(value);
+        Ok(())
+    }
+
+    /// Append one sample, dropping the oldest one if the buffer is full.
+    pub(crate) fn push(&mut self, preset: BrainFlowPresets, mut sample: Vec<f64>) -> Result<()> {
+        let capacity = match (self.streaming, self.capacity) {
+            (true, Some(capacity)) => capacity,
+            _ => return Err(self.error("push", "stream is not running")),
+        };
+        let buffer = self.preset_mut("push", preset)?;
+        let num_rows = *buffer.description.num_rows();
+        if sample.len() != num_rows {
+            let reason = format!("{:?} samples have {} rows, not {}", preset, num_rows, sample.len());
+            return Err(self.error("push", reason));
+        }
+        if let (Some(marker), Some(channel)) = (buffer.pending_marker.take(), *buffer.description.marker_channel()) {
+            sample[channel] = marker;
+        }
+        if buffer.samples.len() == capacity {
+            buffer.samples.pop_front();
+        }
+        buffer.samples.push_back(sample);
+        Ok(())
+    }
+
+    fn check_readable(&self, operation: &'static str) -> Result<()> {
+        self.check_prepared(operation)?;
+        if self.capacity.is_none() {
+            return Err(self.error(operation, "no stream was started in this session"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn count(&self, preset: BrainFlowPresets) -> Result<usize> {
+        self.check_readable("get_board_data_count")?;
+        Ok(self.preset("get_board_data_count", preset)?.samples.len())
+    }
+
+    /// Remove up to `num_samples` of the oldest samples, all if `None`.
+    pub(crate) fn take(&mut self, num_samples: Option<usize>, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.check_readable("get_board_data")?;
+        let buffer = self.preset_mut("get_board_data", preset)?;
+        let num_samples = num_samples.unwrap_or(buffer.samples.len()).min(buffer.samples.len());
+        let samples = buffer.samples.drain(..num_samples).collect::<Vec<_>>();
+        Ok(to_table(*buffer.description.num_rows(), samples.iter()))
+    }
+
+    /// The newest `num_samples` samples, without removing them.
+    pub(crate) fn current(&self, num_samples: usize, preset: BrainFlowPresets) -> Result<Array2<f64>> {
+        self.check_readable("get_current_board_data")?;
+        let buffer = self.preset("get_current_board_data", preset)?;
+        let skip = buffer.samples.len().saturating_sub(num_samples);
+        Ok(to_table(*buffer.description.num_rows(), buffer.samples.iter().skip(skip)))
+    }
+}
+
+/// Data table with one column per sample.
+fn to_table<'a, I>(num_rows: usize, samples: I) -> Array2<f64>
+where
+    I: ExactSizeIterator<Item = &'a Vec<f64>>,
+{
+    let num_samples = samples.len();
+    let mut data = Array2::zeros((num_rows, num_samples));
+    for (i, sample) in samples.enumerate() {
+        for (row, value) in sample.iter().enumerate() {
+            data[[row, i]] = *value;
+        }
+    }
+    data
+}
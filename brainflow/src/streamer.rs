@@ -0,0 +1,221 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, net::Ipv4Addr, path::PathBuf, str::FromStr};
+
+use crate::{error::Error, Result};
+
+const FILE_PREFIX: &str = "file://";
+const STREAMING_BOARD_PREFIX: &str = "streaming_board://";
+
+/// How a file streamer opens its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileMode {
+    /// Truncate the file.
+    Write,
+    /// Append to the file.
+    Append,
+}
+
+impl fmt::Display for FileMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileMode::Write => write!(f, "w"),
+            FileMode::Append => write!(f, "a"),
+        }
+    }
+}
+
+impl FromStr for FileMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "w" => Ok(FileMode::Write),
+            "a" => Ok(FileMode::Append),
+            _ => Err(Error::InvalidStreamerParams(format!(
+                "unknown file mode `{}`, expected `w` or `a`",
+                s
+            ))),
+        }
+    }
+}
+
+/// Typed streamer params for [crate::board_shim::BoardShim::start_stream_with_spec],
+/// [crate::board_shim::BoardShim::add_streamer_with_spec] and
+/// [crate::board_shim::BoardShim::delete_streamer_with_spec].
+///
+/// Parses from and displays as the native format, e.g. `file://data.csv:w` or `streaming_board://225.1.1.1:6677`,
+/// and is stored in that format by serde.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StreamerSpec {
+    /// Write data to a csv file.
+    File { path: PathBuf, mode: FileMode },
+    /// Send data to a multicast group which a [crate::BoardIds::StreamingBoard] can read.
+    StreamingBoard { address: Ipv4Addr, port: u16 },
+}
+
+impl StreamerSpec {
+    /// File streamer, the path must not be empty.
+    pub fn file<P: Into<PathBuf>>(path: P, mode: FileMode) -> Result<Self> {
+        let path = path.into();
+        if path.as_os_str().is_empty() {
+            return Err(Error::InvalidStreamerParams("file path is empty".to_string()));
+        }
+        if path.to_str().is_none() {
+            return Err(Error::InvalidStreamerParams(format!(
+                "file path {} is not valid unicode",
+                path.display()
+            )));
+        }
+        Ok(StreamerSpec::File { path, mode })
+    }
+
+    /// Streaming board streamer, the address must be an IPv4 multicast address and the port not zero.
+    pub fn streaming_board(address: Ipv4Addr, port: u16) -> Result<Self> {
+        if !address.is_multicast() {
+            return Err(Error::InvalidStreamerParams(format!(
+                "{} is not a multicast address (224.0.0.0/4)",
+                address
+            )));
+        }
+        if port == 0 {
+            return Err(Error::InvalidStreamerParams("port must not be 0".to_string()));
+        }
+        Ok(StreamerSpec::StreamingBoard { address, port })
+    }
+
+    /// Check that the streamer can be opened on this machine, e.g. that the directory of a file exists.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            StreamerSpec::File { path, .. } => match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() && !dir.is_dir() => Err(Error::InvalidStreamerParams(
+                    format!("directory {} does not exist", dir.display()),
+                )),
+                _ => Ok(()),
+            },
+            StreamerSpec::StreamingBoard { .. } => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for StreamerSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamerSpec::File { path, mode } => write!(f, "{}{}:{}", FILE_PREFIX, path.display(), mode),
+            StreamerSpec::StreamingBoard { address, port } => {
+                write!(f, "{}{}:{}", STREAMING_BOARD_PREFIX, address, port)
+            }
+        }
+    }
+}
+
+impl FromStr for StreamerSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(rest) = s.strip_prefix(FILE_PREFIX) {
+            // the path itself may contain colons, e.g. on windows
+            let (path, mode) = rest.rsplit_once(':').ok_or_else(|| {
+                Error::InvalidStreamerParams(format!("`{}` has no file mode, expected {}<path>:<w|a>", s, FILE_PREFIX))
+            })?;
+            StreamerSpec::file(path, mode.parse()?)
+        } else if let Some(rest) = s.strip_prefix(STREAMING_BOARD_PREFIX) {
+            let (address, port) = rest.rsplit_once(':').ok_or_else(|| {
+                Error::InvalidStreamerParams(format!(
+                    "`{}` has no port, expected {}<address>:<port>",
+                    s, STREAMING_BOARD_PREFIX
+                ))
+            })?;
+            let address = address
+                .parse()
+                .map_err(|_| Error::InvalidStreamerParams(format!("`{}` is not an IPv4 address", address)))?;
+            let port = port
+                .parse()
+                .map_err(|_| Error::InvalidStreamerParams(format!("`{}` is not a port", port)))?;
+            StreamerSpec::streaming_board(address, port)
+        } else {
+            Err(Error::InvalidStreamerParams(format!(
+                "`{}` should start with {} or {}",
+                s, FILE_PREFIX, STREAMING_BOARD_PREFIX
+            )))
+        }
+    }
+}
+
+impl Serialize for StreamerSpec {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for StreamerSpec {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::{FileMode, StreamerSpec};
+
+    #[test]
+    fn test_parse_round_trip() {
+        for params in [
+            "file://data.csv:w",
+            "file://C:\\data\\data.csv:a",
+            "streaming_board://225.1.1.1:6677",
+        ] {
+            let spec: StreamerSpec = params.parse().unwrap();
+            assert_eq!(params, spec.to_string());
+        }
+        assert_eq!(
+            StreamerSpec::File { path: "data.csv".into(), mode: FileMode::Write },
+            "file://data.csv:w".parse().unwrap()
+        );
+        assert_eq!(
+            StreamerSpec::StreamingBoard { address: Ipv4Addr::new(225, 1, 1, 1), port: 6677 },
+            "streaming_board://225.1.1.1:6677".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_params() {
+        for params in [
+            "",
+            "data.csv",
+            "file://data.csv",
+            "file://data.csv:x",
+            "file://:w",
+            "streaming_board://192.168.1.1:6677",
+            "streaming_board://225.1.1.1",
+            "streaming_board://225.1.1.1:0",
+            "streaming_board://225.1.1.1:70000",
+        ] {
+            assert!(params.parse::<StreamerSpec>().is_err(), "{} should be rejected", params);
+        }
+    }
+
+    #[test]
+    fn test_validate_checks_directory() {
+        let spec = StreamerSpec::file("data.csv", FileMode::Write).unwrap();
+        assert!(spec.validate().is_ok());
+        let spec = StreamerSpec::file("/does/not/exist/data.csv", FileMode::Write).unwrap();
+        assert!(spec.validate().is_err());
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        let spec: StreamerSpec = "file://data.csv:a".parse().unwrap();
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!("\"file://data.csv:a\"", json);
+        assert_eq!(spec, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,143 @@
+use ndarray::Array2;
+use std::{
+    marker::PhantomData,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use crate::{board_shim::BoardShim, BrainFlowPresets, Result};
+
+/// The polling thread of a [BoardSubscription] ended, e.g. after delivering an error, no more chunks arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("the polling thread of the subscription ended")]
+pub struct Disconnected;
+
+/// New data of a board, delivered by a background polling thread.
+///
+/// Every chunk holds the samples which arrived since the previous chunk, so chunks never overlap.
+/// The subscription is also a blocking [Iterator] which ends when polling stops,
+/// e.g. after an error was delivered. Dropping it stops and joins the polling thread.
+///
+/// The subscription borrows the board and cannot outlive it. Through [crate::session::StreamingSession::subscribe]
+/// the borrow also keeps the session from being stopped or released while polling, a plain [BoardShim] can
+/// still be stopped through the shared borrow, after which the subscription delivers the error and ends.
+pub struct BoardSubscription<'a> {
+    stop: Option<Sender<()>>,
+    chunks: Receiver<Result<Array2<f64>>>,
+    handle: Option<JoinHandle<()>>,
+    board: PhantomData<&'a BoardShim>,
+}
+
+impl<'a> BoardSubscription<'a> {
+    /// Start polling `board` every `poll_interval` for new data of `preset`.
+    pub fn new(board: &'a BoardShim, preset: BrainFlowPresets, poll_interval: Duration) -> Result<Self> {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let (sender, chunks) = mpsc::channel();
+        let board = board.handle();
+        let handle = thread::Builder::new()
+            .name(format!("brainflow-{}-poll", board.get_board_id()))
+            .spawn(move || {
+                // both a stop message and a dropped sender end the loop
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(poll_interval) {
+                    let chunk = match board.get_board_data_count(preset) {
+                        Ok(0) => continue,
+                        Ok(count) => board.get_board_data(Some(count), preset),
+                        Err(e) => Err(e),
+                    };
+                    let failed = chunk.is_err();
+                    if sender.send(chunk).is_err() || failed {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Self {
+            stop: Some(stop),
+            chunks,
+            handle: Some(handle),
+            board: PhantomData,
+        })
+    }
+
+    /// The receiving end of the chunk channel.
+    pub fn receiver(&self) -> &Receiver<Result<Array2<f64>>> {
+        &self.chunks
+    }
+
+    /// Return the next chunk if one is ready, without blocking, `Ok(None)` if none is ready yet.
+    pub fn try_next(&self) -> std::result::Result<Option<Result<Array2<f64>>>, Disconnected> {
+        match self.chunks.try_recv() {
+            Ok(chunk) => Ok(Some(chunk)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(Disconnected),
+        }
+    }
+
+    /// Wait for the next chunk for at most `timeout`, `Ok(None)` if none arrived in time.
+    pub fn next_timeout(&self, timeout: Duration) -> std::result::Result<Option<Result<Array2<f64>>>, Disconnected> {
+        match self.chunks.recv_timeout(timeout) {
+            Ok(chunk) => Ok(Some(chunk)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(Disconnected),
+        }
+    }
+}
+
+impl Iterator for BoardSubscription<'_> {
+    type Item = Result<Array2<f64>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.recv().ok()
+    }
+}
+
+impl Drop for BoardSubscription<'_> {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Disconnected;
+    use crate::board_shim::{self, BoardShim};
+    use crate::brainflow_input_params::BrainFlowInputParamsBuilder;
+    use crate::{BoardIds, BrainFlowPresets};
+
+    #[test]
+    fn test_chunks_do_not_overlap() {
+        let params = BrainFlowInputParamsBuilder::new().other_info("chunks_do_not_overlap").build();
+        let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+        let session = board.prepare().unwrap().start_stream(45000, "").unwrap();
+        let preset = BrainFlowPresets::DefaultPreset;
+        let timestamp_channel = board_shim::get_timestamp_channel(BoardIds::SyntheticBoard, preset).unwrap();
+
+        let subscription = session.subscribe(preset, Duration::from_millis(20)).unwrap();
+        let mut last_timestamp = 0.0;
+        for chunk in subscription.take(3) {
+            let chunk = chunk.unwrap();
+            assert!(chunk.ncols() > 0);
+            let timestamps = chunk.row(timestamp_channel);
+            assert!(timestamps[0] > last_timestamp);
+            last_timestamp = timestamps[timestamps.len() - 1];
+        }
+    }
+
+    #[test]
+    fn test_disconnected_after_error() {
+        let params = BrainFlowInputParamsBuilder::new().other_info("subscription_disconnected").build();
+        let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
+        // the board is not prepared, so the first poll fails and ends the polling thread
+        let subscription = board.subscribe(BrainFlowPresets::DefaultPreset, Duration::from_millis(10)).unwrap();
+        assert!(subscription.next_timeout(Duration::from_secs(5)).unwrap().unwrap().is_err());
+        assert_eq!(Err(Disconnected), subscription.next_timeout(Duration::from_secs(5)).map(|_| ()));
+        assert_eq!(Err(Disconnected), subscription.try_next().map(|_| ()));
+    }
+}
@@ -0,0 +1,965 @@
+use getset::Getters;
+use ndarray::{Array2, Axis};
+use std::{
+    convert::TryInto,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    board_description::BoardDescription, catalogue::Capability, error::Error, BoardIds, BrainFlowPresets, Result,
+};
+
+const MAGIC: &[u8] = b"XDF:";
+
+const FILE_HEADER_TAG: u16 = 1;
+const STREAM_HEADER_TAG: u16 = 2;
+const SAMPLES_TAG: u16 = 3;
+const CLOCK_OFFSET_TAG: u16 = 4;
+const BOUNDARY_TAG: u16 = 5;
+const STREAM_FOOTER_TAG: u16 = 6;
+
+/// Seconds of stream time between two clock offset chunks of a stream.
+const CLOCK_OFFSET_INTERVAL: f64 = 5.0;
+
+/// Value type of the channels of a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelFormat {
+    Float32,
+    Double64,
+    String,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+}
+
+impl ChannelFormat {
+    fn name(self) -> &'static str {
+        match self {
+            ChannelFormat::Float32 => "float32",
+            ChannelFormat::Double64 => "double64",
+            ChannelFormat::String => "string",
+            ChannelFormat::Int8 => "int8",
+            ChannelFormat::Int16 => "int16",
+            ChannelFormat::Int32 => "int32",
+            ChannelFormat::Int64 => "int64",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Self> {
+        [
+            ChannelFormat::Float32,
+            ChannelFormat::Double64,
+            ChannelFormat::String,
+            ChannelFormat::Int8,
+            ChannelFormat::Int16,
+            ChannelFormat::Int32,
+            ChannelFormat::Int64,
+        ]
+        .iter()
+        .find(|f| f.name() == name)
+        .copied()
+        .ok_or_else(|| Error::InvalidXdf(format!("unknown channel format `{}`", name)))
+    }
+}
+
+/// Description of one channel of a stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XdfChannel {
+    pub label: String,
+    pub unit: String,
+    pub channel_type: String,
+}
+
+/// Board layout of a stream written from board data, whose timestamp row is stored as the sample timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XdfBoard {
+    pub board_id: BoardIds,
+    pub preset: BrainFlowPresets,
+    pub num_rows: usize,
+    pub timestamp_channel: Option<usize>,
+}
+
+/// Stream header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XdfStreamInfo {
+    pub name: String,
+    pub stream_type: String,
+    pub channel_count: usize,
+    /// Samples per second, 0 for irregular streams like markers.
+    pub nominal_srate: f64,
+    pub channel_format: ChannelFormat,
+    pub source_id: String,
+    /// Channel descriptions, empty if the header has none.
+    pub channels: Vec<XdfChannel>,
+    pub board: Option<XdfBoard>,
+}
+
+impl XdfStreamInfo {
+    /// Stream of board data of `preset` with one channel per row except the timestamp row.
+    pub fn for_board(board_id: BoardIds, preset: BrainFlowPresets, descr: &BoardDescription) -> Self {
+        let labeled_rows = Capability::labeled_rows(descr);
+        let channels = (0..*descr.num_rows())
+            .filter(|row| Some(*row) != *descr.timestamp_channel())
+            .map(|row| match labeled_rows.iter().find(|(r, _, _)| *r == row) {
+                Some((_, capability, label)) => XdfChannel {
+                    label: label.clone(),
+                    unit: capability.unit().to_string(),
+                    channel_type: capability.name().to_string(),
+                },
+                None if Some(row) == *descr.marker_channel() => XdfChannel {
+                    label: "Marker".to_string(),
+                    unit: String::new(),
+                    channel_type: "Marker".to_string(),
+                },
+                None if Some(row) == *descr.package_num_channel() => XdfChannel {
+                    label: "Package".to_string(),
+                    unit: String::new(),
+                    channel_type: "Package".to_string(),
+                },
+                None => XdfChannel {
+                    label: format!("Row {}", row),
+                    unit: String::new(),
+                    channel_type: "Misc".to_string(),
+                },
+            })
+            .collect::<Vec<_>>();
+        let stream_type = labeled_rows.first().map(|(_, c, _)| c.name()).unwrap_or("Misc");
+        Self {
+            name: format!("{} {}", descr.name(), preset.name()),
+            stream_type: stream_type.to_string(),
+            channel_count: channels.len(),
+            nominal_srate: *descr.sampling_rate() as f64,
+            channel_format: ChannelFormat::Double64,
+            source_id: format!("brainflow_{}_{}", board_id.name(), preset.name()),
+            channels,
+            board: Some(XdfBoard {
+                board_id,
+                preset,
+                num_rows: *descr.num_rows(),
+                timestamp_channel: *descr.timestamp_channel(),
+            }),
+        }
+    }
+
+    /// Irregular stream with one string channel, e.g. for stimulus events.
+    pub fn markers<S: Into<String>>(name: S) -> Self {
+        let name = name.into();
+        Self {
+            source_id: name.clone(),
+            name,
+            stream_type: "Markers".to_string(),
+            channel_count: 1,
+            nominal_srate: 0.0,
+            channel_format: ChannelFormat::String,
+            channels: Vec::new(),
+            board: None,
+        }
+    }
+
+    fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\"?><info>");
+        xml.push_str(&element("name", &self.name));
+        xml.push_str(&element("type", &self.stream_type));
+        xml.push_str(&element("channel_count", &self.channel_count.to_string()));
+        xml.push_str(&element("nominal_srate", &self.nominal_srate.to_string()));
+        xml.push_str(&element("channel_format", self.channel_format.name()));
+        xml.push_str(&element("source_id", &self.source_id));
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+        xml.push_str(&element("created_at", &created_at.to_string()));
+        xml.push_str("<desc>");
+        if !self.channels.is_empty() {
+            xml.push_str("<channels>");
+            for channel in &self.channels {
+                xml.push_str("<channel>");
+                xml.push_str(&element("label", &channel.label));
+                xml.push_str(&element("unit", &channel.unit));
+                xml.push_str(&element("type", &channel.channel_type));
+                xml.push_str("</channel>");
+            }
+            xml.push_str("</channels>");
+        }
+        if let Some(board) = &self.board {
+            xml.push_str("<acquisition><manufacturer>BrainFlow</manufacturer></acquisition><brainflow>");
+            xml.push_str(&element("board_id", board.board_id.name()));
+            xml.push_str(&element("preset", board.preset.name()));
+            xml.push_str(&element("num_rows", &board.num_rows.to_string()));
+            if let Some(channel) = board.timestamp_channel {
+                xml.push_str(&element("timestamp_channel", &channel.to_string()));
+            }
+            xml.push_str("</brainflow>");
+        }
+        xml.push_str("</desc></info>");
+        xml
+    }
+
+    fn from_xml(xml: &str) -> Result<Self> {
+        let info = XmlElement::parse(xml)?;
+        let number = |name: &str| -> Result<f64> {
+            let text = info.child_text(name);
+            text.parse().map_err(|_| Error::InvalidXdf(format!("invalid {} `{}`", name, text)))
+        };
+        let desc = info.child("desc");
+        let channels = desc
+            .and_then(|d| d.child("channels"))
+            .map(|c| {
+                c.children("channel")
+                    .map(|channel| XdfChannel {
+                        label: channel.child_text("label").to_string(),
+                        unit: channel.child_text("unit").to_string(),
+                        channel_type: channel.child_text("type").to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let board = match desc.and_then(|d| d.child("brainflow")) {
+            Some(brainflow) => Some(XdfBoard {
+                board_id: brainflow.child_text("board_id").parse()?,
+                preset: brainflow.child_text("preset").parse()?,
+                num_rows: brainflow
+                    .child_text("num_rows")
+                    .parse()
+                    .map_err(|_| Error::InvalidXdf("invalid num_rows".to_string()))?,
+                timestamp_channel: brainflow.child_text("timestamp_channel").parse().ok(),
+            }),
+            None => None,
+        };
+        Ok(Self {
+            name: info.child_text("name").to_string(),
+            stream_type: info.child_text("type").to_string(),
+            channel_count: info
+                .child_text("channel_count")
+                .parse()
+                .map_err(|_| Error::InvalidXdf(format!("invalid channel_count `{}`", info.child_text("channel_count"))))?,
+            nominal_srate: number("nominal_srate")?,
+            channel_format: ChannelFormat::from_name(info.child_text("channel_format"))?,
+            source_id: info.child_text("source_id").to_string(),
+            channels,
+            board,
+        })
+    }
+}
+
+/// State of a stream being written.
+struct StreamState {
+    info: XdfStreamInfo,
+    first_timestamp: Option<f64>,
+    last_timestamp: f64,
+    sample_count: usize,
+    /// Offset written with the next clock offset chunk.
+    clock_offset: f64,
+    /// Written clock offsets, as collection time and offset.
+    clock_offsets: Vec<(f64, f64)>,
+}
+
+/// Writes streams to an XDF file.
+///
+/// Every stream gets a clock offset chunk with its first samples and every 5 seconds of stream time.
+/// BrainFlow timestamps are taken from the clock of the host, so the offset is 0 unless set with
+/// [XdfWriter::set_clock_offset].
+pub struct XdfWriter<W: Write> {
+    writer: W,
+    streams: Vec<StreamState>,
+}
+
+impl XdfWriter<BufWriter<File>> {
+    /// Create the file at `path` and write the file header.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> XdfWriter<W> {
+    /// Write the file header to `writer`.
+    pub fn new(mut writer: W) -> Result<Self> {
+        writer.write_all(MAGIC)?;
+        let mut xdf = Self {
+            writer,
+            streams: Vec::new(),
+        };
+        xdf.write_chunk(FILE_HEADER_TAG, b"<?xml version=\"1.0\"?><info><version>1.0</version></info>")?;
+        Ok(xdf)
+    }
+
+    /// Write the header of a new stream and return its id.
+    pub fn add_stream(&mut self, info: XdfStreamInfo) -> Result<u32> {
+        if !info.channels.is_empty() && info.channels.len() != info.channel_count {
+            return Err(Error::InvalidXdf(format!(
+                "{} has {} channel descriptions for {} channels",
+                info.name,
+                info.channels.len(),
+                info.channel_count
+            )));
+        }
+        let stream_id = self.streams.len() as u32 + 1;
+        let mut content = stream_id.to_le_bytes().to_vec();
+        content.extend_from_slice(info.to_xml().as_bytes());
+        self.write_chunk(STREAM_HEADER_TAG, &content)?;
+        self.streams.push(StreamState {
+            info,
+            first_timestamp: None,
+            last_timestamp: 0.0,
+            sample_count: 0,
+            clock_offset: 0.0,
+            clock_offsets: Vec::new(),
+        });
+        Ok(stream_id)
+    }
+
+    /// Add a stream for board data of `preset`, see [XdfStreamInfo::for_board].
+    pub fn add_board_stream(
+        &mut self,
+        board_id: BoardIds,
+        preset: BrainFlowPresets,
+        descr: &BoardDescription,
+    ) -> Result<u32> {
+        self.add_stream(XdfStreamInfo::for_board(board_id, preset, descr))
+    }
+
+    /// Offset of the stream clock to the recording clock in seconds, written with the next clock offset chunk.
+    pub fn set_clock_offset(&mut self, stream_id: u32, clock_offset: f64) -> Result<()> {
+        self.stream_mut(stream_id)?.clock_offset = clock_offset;
+        Ok(())
+    }
+
+    /// Append board data, as returned by [crate::board_shim::BoardShim::get_board_data], to a board stream.
+    pub fn write_board_data(&mut self, stream_id: u32, data: &Array2<f64>) -> Result<()> {
+        let board = self.stream_mut(stream_id)?.info.board.ok_or_else(|| {
+            Error::InvalidXdf(format!("stream {} is not a board stream", stream_id))
+        })?;
+        if data.nrows() != board.num_rows {
+            return Err(Error::InvalidXdf(format!(
+                "board data has {} rows instead of {}",
+                data.nrows(),
+                board.num_rows
+            )));
+        }
+        let rows = (0..board.num_rows).filter(|row| Some(*row) != board.timestamp_channel).collect::<Vec<_>>();
+        let timestamps = match board.timestamp_channel {
+            Some(channel) => data.row(channel).to_vec(),
+            None => Vec::new(),
+        };
+        self.write_samples(stream_id, &timestamps, &data.select(Axis(0), &rows))
+    }
+
+    /// Append samples to a numeric stream, `values` has one row per channel and one column per sample.
+    ///
+    /// Without timestamps, the samples are stamped by the reader from the nominal sampling rate.
+    pub fn write_samples(&mut self, stream_id: u32, timestamps: &[f64], values: &Array2<f64>) -> Result<()> {
+        let info = &self.stream_mut(stream_id)?.info;
+        let format = info.channel_format;
+        if format == ChannelFormat::String {
+            return Err(Error::InvalidXdf(format!("{} is a string stream", info.name)));
+        }
+        if values.nrows() != info.channel_count || !(timestamps.is_empty() || timestamps.len() == values.ncols()) {
+            return Err(Error::InvalidXdf(format!(
+                "{} values and {} timestamps do not match {} channels",
+                values.ncols(),
+                timestamps.len(),
+                info.channel_count
+            )));
+        }
+        if values.ncols() == 0 {
+            return Ok(());
+        }
+        let mut content = samples_header(stream_id, values.ncols());
+        for (i, column) in values.columns().into_iter().enumerate() {
+            push_timestamp(&mut content, timestamps.get(i));
+            for value in column {
+                match format {
+                    ChannelFormat::Float32 => content.extend_from_slice(&(*value as f32).to_le_bytes()),
+                    ChannelFormat::Double64 => content.extend_from_slice(&value.to_le_bytes()),
+                    ChannelFormat::Int8 => content.extend_from_slice(&(*value as i8).to_le_bytes()),
+                    ChannelFormat::Int16 => content.extend_from_slice(&(*value as i16).to_le_bytes()),
+                    ChannelFormat::Int32 => content.extend_from_slice(&(*value as i32).to_le_bytes()),
+                    ChannelFormat::Int64 => content.extend_from_slice(&(*value as i64).to_le_bytes()),
+                    ChannelFormat::String => unreachable!(),
+                }
+            }
+        }
+        self.write_chunk(SAMPLES_TAG, &content)?;
+        self.samples_written(stream_id, timestamps, values.ncols())
+    }
+
+    /// Append samples to a string stream, one vector with a value per channel for each sample.
+    pub fn write_strings(&mut self, stream_id: u32, timestamps: &[f64], samples: &[Vec<String>]) -> Result<()> {
+        let info = &self.stream_mut(stream_id)?.info;
+        if info.channel_format != ChannelFormat::String {
+            return Err(Error::InvalidXdf(format!("{} is not a string stream", info.name)));
+        }
+        if samples.iter().any(|s| s.len() != info.channel_count) || timestamps.len() != samples.len() {
+            return Err(Error::InvalidXdf(format!("samples do not match the channels of {}", info.name)));
+        }
+        if samples.is_empty() {
+            return Ok(());
+        }
+        let mut content = samples_header(stream_id, samples.len());
+        for (timestamp, sample) in timestamps.iter().zip(samples) {
+            push_timestamp(&mut content, Some(timestamp));
+            for value in sample {
+                push_varlen(&mut content, value.len() as u64);
+                content.extend_from_slice(value.as_bytes());
+            }
+        }
+        self.write_chunk(SAMPLES_TAG, &content)?;
+        self.samples_written(stream_id, timestamps, samples.len())
+    }
+
+    /// Append one marker to a stream created with [XdfStreamInfo::markers].
+    pub fn write_marker(&mut self, stream_id: u32, timestamp: f64, marker: &str) -> Result<()> {
+        self.write_strings(stream_id, &[timestamp], &[vec![marker.to_string()]])
+    }
+
+    /// Write the stream footers and return the writer.
+    pub fn finish(mut self) -> Result<W> {
+        for i in 0..self.streams.len() {
+            let stream = &self.streams[i];
+            let mut xml = String::from("<?xml version=\"1.0\"?><info>");
+            xml.push_str(&element("first_timestamp", &stream.first_timestamp.unwrap_or(0.0).to_string()));
+            xml.push_str(&element("last_timestamp", &stream.last_timestamp.to_string()));
+            xml.push_str(&element("sample_count", &stream.sample_count.to_string()));
+            xml.push_str("<clock_offsets>");
+            for (time, value) in &stream.clock_offsets {
+                xml.push_str(&format!("<offset><time>{}</time><value>{}</value></offset>", time, value));
+            }
+            xml.push_str("</clock_offsets></info>");
+            let mut content = (i as u32 + 1).to_le_bytes().to_vec();
+            content.extend_from_slice(xml.as_bytes());
+            self.write_chunk(STREAM_FOOTER_TAG, &content)?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn stream_mut(&mut self, stream_id: u32) -> Result<&mut StreamState> {
+        self.streams
+            .get_mut((stream_id as usize).wrapping_sub(1))
+            .ok_or_else(|| Error::InvalidXdf(format!("unknown stream {}", stream_id)))
+    }
+
+    /// Update the footer values and write a clock offset chunk if one is due.
+    fn samples_written(&mut self, stream_id: u32, timestamps: &[f64], num_samples: usize) -> Result<()> {
+        let stream = self.stream_mut(stream_id)?;
+        stream.sample_count += num_samples;
+        let (first, last) = match (timestamps.first(), timestamps.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return Ok(()),
+        };
+        stream.first_timestamp.get_or_insert(first);
+        stream.last_timestamp = last;
+        let due = match stream.clock_offsets.last() {
+            Some((time, _)) => last - time >= CLOCK_OFFSET_INTERVAL,
+            None => true,
+        };
+        if due {
+            let clock_offset = stream.clock_offset;
+            stream.clock_offsets.push((last, clock_offset));
+            let mut content = stream_id.to_le_bytes().to_vec();
+            content.extend_from_slice(&last.to_le_bytes());
+            content.extend_from_slice(&clock_offset.to_le_bytes());
+            self.write_chunk(CLOCK_OFFSET_TAG, &content)?;
+        }
+        Ok(())
+    }
+
+    fn write_chunk(&mut self, tag: u16, content: &[u8]) -> Result<()> {
+        let mut header = Vec::new();
+        push_varlen(&mut header, content.len() as u64 + 2);
+        header.extend_from_slice(&tag.to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(content)?;
+        Ok(())
+    }
+}
+
+/// A stream read from an XDF file.
+#[derive(Debug, Clone, Getters)]
+#[getset(get = "pub")]
+pub struct XdfStream {
+    stream_id: u32,
+    info: XdfStreamInfo,
+    /// Timestamp of every sample, on the clock of the stream.
+    timestamps: Vec<f64>,
+    /// One row per channel and one column per sample, empty for string streams.
+    data: Array2<f64>,
+    /// Values of string streams, one vector per sample.
+    strings: Vec<Vec<String>>,
+    /// Clock offsets as collection time and offset.
+    clock_offsets: Vec<(f64, f64)>,
+}
+
+impl XdfStream {
+    /// Timestamps on the clock of the recording, with the clock offsets interpolated linearly.
+    pub fn synchronized_timestamps(&self) -> Vec<f64> {
+        let offsets = &self.clock_offsets;
+        self.timestamps
+            .iter()
+            .map(|t| {
+                let offset = match offsets.iter().position(|(time, _)| time > t) {
+                    _ if offsets.is_empty() => 0.0,
+                    Some(0) => offsets[0].1,
+                    None => offsets[offsets.len() - 1].1,
+                    Some(i) => {
+                        let (t0, o0) = offsets[i - 1];
+                        let (t1, o1) = offsets[i];
+                        o0 + (o1 - o0) * (t - t0) / (t1 - t0)
+                    }
+                };
+                t + offset
+            })
+            .collect()
+    }
+
+    /// Rebuild the board data of a stream written with [XdfWriter::write_board_data].
+    pub fn to_board_data(&self) -> Result<Array2<f64>> {
+        let board = self
+            .info
+            .board
+            .ok_or_else(|| Error::InvalidXdf(format!("{} is not a board stream", self.info.name)))?;
+        let mut board_data = Array2::zeros((board.num_rows, self.data.ncols()));
+        let rows = (0..board.num_rows).filter(|row| Some(*row) != board.timestamp_channel);
+        for (channel, row) in rows.enumerate() {
+            if channel >= self.data.nrows() {
+                return Err(Error::InvalidXdf(format!("{} has too few channels", self.info.name)));
+            }
+            board_data.row_mut(row).assign(&self.data.row(channel));
+        }
+        if let Some(channel) = board.timestamp_channel {
+            board_data.row_mut(channel).assign(&ndarray::ArrayView1::from(&self.timestamps));
+        }
+        Ok(board_data)
+    }
+}
+
+/// Read all streams of an XDF file.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Vec<XdfStream>> {
+    parse(&fs::read(path)?)
+}
+
+/// Parse the content of an XDF file.
+pub fn parse(bytes: &[u8]) -> Result<Vec<XdfStream>> {
+    let body = bytes.strip_prefix(MAGIC).ok_or_else(|| Error::InvalidXdf("missing XDF: magic".to_string()))?;
+    let mut reader = ByteReader { bytes: body };
+    let mut streams: Vec<(XdfStream, Vec<f64>)> = Vec::new();
+    while !reader.bytes.is_empty() {
+        let length = reader.varlen()? as usize;
+        if length < 2 {
+            return Err(Error::InvalidXdf("chunk without tag".to_string()));
+        }
+        let tag = u16::from_le_bytes(reader.array()?);
+        let mut chunk = ByteReader {
+            bytes: reader.take(length - 2)?,
+        };
+        match tag {
+            STREAM_HEADER_TAG => {
+                let stream_id = chunk.u32()?;
+                let info = XdfStreamInfo::from_xml(&String::from_utf8_lossy(chunk.bytes))?;
+                // every channel of a sample takes at least one byte of the file
+                if info.channel_count > bytes.len() {
+                    return Err(Error::InvalidXdf(format!(
+                        "{} has {} channels, more than the file can hold",
+                        info.name, info.channel_count
+                    )));
+                }
+                let data = Array2::zeros((info.channel_count, 0));
+                let stream = XdfStream {
+                    stream_id,
+                    info,
+                    timestamps: Vec::new(),
+                    data,
+                    strings: Vec::new(),
+                    clock_offsets: Vec::new(),
+                };
+                streams.push((stream, Vec::new()));
+            }
+            SAMPLES_TAG => {
+                let stream_id = chunk.u32()?;
+                let (stream, values) = find_stream(&mut streams, stream_id)?;
+                let num_samples = chunk.varlen()?;
+                // a sample takes at least one byte for its timestamp and one per channel
+                let sample_size = stream.info.channel_count as u64 + 1;
+                if num_samples.saturating_mul(sample_size) > chunk.bytes.len() as u64 {
+                    return Err(Error::InvalidXdf(format!(
+                        "samples chunk of {} is too short for {} samples",
+                        stream.info.name, num_samples
+                    )));
+                }
+                for _ in 0..num_samples {
+                    let timestamp = match chunk.take(1)?[0] {
+                        0 => {
+                            let srate = stream.info.nominal_srate;
+                            let previous = stream.timestamps.last().copied().unwrap_or(0.0);
+                            if srate > 0.0 && !stream.timestamps.is_empty() {
+                                previous + 1.0 / srate
+                            } else {
+                                previous
+                            }
+                        }
+                        8 => chunk.f64()?,
+                        n => return Err(Error::InvalidXdf(format!("timestamp with {} bytes", n))),
+                    };
+                    stream.timestamps.push(timestamp);
+                    let format = stream.info.channel_format;
+                    if format == ChannelFormat::String {
+                        let sample = (0..stream.info.channel_count)
+                            .map(|_| {
+                                let length = chunk.varlen()? as usize;
+                                Ok(String::from_utf8_lossy(chunk.take(length)?).into_owned())
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        stream.strings.push(sample);
+                        continue;
+                    }
+                    for _ in 0..stream.info.channel_count {
+                        let value = match format {
+                            ChannelFormat::Float32 => f32::from_le_bytes(chunk.array()?) as f64,
+                            ChannelFormat::Double64 => chunk.f64()?,
+                            ChannelFormat::Int8 => i8::from_le_bytes(chunk.array()?) as f64,
+                            ChannelFormat::Int16 => i16::from_le_bytes(chunk.array()?) as f64,
+                            ChannelFormat::Int32 => i32::from_le_bytes(chunk.array()?) as f64,
+                            ChannelFormat::Int64 => i64::from_le_bytes(chunk.array()?) as f64,
+                            ChannelFormat::String => unreachable!(),
+                        };
+                        values.push(value);
+                    }
+                }
+            }
+            CLOCK_OFFSET_TAG => {
+                let stream_id = chunk.u32()?;
+                let (stream, _) = find_stream(&mut streams, stream_id)?;
+                let collection_time = chunk.f64()?;
+                let offset = chunk.f64()?;
+                stream.clock_offsets.push((collection_time, offset));
+            }
+            // the footer repeats what is known from the other chunks
+            FILE_HEADER_TAG | BOUNDARY_TAG | STREAM_FOOTER_TAG => {}
+            // unknown chunks are skipped, as the format requires
+            _ => {}
+        }
+    }
+    streams
+        .into_iter()
+        .map(|(mut stream, values)| {
+            let num_samples = if stream.info.channel_format == ChannelFormat::String {
+                0
+            } else {
+                stream.timestamps.len()
+            };
+            stream.data = Array2::from_shape_vec((num_samples, stream.info.channel_count), values)?.reversed_axes();
+            stream.data = stream.data.as_standard_layout().to_owned();
+            Ok(stream)
+        })
+        .collect()
+}
+
+fn find_stream(streams: &mut [(XdfStream, Vec<f64>)], stream_id: u32) -> Result<&mut (XdfStream, Vec<f64>)> {
+    streams
+        .iter_mut()
+        .find(|(s, _)| s.stream_id == stream_id)
+        .ok_or_else(|| Error::InvalidXdf(format!("samples of stream {} before its header", stream_id)))
+}
+
+/// Content of a samples chunk up to the samples.
+fn samples_header(stream_id: u32, num_samples: usize) -> Vec<u8> {
+    let mut content = stream_id.to_le_bytes().to_vec();
+    push_varlen(&mut content, num_samples as u64);
+    content
+}
+
+fn push_timestamp(content: &mut Vec<u8>, timestamp: Option<&f64>) {
+    match timestamp {
+        Some(timestamp) => {
+            content.push(8);
+            content.extend_from_slice(&timestamp.to_le_bytes());
+        }
+        None => content.push(0),
+    }
+}
+
+/// Write a number with a leading byte giving its size.
+fn push_varlen(content: &mut Vec<u8>, value: u64) {
+    if value <= u8::MAX as u64 {
+        content.extend_from_slice(&[1, value as u8]);
+    } else if value <= u32::MAX as u64 {
+        content.push(4);
+        content.extend_from_slice(&(value as u32).to_le_bytes());
+    } else {
+        content.push(8);
+        content.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Reads little endian values from a byte slice.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < n {
+            return Err(Error::InvalidXdf("unexpected end of file".to_string()));
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    fn varlen(&mut self) -> Result<u64> {
+        match self.take(1)?[0] {
+            1 => Ok(self.take(1)?[0] as u64),
+            4 => Ok(self.u32()? as u64),
+            8 => Ok(u64::from_le_bytes(self.array()?)),
+            n => Err(Error::InvalidXdf(format!("length with {} bytes", n))),
+        }
+    }
+}
+
+/// `<name>text</name>` with the text escaped.
+fn element(name: &str, text: &str) -> String {
+    let text = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;");
+    format!("<{0}>{1}</{0}>", name, text)
+}
+
+/// Element of the XML headers, attributes are ignored.
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    text: String,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// Parse the root element of a document.
+    fn parse(xml: &str) -> Result<Self> {
+        let invalid = || Error::InvalidXdf("invalid XML header".to_string());
+        // the document is the bottom of the stack
+        let mut stack = vec![XmlElement::default()];
+        let mut rest = xml;
+        while !rest.is_empty() {
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = comment.find("-->").ok_or_else(invalid)?;
+                rest = &comment[end + 3..];
+            } else if let Some(tag) = rest.strip_prefix('<') {
+                let end = tag.find('>').ok_or_else(invalid)?;
+                rest = &tag[end + 1..];
+                let tag = &tag[..end];
+                if tag.starts_with('?') || tag.starts_with('!') {
+                    continue;
+                }
+                if let Some(name) = tag.strip_prefix('/') {
+                    let element = stack.pop().ok_or_else(invalid)?;
+                    if element.name != name.trim() {
+                        return Err(invalid());
+                    }
+                    stack.last_mut().ok_or_else(invalid)?.children.push(element);
+                } else {
+                    let name = tag.trim_end_matches('/').split_whitespace().next().ok_or_else(invalid)?;
+                    let element = XmlElement {
+                        name: name.to_string(),
+                        ..Default::default()
+                    };
+                    if tag.ends_with('/') {
+                        stack.last_mut().ok_or_else(invalid)?.children.push(element);
+                    } else {
+                        stack.push(element);
+                    }
+                }
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                stack.last_mut().ok_or_else(invalid)?.text.push_str(&unescape(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+        match (stack.pop(), stack.is_empty()) {
+            (Some(document), true) => document.children.into_iter().next().ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Trimmed text of the first child `name`, empty if there is none.
+    fn child_text(&self, name: &str) -> &str {
+        self.child(name).map(|c| c.text.trim()).unwrap_or("")
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+    use std::{env, fs, io::Cursor};
+
+    use super::{parse, ChannelFormat, XdfStreamInfo, XdfWriter, XmlElement};
+    use crate::{board_description::BoardDescription, BoardIds};
+
+    /// Board data of `descr` with increasing timestamps.
+    fn board_data(descr: &BoardDescription, start: usize, num_samples: usize) -> Array2<f64> {
+        let mut data = Array2::from_shape_fn((*descr.num_rows(), num_samples), |(row, col)| {
+            (row * 1000 + start + col) as f64 + 0.25
+        });
+        if let Some(channel) = *descr.timestamp_channel() {
+            for (i, timestamp) in data.row_mut(channel).iter_mut().enumerate() {
+                *timestamp = 1700000000.0 + (start + i) as f64 / *descr.sampling_rate() as f64;
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_board_streams_round_trip() {
+        let board_id = BoardIds::SyntheticBoard;
+        let descriptions = BoardDescription::load_all(board_id).unwrap();
+        assert!(descriptions.len() > 1);
+
+        let mut writer = XdfWriter::new(Cursor::new(Vec::new())).unwrap();
+        let mut written = Vec::new();
+        for (preset, descr) in &descriptions {
+            let stream_id = writer.add_board_stream(board_id, *preset, descr).unwrap();
+            written.push((stream_id, descr.clone(), Vec::new()));
+        }
+        let markers = writer.add_stream(XdfStreamInfo::markers("Stimulus")).unwrap();
+        // two chunks per stream, interleaved like a live session
+        for start in [0, 2000].iter() {
+            for (stream_id, descr, chunks) in &mut written {
+                let data = board_data(descr, *start, 2000);
+                writer.write_board_data(*stream_id, &data).unwrap();
+                chunks.push(data);
+            }
+            writer.write_marker(markers, 1700000000.5 + *start as f64, "go & stop").unwrap();
+        }
+        writer.set_clock_offset(written[0].0, 0.5).unwrap();
+        assert!(writer.write_board_data(markers, &Array2::zeros((1, 1))).is_err());
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let streams = parse(&bytes).unwrap();
+        assert_eq!(descriptions.len() + 1, streams.len());
+        for ((stream_id, descr, chunks), stream) in written.iter().zip(&streams) {
+            assert_eq!(*stream_id, *stream.stream_id());
+            assert_eq!(*descr.sampling_rate() as f64, stream.info().nominal_srate);
+            assert_eq!(descr.num_rows() - 1, stream.info().channel_count);
+            let expected = ndarray::concatenate(ndarray::Axis(1), &[chunks[0].view(), chunks[1].view()]).unwrap();
+            assert_eq!(expected, stream.to_board_data().unwrap());
+            // 2000 samples are more than 5 seconds
+            assert!(stream.clock_offsets().len() >= 2);
+        }
+        let default = &streams[0];
+        // rows before the timestamp row keep their index
+        let eeg = &default.info().channels[descriptions[0].1.eeg_channels()[0]];
+        assert_eq!("Package", default.info().channels[0].label);
+        assert_eq!(descriptions[0].1.eeg_names()[0], eeg.label);
+        assert_eq!("uV", eeg.unit);
+        assert_eq!("EEG", default.info().stream_type);
+
+        let markers = streams.last().unwrap();
+        assert_eq!(ChannelFormat::String, markers.info().channel_format);
+        assert_eq!(vec![vec!["go & stop".to_string()]; 2], *markers.strings());
+        assert_eq!(&[1700000000.5, 1700002000.5], markers.timestamps().as_slice());
+        assert_eq!(markers.timestamps(), &markers.synchronized_timestamps());
+    }
+
+    #[test]
+    fn test_synchronized_timestamps() {
+        let info = XdfStreamInfo {
+            channel_format: ChannelFormat::Float32,
+            nominal_srate: 1.0,
+            ..XdfStreamInfo::markers("Offsets")
+        };
+        let mut writer = XdfWriter::new(Cursor::new(Vec::new())).unwrap();
+        let stream_id = writer.add_stream(info).unwrap();
+        writer.write_samples(stream_id, &[0.0], &Array2::from_elem((1, 1), 1.5)).unwrap();
+        writer.set_clock_offset(stream_id, 1.0).unwrap();
+        // stamped by the reader from the sampling rate
+        writer.write_samples(stream_id, &[], &Array2::from_elem((1, 9), 2.5)).unwrap();
+        writer.write_samples(stream_id, &[10.0], &Array2::from_elem((1, 1), 3.5)).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let stream = parse(&bytes).unwrap().remove(0);
+        assert_eq!(&[(0.0, 0.0), (10.0, 1.0)], stream.clock_offsets().as_slice());
+        assert_eq!(5.0, stream.timestamps()[5]);
+        assert_eq!(&[0.0, 5.5, 11.0], &[0, 5, 10].map(|i| stream.synchronized_timestamps()[i]));
+        assert_eq!(2.5, stream.data()[[0, 3]]);
+    }
+
+    #[test]
+    fn test_xml_and_files() {
+        let xml = "<?xml version=\"1.0\"?><!-- a > comment --><info><name>EEG &amp; more</name><desc><x/></desc></info>";
+        let info = XmlElement::parse(xml).unwrap();
+        assert_eq!("EEG & more", info.child_text("name"));
+        assert!(info.child("desc").unwrap().child("x").is_some());
+        assert!(XmlElement::parse("<info><name></info>").is_err());
+
+        let dir = env::temp_dir().join("brainflow_tests/rust");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("xdf_markers.xdf");
+        let mut writer = XdfWriter::create(&path).unwrap();
+        let markers = writer.add_stream(XdfStreamInfo::markers("Markers")).unwrap();
+        writer.write_marker(markers, 1.0, "start").unwrap();
+        writer.finish().unwrap();
+        let streams = super::read_file(&path).unwrap();
+        assert_eq!("Markers", streams[0].info().name);
+        assert!(parse(b"XDF").is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_channel_count() {
+        let header = |channel_count: usize| {
+            let info = XdfStreamInfo {
+                channel_format: ChannelFormat::Float32,
+                channel_count,
+                ..XdfStreamInfo::markers("Channels")
+            };
+            let mut writer = XdfWriter::new(Cursor::new(Vec::new())).unwrap();
+            writer.add_stream(info).unwrap();
+            writer.finish().unwrap().into_inner()
+        };
+        assert!(parse(&header(usize::MAX)).is_err());
+        // same length, so the chunk sizes stay valid
+        let mut bytes = header(1000);
+        let count = bytes.windows(4).position(|w| w == b"1000").unwrap();
+        bytes[count..count + 4].copy_from_slice(b"1e30");
+        assert!(parse(&bytes).is_err());
+
+        let mut writer = XdfWriter::new(Cursor::new(Vec::new())).unwrap();
+        let stream_id = writer.add_stream(XdfStreamInfo::markers("Samples")).unwrap();
+        writer.write_marker(stream_id, 1.0, "a").unwrap();
+        let mut bytes = writer.finish().unwrap().into_inner();
+        // claim 200 samples in a chunk which holds one
+        let chunk = bytes.windows(7).position(|w| w == [1, 0, 0, 0, 1, 1, 8]).unwrap();
+        bytes[chunk + 5] = 200;
+        assert!(parse(&bytes).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use brainflow::{board_shim, BoardIds, BrainFlowPresets};
+use brainflow::{board_description::BoardDescription, BoardIds, BrainFlowPresets};
 
 
 const PRESET: BrainFlowPresets = BrainFlowPresets::DefaultPreset;
@@ -31,10 +31,14 @@ impl Default for ChanInfo {
 
 impl ChanInfo {
    pub fn collect(&mut self) {
-      self.description   = board_shim::get_board_descr(BOARD, PRESET).unwrap();
-      self.eeg_indices   = board_shim::get_eeg_channels(BOARD, PRESET).unwrap();
-      self.marker_index  = board_shim::get_marker_channel(BOARD, PRESET).unwrap();
-      self.battery_index = board_shim::get_battery_channel(BOARD, PRESET).unwrap();
+      let descr = BoardDescription::load(BOARD, PRESET).unwrap();
+      self.description   = format!(
+         "{}: {} Hz, {} rows\nEEG: {}",
+         descr.name(), descr.sampling_rate(), descr.num_rows(), descr.eeg_names().join(", ")
+      );
+      self.eeg_indices   = descr.eeg_channels().clone();
+      self.marker_index  = descr.marker_channel().unwrap_or_default();
+      self.battery_index = descr.battery_channel().unwrap_or_default();
    }
 }
 
@@ -1,35 +1,35 @@
 use std::{
+    env,
     thread,
     time::Duration
 };
 
 use brainflow::{
-    board_shim::{
-        self,
-        BoardShim, // BoardShim is the main interface for all boards
-    },
-    brainflow_input_params::BrainFlowInputParamsBuilder,
+    board_shim,
+    session_config::SessionConfig,
     BoardIds,
-    BrainFlowPresets,
 };
 
 
 fn main() {
     board_shim::enable_dev_board_logger().unwrap();
     let _ = board_shim::set_log_file("brainflow.log"); // log info will go to this file
-    let params = BrainFlowInputParamsBuilder::default().build();
-    let board = BoardShim::new(BoardIds::SyntheticBoard, params).unwrap();
-    board.prepare_session().unwrap(); // print params
-    // buffer_size
-    // streamer_params
-    board.start_stream(45000, "").unwrap(); // streams data and stores it in a ring buffer
+    // board and settings come from a TOML or JSON file given as first argument
+    let config = match env::args().nth(1) {
+        Some(path) => SessionConfig::load(path).unwrap(),
+        None => SessionConfig::new(BoardIds::SyntheticBoard),
+    };
+    // validates the config, applies the log levels and adds the streamers
+    let streaming = config.start().unwrap(); // streams data and stores it in a ring buffer, released when dropped
     thread::sleep(Duration::from_secs(5)); // Puts the current thread to sleep for at least 5 sec.
 
-    board.stop_stream().unwrap();
+    let session = streaming.stop_stream().unwrap();
     // get all data and remove it from the internal buffer
-    let data = board.get_board_data(Some(10), BrainFlowPresets::DefaultPreset).unwrap();
+    for preset in config.presets() {
+        let data = session.get_board_data(Some(10), *preset).unwrap();
+        println!("{}", data.len());
+        println!("{:?}", data);
+    }
 
-    board.release_session().unwrap(); // release all resources
-    println!("{}", data.len());
-    println!("{:?}", data);
+    session.release().unwrap(); // release all resources
 }